@@ -35,6 +35,8 @@ class App(ConfigMixin, WindowMixin, ExtrasMixin, CodegenMixin, NativeMixin, Shel
         self.tray = None
         self.shortcut_manager = ShortcutManager()
         self._on_file_drop_callback = None
+        self._on_open_file_callback = None
+        self.protocols = {}
         self.plugin_statuses = []  # Track load status for inspector
 
         # Router Init
@@ -77,6 +79,10 @@ class App(ConfigMixin, WindowMixin, ExtrasMixin, CodegenMixin, NativeMixin, Shel
 
         self._setup_key_value_store()
 
+        # Start the webview runtime now, so it's warm by the time the first window opens
+        if self.config.get("prewarm", False) and self.engine == "native":
+            self.prewarm()
+
         # Register automatic cleanup for thread pool
         # Register automatic cleanup for thread pool
         @self.on_exit
@@ -167,16 +173,33 @@ class App(ConfigMixin, WindowMixin, ExtrasMixin, CodegenMixin, NativeMixin, Shel
         return func
 
     # Expose function to all windows
-    def expose(self, func=None, name=None, secure=False, run_in_thread=True):
+    def expose(
+        self,
+        func=None,
+        name=None,
+        secure=False,
+        run_in_thread=True,
+        pass_context=False,
+        stream=False,
+    ):
         """
         Expose a function to ALL windows created by this App.
         Can be used as a decorator: @app.expose or @app.expose(secure=True)
+        With pass_context=True the function also receives context={"window_id", "origin", "method"}.
+        With stream=True it receives stream=<CallStream> to emit chunks (native engine only).
         """
         # Case 1: Used as @app.expose(secure=True) - func is None
         if func is None:
 
             def decorator(f):
-                self.expose(f, name=name, secure=secure, run_in_thread=run_in_thread)
+                self.expose(
+                    f,
+                    name=name,
+                    secure=secure,
+                    run_in_thread=run_in_thread,
+                    pass_context=pass_context,
+                    stream=stream,
+                )
                 return f
 
             return decorator
@@ -211,6 +234,7 @@ class App(ConfigMixin, WindowMixin, ExtrasMixin, CodegenMixin, NativeMixin, Shel
                             "func": attr,
                             "secure": secure,
                             "run_in_thread": run_in_thread,
+                            "pass_context": pass_context,
                         }
                         self._exposed_ts_defs[attr_name] = self._get_ts_definition(
                             attr_name, attr
@@ -226,6 +250,8 @@ class App(ConfigMixin, WindowMixin, ExtrasMixin, CodegenMixin, NativeMixin, Shel
             "func": func,
             "secure": secure,
             "run_in_thread": run_in_thread,
+            "pass_context": pass_context,
+            "stream": stream,
         }
         self._exposed_ts_defs[name] = self._get_ts_definition(name, func)
         return func
@@ -267,6 +293,35 @@ class App(ConfigMixin, WindowMixin, ExtrasMixin, CodegenMixin, NativeMixin, Shel
         self._on_file_drop_callback = func
         return func
 
+    def on_open_file(self, func):
+        """
+        Decorator to register a handler for documents opened via file associations
+        ("file_associations" in settings.json), on launch or while running (macOS).
+
+        @app.on_open_file
+        def open_document(window, path):
+            print(f"Opening {path}")
+        """
+        self._on_open_file_callback = func
+        return func
+
+    def protocol(self, scheme):
+        """
+        Decorator to serve a custom URL scheme from Python, next to the app's own scheme.
+        Register before the first window is created. The handler runs on a worker thread.
+        http(s) can't be intercepted; on Windows media://x/ is reached as https://media.x/.
+
+        @app.protocol("media")
+        def serve_media(method, url, headers, body):
+            return 200, {"Content-Type": "video/mp4"}, data
+        """
+
+        def decorator(func):
+            self.protocols[scheme] = func
+            return func
+
+        return decorator
+
     def _register_core_apis(self):
         """Automatically exposes built-in system APIs to the frontend."""
         # Shell APIs
@@ -428,7 +483,7 @@ class App(ConfigMixin, WindowMixin, ExtrasMixin, CodegenMixin, NativeMixin, Shel
                         "name": plugin.name,
                         "version": plugin.version,
                         "ui_entry": (
-                            f"pytron://app/plugins/{item}/{plugin.ui_entry}"
+                            f"{self.config.get('scheme', 'pytron')}://app/plugins/{item}/{plugin.ui_entry}"
                             if plugin.ui_entry
                             else None
                         ),
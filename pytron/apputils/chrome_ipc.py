@@ -4,18 +4,19 @@ import json
 import logging
 import threading
 import subprocess
-import struct
 import tempfile
 import platform
 import uuid
 
+from ..engines.chrome.framing import TYPE_CONTROL, encode_frame, read_frame
+
 logger = logging.getLogger("Pytron.ChromeIPC")
 
 
 class ChromeIPCServer:
     """
     A robust Named Pipe server for Windows (or UDS for Unix).
-    Uses length-prefixed binary framing (Mojo-style) for maximum reliability.
+    Uses magic/version/type/length framing (Mojo-style) for maximum reliability.
     """
 
     def __init__(self, pipe_name):
@@ -75,23 +76,19 @@ class ChromeIPCServer:
             self.connected = True
 
     def read_loop(self, callback):
-        """Reads length-prefixed messages."""
+        """Reads framed messages (see engines/chrome/framing.py)."""
 
         while self.connected:
             try:
-                # 1. Read 4-byte Header (Length)
-                header = self._raw_read(4)
-                if not header or len(header) < 4:
-                    break
-
-                msg_len = struct.unpack("<I", header)[0]
-
-                # 2. Read Body
-                body = self._raw_read(msg_len)
-                if not body:
+                # 1. Read one frame (header + body)
+                frame = read_frame(self._read_exact)
+                if frame is None:
                     break
+                kind, body = frame
 
-                # 3. Dispatch
+                # 2. Dispatch (control frames are reserved, nothing to do yet)
+                if kind == TYPE_CONTROL:
+                    continue
                 msg = json.loads(body.decode("utf-8"))
                 callback(msg)
 
@@ -101,6 +98,15 @@ class ChromeIPCServer:
 
         self.connected = False
 
+    def _read_exact(self, n):
+        data = b""
+        while len(data) < n:
+            chunk = self._raw_read(n - len(data))
+            if not chunk:
+                return None
+            data += chunk
+        return data
+
     def _raw_read(self, n):
         from ctypes import windll, byref, c_ulong, create_string_buffer
 
@@ -117,11 +123,9 @@ class ChromeIPCServer:
             return self.conn.recv(n)
 
     def send(self, data_dict):
-        """Sends a length-prefixed JSON message."""
+        """Sends a framed JSON message."""
         with self._lock:
-            body = json.dumps(data_dict).encode("utf-8")
-            header = struct.pack("<I", len(body))
-            full_msg = header + body
+            full_msg = encode_frame(json.dumps(data_dict))
 
             if platform.system() == "Windows":
                 from ctypes import windll, byref, c_ulong
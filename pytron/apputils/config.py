@@ -82,6 +82,14 @@ class ConfigMixin:
                 f"Settings file not found at {path}. Using default configuration."
             )
 
+        # Frozen builds get this from the loader; dev runs read the same settings.json fields
+        if not hasattr(sys, "pytron_app_info"):
+            sys.pytron_app_info = {
+                "name": self.config.get("title"),
+                "version": self.config.get("version"),
+                "author": self.config.get("author"),
+            }
+
     def _setup_identity(self):
         title = self.config.get("title", "Pytron App")
         safe_title = "".join(
@@ -102,6 +110,8 @@ class ConfigMixin:
                 "".join([c for c in (title or "Pytron") if c.isalnum()]) or "PytronApp"
             )
         app_id = f"{author}.{safe_title}.App"
+        # Dev runs apply it through the native engine (see Webview), grouping like packaged builds
+        self.app_id = app_id
 
         if sys.platform == "win32" and getattr(sys, "frozen", False):
             try:
@@ -131,6 +141,7 @@ class ConfigMixin:
         import socket
         import hashlib
         import threading
+        import time
         import os
 
         # Skip during tests and development as they often require flexibility
@@ -142,8 +153,20 @@ class ConfigMixin:
         port = 10000 + (int(hashlib.sha256(app_id.encode()).hexdigest(), 16) % 50000)
         self._instance_socket = socket.socket(socket.AF_INET, socket.SOCK_STREAM)
 
+        # Set by restart(): the previous instance is still shutting down, so wait for
+        # it to release the port instead of forwarding to it and exiting
+        restarted_from = os.environ.pop("PYTRON_RESTARTED_FROM", None)
+        deadline = time.monotonic() + (10.0 if restarted_from else 0.0)
+
         try:
-            self._instance_socket.bind(("127.0.0.1", port))
+            while True:
+                try:
+                    self._instance_socket.bind(("127.0.0.1", port))
+                    break
+                except socket.error:
+                    if time.monotonic() >= deadline:
+                        raise
+                    time.sleep(0.2)
             self._instance_socket.listen(1)
 
             def _listen_for_other_instances():
@@ -35,14 +35,22 @@ class WindowMixin:
             func = data["func"]
             secure = data["secure"]
             run_in_thread = data.get("run_in_thread", True)
+            # Only forwarded when set, so windows without context support still bind
+            extra = {"pass_context": True} if data.get("pass_context") else {}
+            if data.get("stream"):
+                extra["stream"] = True
             if isinstance(func, type):
                 try:
                     window.expose(func)
                 except Exception as e:
                     self.logger.debug(f"Failed to expose class {name}: {e}")
-                    window.bind(name, func, secure=secure, run_in_thread=run_in_thread)
+                    window.bind(
+                        name, func, secure=secure, run_in_thread=run_in_thread, **extra
+                    )
             else:
-                window.bind(name, func, secure=secure, run_in_thread=run_in_thread)
+                window.bind(
+                    name, func, secure=secure, run_in_thread=run_in_thread, **extra
+                )
 
         if target_url:
             window.navigate(target_url)
@@ -108,13 +116,22 @@ class WindowMixin:
 
         return window
 
+    def _dispatch_open_file(self, window, path):
+        self.logger.info(f"Opening file: {path}")
+        callback = getattr(self, "_on_open_file_callback", None)
+        if callback:
+            self.thread_pool.submit(callback, window, path)
+        window.emit("pytron:open-file", {"path": path})
+
     def run(self, **kwargs):
         self.is_running = True
         if "storage_path" not in kwargs:
             kwargs["storage_path"] = self.storage_path
 
-        if sys.platform == "win32" and "storage_path" in kwargs:
-            os.environ["WEBVIEW2_USER_DATA_FOLDER"] = kwargs["storage_path"]
+        self._set_webview2_data_folder(kwargs.get("storage_path"))
+
+        if sys.platform == "win32" and self.config.get("kill_orphaned_webviews", True):
+            self._kill_orphaned_webviews(self.config.get("data_directory") or kwargs.get("storage_path"))
 
         if not self.windows:
             self.create_window()
@@ -131,6 +148,13 @@ class WindowMixin:
                 except (ImportError, Exception):
                     # Not in a PyInstaller frozen environment with splash
                     pass
+                try:
+                    import pytron_loader
+
+                    # The secure loader keeps its splash up through the app's import
+                    pytron_loader.close_splash()
+                except (ImportError, AttributeError):
+                    pass
 
             for combo, func in self.shortcuts.items():
                 self.shortcut_manager.register(combo, func)
@@ -147,10 +171,37 @@ class WindowMixin:
                 if self.windows:
                     self.windows[0].emit("pytron:deep-link", {"url": url})
 
+            # Documents the loader was launched with (file associations)
+            for path in getattr(sys, "pytron_open_files", []):
+                self._dispatch_open_file(self.windows[0], path)
+
             self.windows[0].start()
 
         self.is_running = False
 
+        self._run_exit_callbacks()
+
+        if self.tray:
+            self.tray.stop()
+        self.shortcut_manager.stop()
+
+        if self.config.get("debug", False) and "storage_path" in kwargs:
+            path = kwargs["storage_path"]
+            if os.path.isdir(path):
+                try:
+                    shutil.rmtree(path, ignore_errors=True)
+                except Exception as e:
+                    self.logger.debug(f"Failed to cleanup temp dir {path}: {e}")
+
+    def _run_exit_callbacks(self):
+        """
+        Runs the on_exit callbacks once. The native engine calls this from its graceful
+        quit (the process exits with the event loop), other engines after start() returns.
+        """
+        if getattr(self, "_exit_callbacks_ran", False):
+            return
+        self._exit_callbacks_ran = True
+
         for callback in self._on_exit_callbacks:
             try:
                 if inspect.iscoroutinefunction(callback):
@@ -168,18 +219,6 @@ class WindowMixin:
             except Exception as e:
                 self.logger.error(f"Error in on_exit callback: {e}")
 
-        if self.tray:
-            self.tray.stop()
-        self.shortcut_manager.stop()
-
-        if self.config.get("debug", False) and "storage_path" in kwargs:
-            path = kwargs["storage_path"]
-            if os.path.isdir(path):
-                try:
-                    shutil.rmtree(path, ignore_errors=True)
-                except Exception as e:
-                    self.logger.debug(f"Failed to cleanup temp dir {path}: {e}")
-
     def register_protocol(self, scheme="pytron"):
         if not getattr(sys, "frozen", False):
             self.logger.info(
@@ -280,6 +319,82 @@ class WindowMixin:
         for window in self.windows:
             window.close(force=True)
 
+    def restart(self):
+        """
+        Relaunches the app with the same arguments and quits this instance (on_exit
+        callbacks still run). The new instance waits for this one to release the
+        single-instance guard instead of handing off to it.
+        """
+        try:
+            # Built into the packaged loader
+            import pytron_loader
+
+            pytron_loader.restart()
+        except ImportError:
+            import subprocess
+
+            env = dict(os.environ, PYTRON_RESTARTED_FROM=str(os.getpid()))
+            if getattr(sys, "frozen", False):
+                args = [sys.executable] + sys.argv[1:]
+            else:
+                args = [sys.executable] + sys.argv
+            subprocess.Popen(args, env=env)  # nosec B603
+        self.quit()
+
+    def _set_webview2_data_folder(self, storage_path):
+        # An explicit data_directory/profile is passed to the engine instead; the env var would override it
+        uses_profile = self.config.get("data_directory") or self.config.get("profile")
+        if sys.platform == "win32" and storage_path and not uses_profile:
+            os.environ["WEBVIEW2_USER_DATA_FOLDER"] = storage_path
+
+    def prewarm(self, keep_alive=30.0):
+        """
+        Starts the webview runtime in the background so the first window opens without the
+        cold-start delay (about a second on fresh Windows machines). Call it early, e.g. while
+        a splash screen is up; "prewarm": true in settings.json does it when the App is created.
+        Only WebView2 (Windows) benefits; elsewhere this does nothing.
+        """
+        if sys.platform != "win32":
+            return
+        try:
+            from ..dependencies import pytron_native
+        except ImportError:
+            return
+        call_native = getattr(pytron_native, "prewarm", None)
+        if not call_native:
+            return
+        self._set_webview2_data_folder(self.storage_path)
+        # Must match what the window is created with, see Webview._data_directory
+        data_directory = self.config.get("data_directory")
+        if not data_directory and self.config.get("profile"):
+            data_directory = self.storage_path
+        try:
+            call_native(
+                data_directory,
+                self.config.get("profile"),
+                self.config.get("additional_browser_args"),
+                int(keep_alive * 1000),
+            )
+        except Exception as e:
+            self.logger.warning(f"Could not prewarm the webview: {e}")
+
+    def _kill_orphaned_webviews(self, data_dir):
+        """
+        WebView2 processes from a crashed or force-killed run can outlive their host and
+        keep the data folder locked; clear them before the first window is created.
+        """
+        if not data_dir:
+            return
+        try:
+            from ..dependencies import pytron_native
+        except ImportError:
+            return
+        kill = getattr(pytron_native, "kill_orphaned_webviews", None)
+        if kill:
+            killed = kill(os.path.abspath(data_dir))
+            if killed:
+                self.logger.info(f"Cleaned up {killed} orphaned WebView2 process(es)")
+
     def set_menubar(self, menu_bar):
         """Attaches a MenuBar to the primary window."""
         if self.windows:
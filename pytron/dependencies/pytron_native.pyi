@@ -0,0 +1,216 @@
+# Generated by pytron/engines/native/gen_stubs.py. Do not edit by hand.
+from typing import Any
+
+def is_pipe_available(uid: str) -> bool:
+    """False when another process is already serving `uid` (e.g. a second app instance). A stale socket file with no listener counts as available; `listen` replaces it."""
+    ...
+def set_log_level(level: str) -> None:
+    """Sets the minimum level printed (or forwarded): off, error, warn, info, debug, trace."""
+    ...
+def set_log_handler(handler: Any | None = None) -> None:
+    """Routes native log records to `handler(level, target, message)` instead of stdout. Pass `None` to restore console output."""
+    ...
+def kill_orphaned_webviews(user_data_dir: str) -> int:
+    """Terminates WebView2 processes left behind by a crashed or force-killed instance: every `msedgewebview2.exe` whose `--user-data-dir` lies under `user_data_dir` and whose process tree no longer has a live host. Webviews of a running instance are left alone. Returns how many processes were killed. A no-op outside Windows, where the engine's helper processes exit with their parent."""
+    ...
+def prewarm(data_directory: str | None = None, profile: str | None = None, additional_browser_args: str | None = None, keep_alive_ms: int = 30000) -> None:
+    """Starts the WebView2 browser process in the background (e.g. while the splash is up) so the first window attaches to a running runtime instead of cold-starting one. The arguments must match the ones the window will be created with, or WebView2 refuses the second environment. The environment is held until the first webview exists or `keep_alive_ms` passes. A no-op outside Windows."""
+    ...
+
+class PytronNativeError(RuntimeError):
+    """Base class for native engine failures."""
+
+class PytronWindowError(PytronNativeError):
+    """The window or webview could not be created."""
+
+class PytronIPCError(PytronNativeError):
+    """The IPC pipe could not be opened or written."""
+
+class PytronProtocolError(PytronNativeError):
+    """The custom protocol (asset serving) could not be set up."""
+
+class ChromeIPC:
+    def __init__(self) -> None: ...
+    def listen(self, uid: str | None = None, send_buffer: int = 65536, recv_buffer: int = 65536) -> str:
+        """`send_buffer` / `recv_buffer` size the pipe (Windows) or socket (Unix) buffers in bytes. A `uid` that's None or empty is replaced by a random one; an invalid one raises ValueError."""
+        ...
+    def wait_for_connection(self) -> None: ...
+    def start_read_loop(self, callback: Any, on_disconnect: Any | None = None) -> None:
+        """`on_disconnect()` runs once, when the peer closes the pipe or stops answering keepalives."""
+        ...
+    def start_keepalive(self, interval: float, timeout: float | None = None) -> None:
+        """Pings the peer every `interval` seconds. If nothing arrives for `timeout` seconds (default 3 intervals) the connection is dropped and `on_disconnect` runs."""
+        ...
+    def send(self, data: str, flush: bool = False) -> None:
+        """Queues `data` and returns without waiting for the peer to read it. `flush` makes the writer wait until the peer has drained the pipe (Windows) before the next frame goes out."""
+        ...
+    def request(self, data: str, timeout_ms: int) -> str:
+        """Sends `data` (a JSON object) tagged with a `request_id` and blocks until the peer answers with `{"type": "reply", "request_id": <id>, "payload": ...}`. Returns the payload as JSON."""
+        ...
+
+class NativeWebview:
+    def __init__(self, debug: bool, url_str: str, root_path: str, resizable: bool, frameless: bool, scheme: str = "pytron", cors_origins: list[str] | None = None, native_alert: bool = True, spa_fallback: bool = False, error_pages: dict[int, str] | None = None, protocol_stats: bool = False, asset_archive: str | None = None, headers: dict[str, dict[str, str]] | None = None, service_worker: str | None = None, start_maximized: bool = False, start_fullscreen: bool = False, auto_show_on_ready: bool = False, reload_on_crash: bool = False, watch: bool = False, shadow: bool = True, corners: str | None = None, background_color: str | None = None, additional_browser_args: str | None = None, navigation_allowlist: list[str] | None = None, title: str | None = None, protocol_token: bool = False, initial_css: str | None = None, app_id: str | None = None, data_directory: str | None = None, profile: str | None = None, ipc_batching: bool = False, initial_state: dict[str, str] | None = None, title_bar_style: str | None = None, traffic_light_position: tuple[float, float] | None = None, custom_protocols: dict[str, Any] | None = None) -> None: ...
+    def run(self) -> None: ...
+    def set_title(self, t: str) -> None: ...
+    def set_size(self, w: int, h: int, hints: int) -> None:
+        """Inner (content) size in logical pixels, i.e. before the display's scale factor."""
+        ...
+    def get_size(self) -> tuple[float, float] | None:
+        """Inner size in logical pixels, the same units `set_size` takes. None if the event loop isn't running."""
+        ...
+    def navigate(self, u: str) -> None: ...
+    def navigate_with_headers(self, u: str, headers: dict[str, str]) -> None:
+        """Like `navigate`, attaching extra request headers (e.g. Authorization) to the initial load."""
+        ...
+    def eval(self, j: str) -> None: ...
+    def bind(self, n: str, f: Any, pass_context: bool = False) -> None:
+        """With `pass_context`, page calls pass `{"window_id", "origin", "method"}` as JSON in the third argument; otherwise it stays `0` as it always was."""
+        ...
+    def return_result(self, s: str, st: int, r: str) -> None: ...
+    def emit_chunk(self, seq: str, data: str) -> None:
+        """Delivers one JSON chunk of a streaming call; the page reads them through `call.stream()` or `for await`. `end_stream` (or an error `return_result`) closes the stream."""
+        ...
+    def end_stream(self, seq: str) -> None: ...
+    def insert_css(self, css: str, id: str | None = None) -> str:
+        """Injects `css` as a `<style>` element that survives navigation. Reusing an `id` replaces that sheet in place; returns the id for `remove_css`."""
+        ...
+    def remove_css(self, id: str) -> None: ...
+    def wait_until_ready(self, timeout_ms: int) -> bool:
+        """Blocks (GIL released) until the current page's bridge is up, so `eval`/`emit` aren't dropped. Returns false if `timeout_ms` elapsed first."""
+        ...
+    def set_app_id(self, id: str) -> None:
+        """Windows AppUserModelID (taskbar grouping, icon, jump lists). No-op elsewhere."""
+        ...
+    def terminate(self) -> None:
+        """Hard exit: the loop stops without running `pytron_on_quit`."""
+        ...
+    def quit(self) -> None:
+        """Graceful exit: `pytron_on_quit` gets up to `QUIT_TIMEOUT` to save state, then the loop stops."""
+        ...
+    def show(self) -> None: ...
+    def hide(self) -> None: ...
+    def suspend(self) -> None:
+        """Freezes rendering, timers and script while the window is hidden. Resumed automatically on show."""
+        ...
+    def resume(self) -> None: ...
+    def set_auto_suspend(self, seconds: int | None = None) -> None:
+        """Suspend after the window has stayed hidden for `seconds`; `None` turns it off."""
+        ...
+    def set_hang_watchdog(self, interval_ms: int | None, misses: int = 3) -> None:
+        """Pings the page every `interval_ms`; after `misses` unanswered pings in a row `pytron_on_page_hang("unresponsive")` is called once per hang. None stops the watchdog."""
+        ...
+    def minimize(self) -> None: ...
+    def maximize(self) -> None: ...
+    def unmaximize(self) -> None: ...
+    def toggle_maximize(self) -> None: ...
+    def set_minimize_to_tray(self, m: bool) -> None:
+        """Hide to the tray instead of closing/minimizing (only while a tray icon exists)."""
+        ...
+    def set_close_to_tray(self, c: bool) -> None:
+        """The close button hides the window while a tray icon exists; without one it closes as usual."""
+        ...
+    def start_drag(self) -> None: ...
+    def system_notification(self, t: str, m: str) -> None: ...
+    def set_taskbar_progress(self, s: int, v: int, m: int) -> None: ...
+    def is_online(self) -> bool:
+        """Whether the OS reports internet access. `pytron_on_connectivity_changed(online)` and a `pytron:connectivity` DOM event follow every change."""
+        ...
+    def get_power_state(self) -> dict[str, Any]:
+        """`on_battery`, `battery_percent` (None without a battery) and `charging`. `pytron_on_power_changed` receives the same dict whenever it changes."""
+        ...
+    def get_webview_version(self) -> dict[str, Any]:
+        """`backend` ("webview2" | "wkwebview" | "webkitgtk") and the engine's `version`, for bug reports. On Windows this is the runtime the window actually runs on, which may be a fixed-version one."""
+        ...
+    def get_url(self) -> str:
+        """The page's current URL, including in-page route changes. Empty if the event loop isn't running."""
+        ...
+    def get_hwnd(self) -> int: ...
+    def get_window_handle(self) -> tuple[str, int]:
+        """("win32" | "appkit" | "xlib" | "xcb" | "wayland", raw handle)"""
+        ...
+    def get_protocol_stats(self) -> dict[str, int] | None:
+        """Custom protocol counters, or `None` when created without `protocol_stats=True`. `cache_hits` counts requests served from the embedded archive."""
+        ...
+    def protocol_token(self) -> str | None:
+        """The per-launch protocol token, or `None` without `protocol_token=True`. Python appends it as `?pytron_token=` to URLs it serves, since `src` attributes can't send headers."""
+        ...
+    def print(self, force_light: bool = False) -> None:
+        """Opens the print dialog for the page. `force_light` prints dark-themed pages in a light scheme: `prefers-color-scheme` is emulated as light on Windows, and a print-only white background is injected everywhere, both undone once the dialog closes."""
+        ...
+    def set_fullscreen(self, e: bool) -> None: ...
+    def set_fullscreen_ex(self, enabled: bool, monitor_index: int | None = None, exclusive: bool = False) -> None:
+        """Fullscreen on the `monitor_index`-th display (as the OS enumerates them; default: the window's current one). `exclusive` switches the display to a video mode at its native resolution instead of covering it with a borderless window."""
+        ...
+    def set_always_on_top(self, e: bool) -> None: ...
+    def set_resizable(self, e: bool) -> None: ...
+    def set_aspect_ratio(self, w: int | None = None, h: int | None = None) -> None:
+        """Keeps the window's inner size at `w:h` while the user resizes it. Call with no arguments (or None) to allow any shape again."""
+        ...
+    def set_decorations(self, e: bool) -> None: ...
+    def set_ignore_cursor_events(self, ignore: bool) -> None:
+        """Click-through: the window stays drawn but mouse input goes to whatever is beneath it."""
+        ...
+    def set_corner_preference(self, pref: str) -> None:
+        """Windows 11 corner style: "round", "small", "square" or "default". No-op elsewhere."""
+        ...
+    def set_shadow(self, e: bool) -> None:
+        """Drop shadow for frameless windows (Windows, macOS). No effect on Linux."""
+        ...
+    def center(self) -> None: ...
+    def dialog_open_file(self, title: str, dir: str | None = None, filters: str | None = None) -> str | None: ...
+    def dialog_open_files(self, title: str, dir: str | None = None, filters: str | None = None) -> list[str] | None: ...
+    def dialog_save_file(self, title: str, dir: str | None = None, name: str | None = None, filters: str | None = None) -> str | None: ...
+    def dialog_open_folder(self, title: str, dir: str | None = None) -> str | None: ...
+    def message_box(self, title: str, msg: str, level: str) -> bool: ...
+    def show_about(self) -> None:
+        """Native About box with the name, version and author from the app's settings. macOS shows the standard About panel instead."""
+        ...
+    def message_box_ex(self, title: str, msg: str, level: str = "info", buttons: str = "okcancel") -> str:
+        """Returns the pressed button: "ok", "cancel", "yes", "no", or a custom label."""
+        ...
+    def dialog_prompt(self, title: str, label: str, default: str | None = None) -> str | None: ...
+    def show_context_menu(self, menu_json: str, x: int, y: int) -> None:
+        """Pops up a native menu at window-relative logical (x, y). Clicks arrive at the `pytron_menu_click` callback and as a `pytron:menu-click` DOM event."""
+        ...
+    def set_badge(self, label: str | None = None) -> None:
+        """Dock badge text on macOS, a rendered overlay icon on the Windows taskbar (numbers only), launcher count on Linux. `None` clears it."""
+        ...
+    def emit(self, event_name: str, json: str) -> None:
+        """Dispatches `CustomEvent(event_name, { detail })` on this window. `json` must be valid JSON."""
+        ...
+    def register_accelerator(self, accel: str, id: str) -> None:
+        """Window-local shortcut such as "CmdOrCtrl+Shift+K". While this window is focused, pressing it calls `pytron_on_accelerator(id)` and dispatches `pytron:accelerator`. Re-registering an id replaces it."""
+        ...
+    def unregister_accelerator(self, id: str) -> None: ...
+    def post_custom(self, json: str) -> None:
+        """Queues `json` for `pytron_on_custom(json)` on the event loop thread. Safe from any thread; Rust extensions can send `UserEvent::Custom` through `proxy` directly."""
+        ...
+    def broadcast(self, event_name: str, json: str) -> int:
+        """Like `emit`, but for every live webview in the process. Returns how many were reached."""
+        ...
+    def clear_cache(self) -> bool:
+        """Clears the HTTP cache. Blocks until the webview reports completion."""
+        ...
+    def clear_cookies(self) -> bool:
+        """Deletes every cookie in the webview profile."""
+        ...
+    def clear_all_data(self) -> bool:
+        """Wipes cache, cookies, storage and history."""
+        ...
+    def get_cookies(self, url: str) -> list[dict[str, Any]]:
+        """Cookies visible to `url`, as dicts with name, value, domain, path, expires (unix seconds or None), http_only, secure. Raises RuntimeError before `run()`, since the store can't be read until the loop starts."""
+        ...
+    def set_cookie(self, url: str, name: str, value: str, domain: str | None = None, path: str | None = None, expires: float | None = None, http_only: bool = False, secure: bool = False) -> bool:
+        """Domain and path default to the url's host and "/". Before `run()` the cookie is queued and applied as the loop starts, and this returns True once it is queued."""
+        ...
+    def delete_cookie(self, url: str, name: str) -> bool:
+        """True once the store has handled the request, whether or not a cookie by that name existed. Queued like `set_cookie` before `run()`."""
+        ...
+    def set_prevent_close(self, p: bool) -> None: ...
+    def set_tray_animation(self, frames: list[str], interval_ms: int) -> None:
+        """Cycles the tray icon through `frames` (image paths) every `interval_ms` until `stop_tray_animation`, which restores the static icon."""
+        ...
+    def stop_tray_animation(self) -> None: ...
+    def create_tray(self, icon_path: str, tooltip: str, menu_json: str | None = None) -> None:
+        """`menu_json` uses the context-menu format and replaces the default Show App / Quit items; pass `"[]"` for a tray icon without a menu."""
+        ...
@@ -5,7 +5,6 @@ import logging
 import threading
 import socket
 import uuid
-import struct
 import subprocess
 import tempfile
 import ctypes
@@ -15,6 +14,8 @@ try:
 except ImportError:
     pytron_native = None
 
+from .framing import TYPE_CONTROL, encode_frame, read_frame
+
 logger = logging.getLogger("Pytron.ChromeAdapter")
 
 
@@ -28,11 +29,16 @@ class ChromeIPCServer:
         - \\\\.\\pipe\\pytron-{uuid}-out (Electron Writes -> Python Reads)
     """
 
-    def __init__(self):
+    def __init__(self, keepalive=None, buffer_size=None):
         self.connected = False
         self._lock = threading.Lock()
         self.listening_event = threading.Event()
         self.pipe_path_base = None
+        # Seconds between pings (native IPC only); None disables the keepalive
+        self.keepalive = keepalive
+        # Bytes per direction for the native pipe/socket buffers; None keeps the 64 KiB default
+        self.buffer_size = buffer_size
+        self.on_disconnect = None
 
         # Native implementation (Rust)
         self._native = None
@@ -56,7 +62,11 @@ class ChromeIPCServer:
 
         if self._native:
             try:
-                self.pipe_path_base = self._native.listen(uid)
+                if self.buffer_size:
+                    size = int(self.buffer_size)
+                    self.pipe_path_base = self._native.listen(uid, size, size)
+                else:
+                    self.pipe_path_base = self._native.listen(uid)
                 self.listening_event.set()
                 logger.info(f"Mojo IPC (Native) listening on: {self.pipe_path_base}")
                 self._native.wait_for_connection()
@@ -174,7 +184,9 @@ class ChromeIPCServer:
         if self._native:
             try:
                 # The native read loop runs in its own thread and calls back to Python
-                self._native.start_read_loop(callback)
+                self._native.start_read_loop(callback, self._on_disconnected)
+                if self.keepalive:
+                    self._native.start_keepalive(float(self.keepalive))
                 # We need to block here like the original read_loop did, to keep the thread alive
                 # or until disconnect.
                 while self.connected:
@@ -187,24 +199,21 @@ class ChromeIPCServer:
 
         while self.connected:
             try:
-                # 1. Read 4-byte Header
-                header = self._recv_bytes(4)
-                if not header or len(header) != 4:
-                    break
-                msg_len = struct.unpack("<I", header)[0]
-
-                # 2. Read Body
-                body = self._recv_bytes(msg_len)
-                if not body or len(body) != msg_len:
+                # 1. Read one frame (header + body)
+                frame = read_frame(self._recv_bytes)
+                if frame is None:
                     break
+                kind, body = frame
 
-                # 3. Dispatch
+                # 2. Dispatch (control frames are reserved, nothing to do yet)
+                if kind == TYPE_CONTROL:
+                    continue
                 msg = json.loads(body.decode("utf-8"))
                 callback(msg)
             except Exception as e:
                 logger.error(f"IPC Read Error: {e}")
                 break
-        self.connected = False
+        self._on_disconnected()
         # Cleanup
         if self.is_windows:
             if self._win_in_handle:
@@ -217,6 +226,14 @@ class ChromeIPCServer:
             except:
                 pass
 
+    def _on_disconnected(self):
+        was_connected, self.connected = self.connected, False
+        if was_connected and self.on_disconnect:
+            try:
+                self.on_disconnect()
+            except Exception as e:
+                logger.error(f"IPC disconnect callback failed: {e}")
+
     def _recv_bytes(self, n):
         if self.is_windows:
             buf = ctypes.create_string_buffer(n)
@@ -249,7 +266,8 @@ class ChromeIPCServer:
                 data.extend(packet)
             return data
 
-    def send(self, data_dict):
+    def send(self, data_dict, flush=False):
+        """flush: wait for the shell to drain the pipe first (native IPC on Windows)."""
         if not self.connected:
             return
         with self._lock:
@@ -257,12 +275,10 @@ class ChromeIPCServer:
                 body_str = json.dumps(data_dict)
 
                 if self._native:
-                    self._native.send(body_str)
+                    self._native.send(body_str, flush)
                     return
 
-                body = body_str.encode("utf-8")
-                header = struct.pack("<I", len(body))
-                full_msg = header + body
+                full_msg = encode_frame(body_str)
 
                 if self.is_windows:
                     written = ctypes.c_ulong(0)
@@ -283,6 +299,17 @@ class ChromeIPCServer:
                 self.connected = False
 
 
+    def request(self, data_dict, timeout=5.0):
+        """
+        Sends a command and waits for the shell's reply payload (native IPC only).
+        Raises on timeout or disconnect.
+        """
+        if not self._native:
+            raise RuntimeError("IPC requests need the native engine (pytron_native)")
+        reply = self._native.request(json.dumps(data_dict), int(timeout * 1000))
+        return json.loads(reply)
+
+
 class ChromeAdapter:
     def __init__(self, binary_path, config=None):
         self.binary_path = binary_path
@@ -293,9 +320,15 @@ class ChromeAdapter:
         self._raw_callback = None
         self._queue = []
         self._flush_lock = threading.Lock()
+        # Called with no arguments when the shell disconnects or stops answering pings
+        self.on_disconnect = None
 
     def start(self):
-        self.ipc = ChromeIPCServer()
+        self.ipc = ChromeIPCServer(
+            keepalive=self.config.get("ipc_keepalive"),
+            buffer_size=self.config.get("ipc_buffer_size"),
+        )
+        self.ipc.on_disconnect = self._on_ipc_disconnect
 
         # Start the server thread
         def _server_launcher():
@@ -363,6 +396,11 @@ class ChromeAdapter:
             target=self._proxy_logs, args=(self.process.stderr, "STDERR"), daemon=True
         ).start()
 
+    def _on_ipc_disconnect(self):
+        logger.warning("Mojo Shell IPC disconnected")
+        if self.on_disconnect:
+            self.on_disconnect()
+
     def _proxy_logs(self, pipe, prefix):
         try:
             while True:
@@ -441,5 +479,10 @@ class ChromeAdapter:
             with self._flush_lock:
                 self._queue.append(payload)
 
+    def request(self, payload, timeout=5.0):
+        if not (self.ipc and self.ipc.connected and self.ready):
+            raise RuntimeError("Chrome shell is not connected yet")
+        return self.ipc.request(payload, timeout)
+
     def bind_raw(self, callback):
         self._raw_callback = callback
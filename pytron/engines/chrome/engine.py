@@ -160,6 +160,7 @@ class ChromeWebView(Webview):
             ).futures.ThreadPoolExecutor(max_workers=5)
 
         self._bound_functions = {}
+        self._context_functions = set()
         self._served_data = {}
 
         # 3. Resolve Chrome Binary
@@ -470,7 +471,18 @@ class ChromeWebView(Webview):
             if event in self._bound_functions:
                 func = self._bound_functions[event]
                 try:
-                    result = func(*args) if isinstance(args, list) else func(args)
+                    kwargs = {}
+                    if event in self._context_functions:
+                        kwargs["context"] = {
+                            "window_id": "main",
+                            "origin": self.config.get("url"),
+                            "method": event,
+                        }
+                    result = (
+                        func(*args, **kwargs)
+                        if isinstance(args, list)
+                        else func(args, **kwargs)
+                    )
 
                     if inspect.iscoroutine(result):
                         try:
@@ -493,8 +505,12 @@ class ChromeWebView(Webview):
                             self.w, seq.encode("utf-8"), 1, json.dumps(safe_err)
                         )
 
-    def bind(self, name, func, run_in_thread=True, secure=False):
+    def bind(self, name, func, run_in_thread=True, secure=False, pass_context=False):
         self._bound_functions[name] = func
+        if pass_context:
+            self._context_functions.add(name)
+        else:
+            self._context_functions.discard(name)
         self.bridge.webview_bind(self.w, name.encode("utf-8"), None, None)
 
     # --- Feature Overrides (Compatibility Layer) ---
@@ -549,6 +565,10 @@ class ChromeWebView(Webview):
     def eval(self, js):
         self.bridge.webview_eval(self.w, js)
 
+    def broadcast(self, event, data=None):
+        # One Electron window per engine instance, nothing else to reach
+        self.emit(event, data)
+
     def toggle_maximize(self):
         self.bridge.adapter.send({"action": "toggle_maximize"})
 
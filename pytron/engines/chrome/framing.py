@@ -0,0 +1,84 @@
+"""
+Frame format shared by ChromeIPC (Rust), the Python fallbacks and shell.js:
+
+    magic b"PT" (2) | version (1) | type (1) | body length, u32 LE (4) | body
+"""
+
+import struct
+import logging
+
+logger = logging.getLogger("Pytron.ChromeIPC")
+
+MAGIC = b"PT"
+VERSION = 1
+
+TYPE_TEXT = 0
+TYPE_BINARY = 1
+TYPE_CONTROL = 2  # Keepalive: b"ping" is answered with b"pong"
+
+HEADER = struct.Struct("<2sBBI")
+
+# Give up instead of scanning a corrupt stream forever
+MAX_RESYNC_BYTES = 64 * 1024
+# Bounds the allocation a corrupt or hostile length field can ask for
+MAX_FRAME_LEN = 256 * 1024 * 1024
+
+
+class FrameError(Exception):
+    """The stream is not speaking this protocol (bad magic, version or type)."""
+
+
+def encode_frame(body, kind=TYPE_TEXT):
+    if isinstance(body, str):
+        body = body.encode("utf-8")
+    return HEADER.pack(MAGIC, VERSION, kind, len(body)) + body
+
+
+def read_frame(read_exact):
+    """
+    Reads one frame using read_exact(n), which returns exactly n bytes or None at EOF.
+    Returns (type, body) or None when the peer went away.
+    Skips garbage (including stray "PT" bytes) up to the next valid header; raises FrameError
+    if that fails, or if an in-sync header has the wrong version or an oversized length.
+    """
+    header = read_exact(HEADER.size)
+    if not header:
+        return None
+    header = bytearray(header)
+
+    skipped = 0
+    while True:
+        if header[:2] == MAGIC:
+            try:
+                kind, length = _check_header(header)
+                break
+            except FrameError:
+                # A known type read in sync is a peer we can't talk to; anything else that
+                # merely starts with "PT" is stray bytes, so keep scanning
+                if not skipped and header[3] in (TYPE_TEXT, TYPE_BINARY, TYPE_CONTROL):
+                    raise
+        if skipped >= MAX_RESYNC_BYTES:
+            raise FrameError("Lost frame sync")
+        nxt = read_exact(1)
+        if not nxt:
+            return None
+        header = header[1:] + nxt
+        skipped += 1
+    if skipped:
+        logger.warning(f"IPC resynced after skipping {skipped} bytes")
+
+    body = read_exact(length) if length else b""
+    if body is None or len(body) != length:
+        return None
+    return kind, bytes(body)
+
+
+def _check_header(header):
+    _, version, kind, length = HEADER.unpack(bytes(header))
+    if version != VERSION:
+        raise FrameError(f"Unsupported frame version {version} (expected {VERSION})")
+    if kind not in (TYPE_TEXT, TYPE_BINARY, TYPE_CONTROL):
+        raise FrameError(f"Unknown frame type {kind}")
+    if length > MAX_FRAME_LEN:
+        raise FrameError(f"Frame of {length} bytes exceeds the {MAX_FRAME_LEN} limit")
+    return kind, length
@@ -111,20 +111,7 @@ function connectToPytron() {
             });
 
             // Setup Reader on clientIn
-            clientIn.on('data', (chunk) => {
-                buffer = Buffer.concat([buffer, chunk]);
-                while (buffer.length >= 4) {
-                    const msgLen = buffer.readUInt32LE(0);
-                    if (buffer.length >= 4 + msgLen) {
-                        const bodyBytes = buffer.slice(4, 4 + msgLen);
-                        const bodyString = bodyBytes.toString('utf-8');
-                        handlePythonCommand(bodyString);
-                        buffer = buffer.slice(4 + msgLen);
-                    } else {
-                        break;
-                    }
-                }
-            });
+            clientIn.on('data', onFrameData);
 
             clientIn.on('error', (err) => log(`Pipe-IN Error: ${err.message}`));
             clientOut.on('error', (err) => log(`Pipe-OUT Error: ${err.message} (Code: ${err.code})`));
@@ -168,21 +155,73 @@ function connectToPytron() {
     }
 }
 
-function setupClientListeners(socket) {
-    socket.on('data', (chunk) => {
-        buffer = Buffer.concat([buffer, chunk]);
-        while (buffer.length >= 4) {
-            const msgLen = buffer.readUInt32LE(0);
-            if (buffer.length >= 4 + msgLen) {
-                const bodyBytes = buffer.slice(4, 4 + msgLen);
-                const bodyString = bodyBytes.toString('utf-8');
-                handlePythonCommand(bodyString);
-                buffer = buffer.slice(4 + msgLen);
-            } else {
-                break;
+// Frame: magic "PT" (2) | version (1) | type (1) | body length u32 LE (4) | body
+const FRAME_MAGIC = Buffer.from('PT');
+const FRAME_VERSION = 1;
+const FRAME_HEADER = 8;
+const FRAME_TEXT = 0;
+const FRAME_BINARY = 1;
+const FRAME_CONTROL = 2; // Keepalive: "ping" is answered with "pong"
+
+function encodeFrame(type, bodyBuf) {
+    const header = Buffer.alloc(FRAME_HEADER);
+    FRAME_MAGIC.copy(header, 0);
+    header.writeUInt8(FRAME_VERSION, 2);
+    header.writeUInt8(type, 3);
+    header.writeUInt32LE(bodyBuf.length, 4);
+    return Buffer.concat([header, bodyBuf]);
+}
+
+// Bounds what a corrupt or hostile length field can make us buffer
+const MAX_FRAME_LEN = 256 * 1024 * 1024;
+// Set after skipping garbage: a header that fails checks then is just a stray "PT"
+let resyncing = false;
+
+function onFrameData(chunk) {
+    buffer = Buffer.concat([buffer, chunk]);
+    while (buffer.length >= FRAME_HEADER) {
+        // Resync: drop bytes until the next magic
+        if (buffer[0] !== FRAME_MAGIC[0] || buffer[1] !== FRAME_MAGIC[1]) {
+            const next = buffer.indexOf(FRAME_MAGIC, 1);
+            log(`IPC desync, skipping ${next === -1 ? buffer.length - 1 : next} bytes`);
+            buffer = next === -1 ? buffer.slice(buffer.length - 1) : buffer.slice(next);
+            resyncing = true;
+            continue;
+        }
+        const version = buffer.readUInt8(2);
+        const type = buffer.readUInt8(3);
+        const msgLen = buffer.readUInt32LE(4);
+        const known = type === FRAME_TEXT || type === FRAME_BINARY || type === FRAME_CONTROL;
+        const problem = version !== FRAME_VERSION
+            ? `frame version ${version}, expected ${FRAME_VERSION}. Core and shell are out of date with each other.`
+            : msgLen > MAX_FRAME_LEN ? `frame of ${msgLen} bytes exceeds the ${MAX_FRAME_LEN} limit` : null;
+        if (problem || (resyncing && !known)) {
+            // Only a known type read in sync is fatal; otherwise keep scanning past this "PT"
+            if (resyncing || !known) {
+                buffer = buffer.slice(1);
+                resyncing = true;
+                continue;
             }
+            log(`FATAL: IPC ${problem}`);
+            app.quit();
+            return;
         }
-    });
+        if (buffer.length < FRAME_HEADER + msgLen) break;
+        const body = buffer.slice(FRAME_HEADER, FRAME_HEADER + msgLen);
+        buffer = buffer.slice(FRAME_HEADER + msgLen);
+        resyncing = false;
+        if (type === FRAME_TEXT) {
+            handlePythonCommand(body.toString('utf-8'));
+        } else if (type === FRAME_CONTROL) {
+            if (body.toString('utf-8') === 'ping') sendControl('pong');
+        } else if (!known) {
+            log(`Ignoring unknown IPC frame type ${type}`);
+        }
+    }
+}
+
+function setupClientListeners(socket) {
+    socket.on('data', onFrameData);
     socket.on('error', (err) => log(`Socket Error: ${err.message}`));
     socket.on('close', () => {
         log("Socket Closed. Exiting.");
@@ -190,22 +229,36 @@ function setupClientListeners(socket) {
     });
 }
 
+function sendControl(text) {
+    const target = clientOut || client;
+    if (target && !target.destroyed) {
+        target.write(encodeFrame(FRAME_CONTROL, Buffer.from(text, 'utf8')));
+    }
+}
+
 function sendToPython(type, payload) {
     const target = clientOut || client;
 
     if (target && !target.destroyed) {
         try {
             const bodyStr = JSON.stringify({ type, payload });
-            const bodyBuf = Buffer.from(bodyStr, 'utf8');
-            const headerBuf = Buffer.alloc(4);
-            headerBuf.writeUInt32LE(bodyBuf.length, 0);
-            target.write(Buffer.concat([headerBuf, bodyBuf]));
+            target.write(encodeFrame(FRAME_TEXT, Buffer.from(bodyStr, 'utf8')));
         } catch (e) {
             log(`Send Error: ${e.message}`);
         }
     }
 }
 
+// Answers a ChromeIPC.request(); commands sent with send() carry no request_id
+function replyToPython(command, payload) {
+    if (command.request_id === undefined) return;
+    const target = clientOut || client;
+    if (target && !target.destroyed) {
+        const bodyStr = JSON.stringify({ type: 'reply', request_id: command.request_id, payload: payload === undefined ? null : payload });
+        target.write(encodeFrame(FRAME_TEXT, Buffer.from(bodyStr, 'utf8')));
+    }
+}
+
 function handlePythonCommand(cmd) {
     if (isDebug) log(`Executing: ${cmd.substring(0, 100)}...`);
 
@@ -236,7 +289,16 @@ function handlePythonCommand(cmd) {
                 }
                 break;
             case 'eval':
-                if (mainWindow) mainWindow.webContents.executeJavaScript(command.code).catch(e => log(`Eval Err: ${e.message}`)); // nosemgrep
+                if (mainWindow) {
+                    mainWindow.webContents.executeJavaScript(command.code) // nosemgrep
+                        .then(result => replyToPython(command, result))
+                        .catch(e => {
+                            log(`Eval Err: ${e.message}`);
+                            replyToPython(command, { error: e.message });
+                        });
+                } else {
+                    replyToPython(command, { error: 'no window' });
+                }
                 break;
             case 'set_title':
                 if (mainWindow) mainWindow.setTitle(command.title);
@@ -3,6 +3,8 @@ import shutil
 import os
 import sys
 
+sys.path.insert(0, os.path.dirname(os.path.abspath(__file__)))
+
 # Paths
 # This script is in pytron/pytron/engines/native/
 ENGINE_DIR = os.path.dirname(os.path.abspath(__file__))
@@ -85,6 +87,14 @@ def build():
             print(f"[ERROR] Force copy failed: {e2}. Is the app running?")
             sys.exit(1)
 
+    # 5. Type stubs for editors
+    try:
+        import gen_stubs
+
+        gen_stubs.main()
+    except Exception as e:
+        print(f"[WARN] Could not generate type stubs: {e}")
+
 
 if __name__ == "__main__":
     build()
@@ -0,0 +1,237 @@
+"""
+Generates pytron_native.pyi from the #[pyclass]/#[pymethods]/#[pyfunction]
+items in src/, so editors get real signatures for NativeWebview and ChromeIPC.
+
+Run directly (or via build.py) whenever the Rust surface changes:
+    python gen_stubs.py [output.pyi]
+"""
+
+import os
+import re
+import sys
+
+ENGINE_DIR = os.path.dirname(os.path.abspath(__file__))
+SRC_DIR = os.path.join(ENGINE_DIR, "src")
+ROOT = os.path.abspath(os.path.join(ENGINE_DIR, "..", "..", ".."))
+DEFAULT_OUT = os.path.join(ROOT, "pytron", "dependencies", "pytron_native.pyi")
+
+SCALARS = {
+    "String": "str",
+    "str": "str",
+    "bool": "bool",
+    "f32": "float",
+    "f64": "float",
+    "PyObject": "Any",
+    "PyAny": "Any",
+    "PyDict": "dict[str, Any]",
+    "()": "None",
+}
+INTS = {"i8", "i16", "i32", "i64", "isize", "u8", "u16", "u32", "u64", "usize"}
+
+
+def split_top(s, sep=","):
+    """Splits on `sep` outside of <>, () and []."""
+    parts, depth, cur = [], 0, ""
+    for ch in s:
+        if ch in "<([":
+            depth += 1
+        elif ch in ">)]":
+            depth -= 1
+        if ch == sep and depth == 0:
+            parts.append(cur.strip())
+            cur = ""
+        else:
+            cur += ch
+    if cur.strip():
+        parts.append(cur.strip())
+    return parts
+
+
+def py_type(rust):
+    t = rust.strip()
+    t = re.sub(r"^&('\w+\s+)?(mut\s+)?", "", t)
+    t = re.sub(r"'\w+\s*,\s*", "", t)  # Bound<'py, T> -> Bound<T>
+    if t.startswith("(") and t.endswith(")"):
+        inner = split_top(t[1:-1])
+        return "tuple[" + ", ".join(py_type(x) for x in inner) + "]" if inner else "None"
+    m = re.match(r"^([\w:]+)\s*<(.*)>$", t)
+    if m:
+        name, args = m.group(1).split("::")[-1], split_top(m.group(2))
+        if name == "Option":
+            return f"{py_type(args[0])} | None"
+        if name == "Vec":
+            return f"list[{py_type(args[0])}]"
+        if name in ("HashMap", "BTreeMap"):
+            return f"dict[{py_type(args[0])}, {py_type(args[1])}]"
+        if name in ("PyResult", "Bound", "Py", "Box"):
+            return py_type(args[0])
+        return "Any"
+    name = t.split("::")[-1]
+    if name in INTS:
+        return "int"
+    return SCALARS.get(name, "Any")
+
+
+def py_default(rust):
+    d = rust.strip()
+    m = re.match(r'^String::from\((".*")\)$', d)
+    if m:
+        return m.group(1)
+    if d.startswith('"'):
+        return d
+    return {"true": "True", "false": "False", "None": "None"}.get(d, d)
+
+
+def block_end(text, start):
+    """Index just past the `}` matching the `{` at `start`."""
+    depth = 0
+    for i in range(start, len(text)):
+        if text[i] == "{":
+            depth += 1
+        elif text[i] == "}":
+            depth -= 1
+            if depth == 0:
+                return i + 1
+    return len(text)
+
+
+FN_RE = re.compile(
+    r"((?:[ \t]*(?:///[^\n]*|#\[[^\n]*\])\n)*)[ \t]*(?:pub(?:\([^)]*\))?\s+)?fn\s+(\w+)\s*(<[^>]*>)?\s*\(",
+)
+
+
+def parse_fn(text, m):
+    attrs, name = m.group(1), m.group(2)
+    # Parameter list: match the parenthesis opened by the regex
+    i, depth = m.end(), 1
+    while depth:
+        depth += {"(": 1, ")": -1}.get(text[i], 0)
+        i += 1
+    params = text[m.end() : i - 1]
+    ret = re.match(r"\s*->\s*([^{;]+)", text[i:])
+    ret_type = ret.group(1).strip() if ret else "()"
+
+    args = []
+    for p in split_top(params):
+        if p in ("&self", "&mut self", "self") or ":" not in p:
+            continue
+        pname, ptype = [x.strip() for x in p.split(":", 1)]
+        if re.match(r"^Python\s*<", ptype):
+            continue
+        args.append((pname, ptype))
+
+    defaults = {}
+    sig = re.search(r"#\[pyo3\(signature\s*=\s*\((.*)\)\)\]", attrs)
+    if sig:
+        for entry in split_top(sig.group(1)):
+            if "=" in entry:
+                k, v = entry.split("=", 1)
+                defaults[k.strip()] = py_default(v)
+
+    doc = [l.strip()[3:].strip() for l in attrs.splitlines() if l.strip().startswith("///")]
+    return {
+        "name": name,
+        "new": "#[new]" in attrs,
+        "args": args,
+        "defaults": defaults,
+        "ret": ret_type,
+        "doc": doc,
+    }
+
+
+def render_fn(f, indent="", method=False):
+    params = ["self"] if method else []
+    for pname, ptype in f["args"]:
+        p = f"{pname}: {py_type(ptype)}"
+        if pname in f["defaults"]:
+            p += f" = {f['defaults'][pname]}"
+        params.append(p)
+    name, ret = ("__init__", "None") if f["new"] else (f["name"], py_type(f["ret"]))
+    head = f"{indent}def {name}({', '.join(params)}) -> {ret}:"
+    if not f["doc"]:
+        return [head + " ..."]
+    doc = " ".join(f["doc"]).replace('"""', "'''")
+    return [head, f'{indent}    """{doc}"""', f"{indent}    ..."]
+
+
+EXC_RE = re.compile(r'create_exception!\(\s*\w+\s*,\s*(\w+)\s*,\s*(\w+)\s*(?:,\s*"([^"]*)")?\s*\)')
+
+
+def collect_exceptions(sources):
+    """`create_exception!(module, Name, Base, "doc")` -> (name, python base, doc)."""
+    found = []
+    for text in sources:
+        for m in EXC_RE.finditer(text):
+            base = m.group(2)
+            if base.startswith("Py") and not base.startswith("Pytron"):
+                base = base[2:]  # pyo3's PyRuntimeError -> RuntimeError
+            found.append((m.group(1), base, m.group(3)))
+    return found
+
+
+def collect(sources):
+    classes, functions = {}, []
+    for text in sources:
+        for m in re.finditer(r"#\[pyclass[^\]]*\]\s*(?:#\[[^\]]*\]\s*)*pub\s+struct\s+(\w+)", text):
+            classes.setdefault(m.group(1), [])
+        for m in re.finditer(r"#\[pymethods\]\s*impl\s+(\w+)\s*\{", text):
+            body = text[m.end() - 1 : block_end(text, m.end() - 1)]
+            methods = classes.setdefault(m.group(1), [])
+            depth_map = _depths(body)
+            for fm in FN_RE.finditer(body):
+                if depth_map[fm.start(2)] == 1:
+                    methods.append(parse_fn(body, fm))
+        for m in FN_RE.finditer(text):
+            if "#[pyfunction]" in m.group(1):
+                functions.append(parse_fn(text, m))
+    return classes, functions
+
+
+def _depths(body):
+    depths, d = [], 0
+    for ch in body:
+        if ch == "{":
+            d += 1
+        elif ch == "}":
+            d -= 1
+        depths.append(d)
+    return depths
+
+
+def generate():
+    sources = []
+    for name in sorted(os.listdir(SRC_DIR)):
+        if name.endswith(".rs"):
+            with open(os.path.join(SRC_DIR, name), encoding="utf-8") as fh:
+                sources.append(fh.read())
+    classes, functions = collect(sources)
+
+    out = [
+        "# Generated by pytron/engines/native/gen_stubs.py. Do not edit by hand.",
+        "from typing import Any",
+        "",
+    ]
+    for f in functions:
+        out += render_fn(f)
+    for name, base, doc in collect_exceptions(sources):
+        out += ["", f"class {name}({base}):"]
+        out.append(f'    """{doc}"""' if doc else "    ...")
+    for cls in sorted(classes):
+        out += ["", f"class {cls}:"]
+        methods = classes[cls] or []
+        if not methods:
+            out.append("    ...")
+        for f in methods:
+            out += render_fn(f, indent="    ", method=True)
+    return "\n".join(out) + "\n"
+
+
+def main():
+    dest = sys.argv[1] if len(sys.argv) > 1 else DEFAULT_OUT
+    with open(dest, "w", encoding="utf-8", newline="\n") as fh:
+        fh.write(generate())
+    print(f"[STUBS] Wrote {dest}")
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,157 @@
+use std::path::PathBuf;
+use std::process::Command;
+use rfd::{AsyncFileDialog, FileHandle, MessageButtons, MessageDialogResult, MessageLevel};
+
+#[derive(Clone, Copy, Debug)]
+pub enum FileDialogKind {
+    OpenFile,
+    OpenFiles,
+    SaveFile,
+    OpenFolder,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FileDialogOptions {
+    pub title: String,
+    pub dir: Option<String>,
+    pub name: Option<String>,
+    /// "Images:png,jpg;Text:txt"
+    pub filters: Option<String>,
+}
+
+impl FileDialogOptions {
+    pub fn builder(&self) -> AsyncFileDialog {
+        let mut d = AsyncFileDialog::new().set_title(&self.title);
+        if let Some(p) = &self.dir { d = d.set_directory(PathBuf::from(p)); }
+        if let Some(n) = &self.name { d = d.set_file_name(n); }
+        if let Some(f) = &self.filters {
+             for group in f.split(';') {
+                 let parts: Vec<&str> = group.split(':').collect();
+                 if parts.len() == 2 {
+                     let exts: Vec<&str> = parts[1].split(',').collect();
+                     d = d.add_filter(parts[0], &exts);
+                 }
+             }
+        }
+        d
+    }
+}
+
+/// Awaits the dialog and returns the chosen path(s), `None` on cancel.
+/// Single-selection kinds yield at most one entry.
+pub async fn show_file_dialog(kind: FileDialogKind, dialog: AsyncFileDialog) -> Option<Vec<String>> {
+    let picked = match kind {
+        FileDialogKind::OpenFile => dialog.pick_file().await.map(|h| vec![h]),
+        FileDialogKind::OpenFiles => dialog.pick_files().await,
+        FileDialogKind::SaveFile => dialog.save_file().await.map(|h| vec![h]),
+        FileDialogKind::OpenFolder => dialog.pick_folder().await.map(|h| vec![h]),
+    };
+    picked.map(|handles| handles.into_iter().map(path_string).collect())
+}
+
+/// JSON result for the bridge: a list for multi-select, a path string otherwise, `null` on cancel.
+pub fn file_dialog_json(kind: FileDialogKind, res: Option<Vec<String>>) -> String {
+    let value = match (kind, res) {
+        (_, None) => serde_json::Value::Null,
+        (FileDialogKind::OpenFiles, Some(paths)) => serde_json::json!(paths),
+        (_, Some(paths)) => paths.into_iter().next().map(serde_json::Value::String).unwrap_or_default(),
+    };
+    value.to_string()
+}
+
+pub fn path_string(handle: FileHandle) -> String {
+    handle.path().to_string_lossy().to_string()
+}
+
+pub fn parse_level(level: &str) -> MessageLevel {
+    match level {
+        "error" => MessageLevel::Error,
+        "warning" => MessageLevel::Warning,
+        _ => MessageLevel::Info,
+    }
+}
+
+/// "ok", "okcancel", "yesno", "yesnocancel", or up to three custom labels
+/// separated by `|` (e.g. "Save|Don't Save|Cancel").
+pub fn parse_buttons(buttons: &str) -> MessageButtons {
+    match buttons.to_ascii_lowercase().as_str() {
+        "ok" => MessageButtons::Ok,
+        "okcancel" => MessageButtons::OkCancel,
+        "yesno" => MessageButtons::YesNo,
+        "yesnocancel" => MessageButtons::YesNoCancel,
+        _ => {
+            let labels: Vec<String> = buttons.split('|').map(|l| l.trim().to_string()).collect();
+            match labels.as_slice() {
+                [a] => MessageButtons::OkCustom(a.clone()),
+                [a, b] => MessageButtons::OkCancelCustom(a.clone(), b.clone()),
+                [a, b, c, ..] => MessageButtons::YesNoCancelCustom(a.clone(), b.clone(), c.clone()),
+                [] => MessageButtons::Ok,
+            }
+        }
+    }
+}
+
+/// Name of the pressed button: "ok", "cancel", "yes", "no", or the custom label.
+pub fn result_label(res: MessageDialogResult) -> String {
+    match res {
+        MessageDialogResult::Ok => "ok".to_string(),
+        MessageDialogResult::Cancel => "cancel".to_string(),
+        MessageDialogResult::Yes => "yes".to_string(),
+        MessageDialogResult::No => "no".to_string(),
+        MessageDialogResult::Custom(label) => label,
+    }
+}
+
+/// Shows a single-line text input box. Returns `None` when the user cancels.
+///
+/// rfd has no text prompt, so this shells out to the platform's own input box
+/// (VisualBasic InputBox, AppleScript `display dialog`, zenity/kdialog).
+pub fn prompt(title: &str, label: &str, default: &str) -> Option<String> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        let script = format!(
+            "[Console]::OutputEncoding = [Text.Encoding]::UTF8; Add-Type -AssemblyName Microsoft.VisualBasic; [Microsoft.VisualBasic.Interaction]::InputBox('{}', '{}', '{}')",
+            ps_quote(label), ps_quote(title), ps_quote(default)
+        );
+        let out = Command::new("powershell")
+            .args(["-NoProfile", "-Command", &script])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .ok()?;
+        // InputBox reports Cancel as an empty string
+        let text = String::from_utf8_lossy(&out.stdout).trim_end_matches(['\r', '\n']).to_string();
+        if text.is_empty() { None } else { Some(text) }
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let script = format!(
+            "text returned of (display dialog \"{}\" with title \"{}\" default answer \"{}\")",
+            as_quote(label), as_quote(title), as_quote(default)
+        );
+        let out = Command::new("osascript").args(["-e", &script]).output().ok()?;
+        if !out.status.success() { return None; } // Cancel exits with -128
+        Some(String::from_utf8_lossy(&out.stdout).trim_end_matches('\n').to_string())
+    }
+    #[cfg(target_os = "linux")]
+    {
+        let out = Command::new("zenity")
+            .args(["--entry", "--title", title, "--text", label, "--entry-text", default])
+            .output()
+            .or_else(|_| Command::new("kdialog").args(["--title", title, "--inputbox", label, default]).output())
+            .ok()?;
+        if !out.status.success() { return None; }
+        Some(String::from_utf8_lossy(&out.stdout).trim_end_matches('\n').to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn ps_quote(s: &str) -> String {
+    s.replace('\'', "''")
+}
+
+#[cfg(target_os = "macos")]
+fn as_quote(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
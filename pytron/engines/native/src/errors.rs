@@ -0,0 +1,18 @@
+use pyo3::create_exception;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+
+// Subclass RuntimeError so existing `except RuntimeError` handlers keep working.
+create_exception!(pytron_native, PytronNativeError, PyRuntimeError, "Base class for native engine failures.");
+create_exception!(pytron_native, PytronWindowError, PytronNativeError, "The window or webview could not be created.");
+create_exception!(pytron_native, PytronIPCError, PytronNativeError, "The IPC pipe could not be opened or written.");
+create_exception!(pytron_native, PytronProtocolError, PytronNativeError, "The custom protocol (asset serving) could not be set up.");
+
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("PytronNativeError", py.get_type::<PytronNativeError>())?;
+    m.add("PytronWindowError", py.get_type::<PytronWindowError>())?;
+    m.add("PytronIPCError", py.get_type::<PytronIPCError>())?;
+    m.add("PytronProtocolError", py.get_type::<PytronProtocolError>())?;
+    Ok(())
+}
@@ -1,31 +1,84 @@
 use pyo3::prelude::*;
+use std::sync::mpsc::Sender;
+use crate::dialogs::{FileDialogKind, FileDialogOptions};
+use crate::storage::{BrowsingData, CookieCommand};
+use tray_icon::menu::accelerator::{Code, Modifiers};
 
 pub enum UserEvent {
     Eval(String),
-    Bind(String, PyObject),   
+    Bind(String, PyObject, bool), // Name, func, pass_context
     Dispatch(PyObject, String, String), // Func, Seq, MethodName
     DispatchData(PyObject, String, String, String), // Func, Seq, Args, MethodName
-    CallPython(PyObject, String, String, String), 
+    CallPython(PyObject, String, String, String, String), // Func, Seq, Args, MethodName, Origin
     
     Return(String, i32, String),
+    StreamChunk(String, String), // Seq, JSON chunk
+    StreamEnd(String), // Seq
     SetTitle(String),
     SetSize(i32, i32, u32),
     Navigate(String),
+    NavigateWithHeaders(String, Vec<(String, String)>), // Url, headers
     Quit,
+    RequestQuit, // Graceful: runs pytron_on_quit, flushes queued events, then Quit
     Minimize,
     SetMaximized(bool),
+    ToggleMaximize,
     SetVisible(bool),
+    SetSuspended(bool),
+    SetAutoSuspend(Option<u64>), // Seconds, None disables
+    AutoSuspend(u64), // Visibility epoch the timer was armed for
     DragWindow,
+    DragResize(tao::window::ResizeDirection),
     SetAlwaysOnTop(bool),
     Notification(String, String), // Title, Message
     TaskbarProgress(i32, i32, i32), // State, Value, Max
     SetResizable(bool),
+    SetAspectRatio(Option<(u32, u32)>), // Width, height; None lifts the constraint
     SetFullscreen(bool),
+    SetFullscreenEx(bool, Option<usize>, bool), // Enabled, monitor index, exclusive
     CenterWindow,
     SetPreventClose(bool),
-    CreateTray(String, String), // icon_path, tooltip
+    SetMinimizeToTray(bool),
+    SetCloseToTray(bool),
+    CreateTray(String, String, Option<String>), // icon_path, tooltip, menu JSON (None = default items)
+    SetTrayAnimation(Vec<String>, u64), // Frame paths, interval in ms
+    StopTrayAnimation,
+    TrayAnimationTick,
     TrayMenuClick(String), // id
+    TrayIconClick(f64, f64), // x, y (physical screen coords)
+    TrayIconDoubleClick(f64, f64), // x, y
     SetDecorations(bool),
-    MessageBox(String, String, String, String), // Title, Message, Level, Seq
+    SetShadow(bool),
+    SetIgnoreCursorEvents(bool),
+    SetCornerPreference(i32), // DWM_WINDOW_CORNER_PREFERENCE
+    MessageBox(String, String, String, String, Option<String>), // Title, Message, Level, Seq, Buttons
+    FileDialog(FileDialogKind, FileDialogOptions, String), // Kind, Options, Seq
     OpenExternal(String),
+    InsertCss(String, String), // Style element id, CSS
+    RemoveCss(String), // Style element id
+    ShowAbout(Option<String>, Option<String>, Option<String>), // Name, version, author
+    Log(String), // Message from window.pytron_log
+    ShowContextMenu(String, i32, i32), // Menu JSON, X, Y (logical, window-relative)
+    MenuClick(String), // id
+    SetBadge(Option<String>), // Label, None clears
+    ClearBrowsingData(BrowsingData, Sender<bool>), // Kind, completion
+    GetUrl(Sender<String>),
+    GetWebviewVersion(Sender<String>),
+    GetSize(Sender<(f64, f64)>), // Logical inner size
+    Print(bool), // Force a light color scheme while printing
+    PowerChanged(crate::power::PowerState),
+    ConnectivityChanged(bool),
+    PrintDone,
+    Cookies(CookieCommand),
+    PageLoaded(String), // Url
+    RendererCrashed(String), // Reason
+    SetHangWatchdog(Option<(u64, u32)>), // Ping interval ms, misses before reporting; None stops it
+    HangPing,
+    HangPong,
+    AssetsChanged(Vec<String>), // Paths relative to the asset root
+    Emit(String, String), // Event name, JSON detail
+    Custom(String), // JSON payload for pytron_on_custom
+    RegisterAccelerator(Modifiers, Code, String), // Shortcut, id
+    UnregisterAccelerator(String), // id
+    KeyDown(Modifiers, Code), // Forwarded from the page's capture-phase keydown listener
 }
@@ -1,25 +1,56 @@
 use pyo3::prelude::*;
-use std::sync::{Arc, Mutex};
+use crate::errors::PytronIPCError;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
 
 #[cfg(target_os = "windows")]
 use windows::{
     core::PCWSTR,
-    Win32::Foundation::{HANDLE, CloseHandle},
-    Win32::System::Pipes::{CreateNamedPipeW, ConnectNamedPipe, NAMED_PIPE_MODE},
-    Win32::Storage::FileSystem::{WriteFile, ReadFile, FILE_FLAGS_AND_ATTRIBUTES},
+    Win32::Foundation::{HANDLE, CloseHandle, GetLastError, ERROR_ACCESS_DENIED, ERROR_FILE_NOT_FOUND},
+    Win32::System::Pipes::{CreateNamedPipeW, ConnectNamedPipe, WaitNamedPipeW, NAMED_PIPE_MODE},
+    Win32::Storage::FileSystem::{WriteFile, ReadFile, FlushFileBuffers, FILE_FLAGS_AND_ATTRIBUTES, FILE_FLAG_FIRST_PIPE_INSTANCE},
 };
 
 #[cfg(not(target_os = "windows"))]
 use std::os::unix::net::{UnixListener, UnixStream};
-#[cfg(not(target_os = "windows"))]
-use std::io::{Read, Write};
+use std::io::{self, Read, Write};
 
 const PIPE_ACCESS_DUPLEX: u32 = 0x00000003;
 const PIPE_TYPE_BYTE: u32 = 0x00000000;
 const PIPE_READMODE_BYTE: u32 = 0x00000000;
 const PIPE_WAIT: u32 = 0x00000000;
+/// Default kernel buffer per direction (matches the `listen` defaults); raise it for large, frequent frames.
+#[cfg(not(target_os = "windows"))]
+const DEFAULT_BUFFER_SIZE: u32 = 64 * 1024;
+
+// Frame: magic "PT" (2) | version (1) | type (1) | body length u32 LE (4) | body
+const FRAME_MAGIC: [u8; 2] = *b"PT";
+const FRAME_VERSION: u8 = 1;
+const FRAME_HEADER_LEN: usize = 8;
+const FRAME_TEXT: u8 = 0;
+const FRAME_BINARY: u8 = 1;
+const FRAME_CONTROL: u8 = 2; // Keepalive: "ping" is answered with "pong"
+// Give up instead of scanning a corrupt stream forever
+const MAX_RESYNC_BYTES: usize = 64 * 1024;
+// Bounds the allocation a corrupt or hostile length field can ask for
+const MAX_FRAME_LEN: usize = 256 * 1024 * 1024;
+// Keeps the Unix socket path well under the 104/108-byte sun_path limit
+const MAX_UID_LEN: usize = 64;
 
+/// Channel naming: a `uid` of ASCII letters, digits and dashes (at most 64) maps to
+/// `\\.\pipe\pytron-{uid}-in` / `-out` on Windows and `/tmp/pytron-{uid}.sock` elsewhere,
+/// where the listener also holds a `flock` on `/tmp/pytron-{uid}.sock.lock`.
+/// Without a uid, `listen` picks a random one; either way it returns the resulting path.
+///
+/// Concurrency model:
+/// - One reader thread (`start_read_loop`) owns the read side and calls `callback` with the GIL held.
+///   Replies to `request` are routed to the waiting caller instead and never reach `callback`.
+/// - Every outgoing frame (`send`, pongs, keepalive pings) goes through a queue drained by one
+///   writer thread, so `send` never blocks on the pipe. It is safe to call from any thread,
+///   including from inside `callback` to reply immediately; frames keep their send order.
 #[pyclass]
 pub struct ChromeIPC {
     #[cfg(target_os = "windows")]
@@ -29,9 +60,20 @@ pub struct ChromeIPC {
     
     #[cfg(not(target_os = "windows"))]
     stream: Arc<Mutex<Option<UnixStream>>>,
+    #[cfg(not(target_os = "windows"))]
+    listener: Mutex<Option<UnixListener>>, // Bound in `listen`, so a second listener on the same uid fails there
+    #[cfg(not(target_os = "windows"))]
+    lock: Option<std::fs::File>, // `flock`ed `<socket>.lock`, held for as long as this channel lives
     
     connected: Arc<Mutex<bool>>,
     pipe_path: String,
+    #[cfg(not(target_os = "windows"))]
+    buffer_sizes: (u32, u32), // (send, recv) in bytes, applied once the peer connects
+    last_seen: Arc<Mutex<Instant>>, // Any frame from the peer counts as a sign of life
+    on_disconnect: Arc<Mutex<Option<PyObject>>>,
+    outbox: Mutex<Option<mpsc::Sender<(Vec<u8>, bool)>>>, // (frame, flush after writing); started on first use
+    pending: Arc<Mutex<HashMap<u64, mpsc::Sender<String>>>>, // `request` calls awaiting a reply, by id
+    next_request_id: AtomicU64,
 }
 
 #[pymethods]
@@ -45,15 +87,42 @@ impl ChromeIPC {
             handle_out: Arc::new(Mutex::new(None)),
             #[cfg(not(target_os = "windows"))]
             stream: Arc::new(Mutex::new(None)),
+            #[cfg(not(target_os = "windows"))]
+            listener: Mutex::new(None),
+            #[cfg(not(target_os = "windows"))]
+            lock: None,
             connected: Arc::new(Mutex::new(false)),
             pipe_path: String::new(),
+            #[cfg(not(target_os = "windows"))]
+            buffer_sizes: (DEFAULT_BUFFER_SIZE, DEFAULT_BUFFER_SIZE),
+            last_seen: Arc::new(Mutex::new(Instant::now())),
+            on_disconnect: Arc::new(Mutex::new(None)),
+            outbox: Mutex::new(None),
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            next_request_id: AtomicU64::new(1),
         }
     }
 
-    fn listen(&mut self, uid: String) -> PyResult<String> {
+    /// `send_buffer` / `recv_buffer` size the pipe (Windows) or socket (Unix) buffers in bytes.
+    /// A `uid` that's None or empty is replaced by a random one; an invalid one raises ValueError.
+    #[pyo3(signature = (uid=None, send_buffer=65536, recv_buffer=65536))]
+    fn listen(&mut self, uid: Option<String>, send_buffer: u32, recv_buffer: u32) -> PyResult<String> {
+        if send_buffer == 0 || recv_buffer == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>("buffer sizes must be positive"));
+        }
+        let uid = match uid.filter(|u| !u.is_empty()) {
+            Some(uid) => {
+                check_uid(&uid)?;
+                uid
+            }
+            None => crate::protocol::generate_token(),
+        };
         #[cfg(target_os = "windows")]
         {
-            let base_path = format!(r#"\\.\pipe\pytron-{}"#, uid);
+            if !pipe_free(&uid) {
+                return Err(pipe_in_use(&uid));
+            }
+            let base_path = channel_path(&uid);
             let path_in = format!("{}-in", base_path);
             let path_out = format!("{}-out", base_path);
             
@@ -65,28 +134,32 @@ impl ChromeIPC {
             let h_in = unsafe {
                 CreateNamedPipeW(
                     PCWSTR(w_path_in.as_ptr()),
-                    FILE_FLAGS_AND_ATTRIBUTES(PIPE_ACCESS_DUPLEX),
+                    FILE_FLAGS_AND_ATTRIBUTES(PIPE_ACCESS_DUPLEX) | FILE_FLAG_FIRST_PIPE_INSTANCE,
                     NAMED_PIPE_MODE(PIPE_TYPE_BYTE | PIPE_WAIT),
                     1,
-                    65536,
-                    65536,
+                    send_buffer,
+                    send_buffer,
                     0,
                     None,
                 )
             };
 
             if h_in.is_invalid() {
-                return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Failed to create IN pipe"));
+                // FILE_FLAG_FIRST_PIPE_INSTANCE: someone created it between the check and now
+                if unsafe { GetLastError() } == ERROR_ACCESS_DENIED {
+                    return Err(pipe_in_use(&uid));
+                }
+                return Err(PytronIPCError::new_err("Failed to create IN pipe"));
             }
 
             let h_out = unsafe {
                 CreateNamedPipeW(
                     PCWSTR(w_path_out.as_ptr()),
-                    FILE_FLAGS_AND_ATTRIBUTES(PIPE_ACCESS_DUPLEX),
+                    FILE_FLAGS_AND_ATTRIBUTES(PIPE_ACCESS_DUPLEX) | FILE_FLAG_FIRST_PIPE_INSTANCE,
                     NAMED_PIPE_MODE(PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT),
                     1,
-                    65536,
-                    65536,
+                    recv_buffer,
+                    recv_buffer,
                     0,
                     None,
                 )
@@ -94,22 +167,36 @@ impl ChromeIPC {
 
             if h_out.is_invalid() {
                 unsafe { let _ = CloseHandle(h_in); }
-                return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Failed to create OUT pipe"));
+                return Err(PytronIPCError::new_err("Failed to create OUT pipe"));
             }
 
             *self.handle_in.lock().unwrap() = Some(h_in.0 as usize);
             *self.handle_out.lock().unwrap() = Some(h_out.0 as usize);
 
+            log::info!("Listening on {}", base_path);
             Ok(base_path)
         }
 
         #[cfg(not(target_os = "windows"))]
         {
-            let path = format!("/tmp/pytron-{}.sock", uid);
-            self.pipe_path = path.clone();
+            let path = channel_path(&uid);
+            let lock = lock_channel(&path).map_err(|e| PytronIPCError::new_err(format!("Failed to lock {}: {}", path, e)))?;
+            let Some(lock) = lock else {
+                return Err(pipe_in_use(&uid));
+            };
+            // We hold the lock, so any leftover socket file is stale
             if std::path::Path::new(&path).exists() {
                 let _ = std::fs::remove_file(&path);
             }
+            let listener = UnixListener::bind(&path).map_err(|e| match e.kind() {
+                io::ErrorKind::AddrInUse => pipe_in_use(&uid),
+                _ => PytronIPCError::new_err(format!("Failed to bind {}: {}", path, e)),
+            })?;
+            *self.listener.lock().unwrap() = Some(listener);
+            self.lock = Some(lock);
+            self.pipe_path = path.clone();
+            self.buffer_sizes = (send_buffer, recv_buffer);
+            log::info!("Listening on {}", path);
             Ok(path)
         }
     }
@@ -117,16 +204,14 @@ impl ChromeIPC {
     fn wait_for_connection(&self, py: Python<'_>) -> PyResult<()> {
         #[cfg(target_os = "windows")]
         {
-            let h_in_val = self.handle_in.lock().unwrap().ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Pipes not initialized"))?;
-            let h_out_val = self.handle_out.lock().unwrap().ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Pipes not initialized"))?;
-            
-            let h_in = HANDLE(h_in_val as _);
-            let h_out = HANDLE(h_out_val as _);
+            let h_in_val = self.handle_in.lock().unwrap().ok_or_else(|| PytronIPCError::new_err("Pipes not initialized"))?;
+            let h_out_val = self.handle_out.lock().unwrap().ok_or_else(|| PytronIPCError::new_err("Pipes not initialized"))?;
 
             // CRITICAL: Release GIL during blocking Win32 call
+            // HANDLE wraps a raw pointer (not Send), so only the values cross into the closure
             py.allow_threads(move || unsafe {
-                let _ = ConnectNamedPipe(h_in, None);
-                let _ = ConnectNamedPipe(h_out, None);
+                let _ = ConnectNamedPipe(HANDLE(h_in_val as _), None);
+                let _ = ConnectNamedPipe(HANDLE(h_out_val as _), None);
             });
 
             *self.connected.lock().unwrap() = true;
@@ -135,116 +220,439 @@ impl ChromeIPC {
 
         #[cfg(not(target_os = "windows"))]
         {
-            let path = self.pipe_path.clone();
-            let stream = py.allow_threads(move || {
-                let listener = UnixListener::bind(&path).unwrap();
-                let (s, _) = listener.accept().unwrap();
-                s
-            });
+            let listener = self.listener.lock().unwrap().take().ok_or_else(|| PytronIPCError::new_err("Socket not initialized"))?;
+            let (stream, _) = py
+                .allow_threads(move || listener.accept())
+                .map_err(|e| PytronIPCError::new_err(format!("Failed to accept on {}: {}", self.pipe_path, e)))?;
+            set_socket_buffers(&stream, self.buffer_sizes);
             *self.stream.lock().unwrap() = Some(stream);
             *self.connected.lock().unwrap() = true;
             Ok(())
         }
     }
 
-    fn start_read_loop(&self, callback: PyObject) -> PyResult<()> {
+    /// `on_disconnect()` runs once, when the peer closes the pipe or stops answering keepalives.
+    #[pyo3(signature = (callback, on_disconnect=None))]
+    fn start_read_loop(&self, callback: PyObject, on_disconnect: Option<PyObject>) -> PyResult<()> {
+        *self.on_disconnect.lock().unwrap() = on_disconnect;
+        *self.last_seen.lock().unwrap() = Instant::now();
         let connected = self.connected.clone();
+        let on_disconnect = self.on_disconnect.clone();
+        let last_seen = self.last_seen.clone();
+        let outbox = self.outbox();
+        let pending = self.pending.clone();
         
         #[cfg(target_os = "windows")]
-        let h_out_val = self.handle_out.lock().unwrap().ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Pipes not initialized"))?;
+        let h_out_val = self.handle_out.lock().unwrap().ok_or_else(|| PytronIPCError::new_err("Pipes not initialized"))?;
         
         #[cfg(not(target_os = "windows"))]
         let mut stream_read = self.stream.lock().unwrap().as_ref().map(|s| s.try_clone().unwrap());
 
         thread::spawn(move || {
             #[cfg(target_os = "windows")]
-            let h_out = HANDLE(h_out_val as _);
+            let mut reader = Some(PipeReader(HANDLE(h_out_val as _)));
+            #[cfg(not(target_os = "windows"))]
+            let mut reader = stream_read.take();
 
             while *connected.lock().unwrap() {
-                #[cfg(target_os = "windows")]
-                {
-                    let mut header = [0u8; 4];
-                    let mut bytes_read = 0u32;
-                    unsafe {
-                        let res = ReadFile(h_out, Some(&mut header), Some(&mut bytes_read), None);
-                        if res.is_err() || bytes_read != 4 { break; }
-                    }
-                    let msg_len = u32::from_le_bytes(header) as usize;
-
-                    let mut body = vec![0u8; msg_len];
-                    unsafe {
-                        let res = ReadFile(h_out, Some(&mut body), Some(&mut bytes_read), None);
-                        if res.is_err() || bytes_read as usize != msg_len { break; }
-                    }
-
-                    if let Ok(msg_str) = String::from_utf8(body) {
-                        Python::with_gil(|py| {
-                            let _ = callback.call1(py, (msg_str,));
-                        });
+                let Some(reader) = reader.as_mut() else { break };
+                let (kind, body) = match read_frame(reader) {
+                    Ok(frame) => frame,
+                    Err(e) => {
+                        if e.kind() == io::ErrorKind::InvalidData {
+                            log::error!("IPC protocol error: {}", e);
+                        }
+                        break;
                     }
-                }
-
-                #[cfg(not(target_os = "windows"))]
-                {
-                    if let Some(mut stream) = stream_read.as_mut() {
-                        let mut header = [0u8; 4];
-                        if stream.read_exact(&mut header).is_err() { break; }
-                        let msg_len = u32::from_le_bytes(header) as usize;
-                        let mut body = vec![0u8; msg_len];
-                        if stream.read_exact(&mut body).is_err() { break; }
-                        
+                };
+                *last_seen.lock().unwrap() = Instant::now();
+                match kind {
+                    FRAME_TEXT => {
                         if let Ok(msg_str) = String::from_utf8(body) {
+                            if resolve_reply(&pending, &msg_str) {
+                                continue;
+                            }
                             Python::with_gil(|py| {
                                 let _ = callback.call1(py, (msg_str,));
                             });
                         }
-                    } else { break; }
+                    }
+                    FRAME_BINARY => {
+                        Python::with_gil(|py| {
+                            let _ = callback.call1(py, (pyo3::types::PyBytes::new(py, &body),));
+                        });
+                    }
+                    _ => {
+                        if body == b"ping" {
+                            let _ = outbox.send((encode_frame(FRAME_CONTROL, b"pong"), false));
+                        }
+                    }
                 }
             }
-            *connected.lock().unwrap() = false;
+            log::debug!("Read loop ended, peer disconnected");
+            // Dropping the senders wakes every waiting `request` with an error
+            pending.lock().unwrap().clear();
+            mark_disconnected(&connected, &on_disconnect);
         });
 
         Ok(())
     }
 
-    fn send(&self, py: Python<'_>, data: String) -> PyResult<()> {
+    /// Pings the peer every `interval` seconds. If nothing arrives for `timeout`
+    /// seconds (default 3 intervals) the connection is dropped and `on_disconnect` runs.
+    #[pyo3(signature = (interval, timeout=None))]
+    fn start_keepalive(&self, interval: f64, timeout: Option<f64>) -> PyResult<()> {
+        if interval <= 0.0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>("interval must be positive"));
+        }
+        let interval = Duration::from_secs_f64(interval);
+        let timeout = timeout.map(Duration::from_secs_f64).unwrap_or(interval * 3);
+        let connected = self.connected.clone();
+        let on_disconnect = self.on_disconnect.clone();
+        let last_seen = self.last_seen.clone();
+        let outbox = self.outbox();
+
+        thread::spawn(move || loop {
+            thread::sleep(interval);
+            if !*connected.lock().unwrap() {
+                break;
+            }
+            let silent_for = last_seen.lock().unwrap().elapsed();
+            if silent_for > timeout {
+                log::warn!("IPC peer silent for {:.1}s, assuming it hung", silent_for.as_secs_f64());
+                mark_disconnected(&connected, &on_disconnect);
+                break;
+            }
+            let _ = outbox.send((encode_frame(FRAME_CONTROL, b"ping"), false));
+        });
+        Ok(())
+    }
+
+    /// Queues `data` and returns without waiting for the peer to read it. `flush` makes the
+    /// writer wait until the peer has drained the pipe (Windows) before the next frame goes out.
+    #[pyo3(signature = (data, flush=false))]
+    fn send(&self, data: String, flush: bool) -> PyResult<()> {
         if !*self.connected.lock().unwrap() {
-            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Not connected"));
+            return Err(PytronIPCError::new_err("Not connected"));
         }
+        self.outbox()
+            .send((encode_frame(FRAME_TEXT, data.as_bytes()), flush))
+            .map_err(|_| PytronIPCError::new_err("IPC writer stopped"))
+    }
 
-        let body = data.into_bytes();
-        let msg_len = body.len() as u32;
-        let header = msg_len.to_le_bytes();
-        let mut full_msg = Vec::with_capacity(4 + body.len());
-        full_msg.extend_from_slice(&header);
-        full_msg.extend_from_slice(&body);
+    /// Sends `data` (a JSON object) tagged with a `request_id` and blocks until the peer answers
+    /// with `{"type": "reply", "request_id": <id>, "payload": ...}`. Returns the payload as JSON.
+    fn request(&self, py: Python<'_>, data: String, timeout_ms: u64) -> PyResult<String> {
+        if !*self.connected.lock().unwrap() {
+            return Err(PytronIPCError::new_err("Not connected"));
+        }
+        let mut message: serde_json::Value = serde_json::from_str(&data)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("request data is not JSON: {}", e)))?;
+        let Some(fields) = message.as_object_mut() else {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>("request data must be a JSON object"));
+        };
+        let id = self.next_request_id.fetch_add(1, Ordering::SeqCst);
+        fields.insert("request_id".into(), id.into());
 
-        #[cfg(target_os = "windows")]
-        {
-            let h_in_val = self.handle_in.lock().unwrap().ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Pipe not connected"))?;
-            let h_in = HANDLE(h_in_val as _);
-            py.allow_threads(move || {
-                let mut bytes_written = 0u32;
-                unsafe {
-                    let _ = WriteFile(h_in, Some(&full_msg), Some(&mut bytes_written), None);
+        let (tx, rx) = mpsc::channel();
+        self.pending.lock().unwrap().insert(id, tx);
+        let sent = self.outbox().send((encode_frame(FRAME_TEXT, message.to_string().as_bytes()), false));
+        if sent.is_err() {
+            self.pending.lock().unwrap().remove(&id);
+            return Err(PytronIPCError::new_err("IPC writer stopped"));
+        }
+
+        let reply = py.allow_threads(|| rx.recv_timeout(Duration::from_millis(timeout_ms)));
+        self.pending.lock().unwrap().remove(&id);
+        match reply {
+            Ok(payload) => Ok(payload),
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(PytronIPCError::new_err(format!("No reply to request {} within {} ms", id, timeout_ms))),
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(PytronIPCError::new_err("Disconnected before the reply arrived")),
+        }
+    }
+}
+
+impl ChromeIPC {
+    fn outbox(&self) -> mpsc::Sender<(Vec<u8>, bool)> {
+        let mut outbox = self.outbox.lock().unwrap();
+        outbox.get_or_insert_with(|| {
+            let writer = FrameWriter {
+                #[cfg(target_os = "windows")]
+                handle_in: self.handle_in.clone(),
+                #[cfg(not(target_os = "windows"))]
+                stream: self.stream.clone(),
+            };
+            let (tx, rx) = mpsc::channel::<(Vec<u8>, bool)>();
+            thread::spawn(move || {
+                for (frame, flush) in rx {
+                    if let Err(e) = writer.write(&frame, flush) {
+                        log::warn!("IPC write failed: {}", e);
+                    }
                 }
             });
+            tx
+        }).clone()
+    }
+}
+
+/// Write side of the connection. Only the outbox thread writes through it.
+struct FrameWriter {
+    #[cfg(target_os = "windows")]
+    handle_in: Arc<Mutex<Option<usize>>>,
+    #[cfg(not(target_os = "windows"))]
+    stream: Arc<Mutex<Option<UnixStream>>>,
+}
+
+impl FrameWriter {
+    /// Writes the whole frame; a short write would leave the peer mid-frame and desync the stream.
+    fn write(&self, frame: &[u8], flush: bool) -> io::Result<()> {
+        #[cfg(target_os = "windows")]
+        {
+            let lock = self.handle_in.lock().unwrap();
+            let mut pipe = PipeWriter(HANDLE(lock.ok_or(io::ErrorKind::NotConnected)? as _));
+            pipe.write_all(frame)?;
+            if flush {
+                pipe.flush()?;
+            }
             Ok(())
         }
 
         #[cfg(not(target_os = "windows"))]
         {
             let mut lock = self.stream.lock().unwrap();
-            if let Some(mut stream) = lock.as_mut() {
-                py.allow_threads(move || {
-                    let _ = stream.write_all(&full_msg);
-                });
+            let stream = lock.as_mut().ok_or(io::ErrorKind::NotConnected)?;
+            stream.write_all(frame)?;
+            if flush {
+                stream.flush()?;
             }
             Ok(())
         }
     }
 }
 
+/// Hands a `reply` message to the `request` waiting on its id; late replies to timed-out
+/// requests are dropped. False when `msg` isn't a reply.
+fn resolve_reply(pending: &Mutex<HashMap<u64, mpsc::Sender<String>>>, msg: &str) -> bool {
+    // Cheap filter so ordinary traffic isn't parsed twice
+    if !msg.contains("\"request_id\"") {
+        return false;
+    }
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(msg) else { return false };
+    if value.get("type").and_then(|t| t.as_str()) != Some("reply") {
+        return false;
+    }
+    let Some(id) = value.get("request_id").and_then(|id| id.as_u64()) else { return false };
+    match pending.lock().unwrap().remove(&id) {
+        Some(tx) => {
+            let payload = value.get("payload").cloned().unwrap_or(serde_json::Value::Null);
+            let _ = tx.send(payload.to_string());
+        }
+        None => log::debug!("Dropping reply to unknown or expired request {}", id),
+    }
+    true
+}
+
+/// Flips `connected` off and runs the callback, only for whoever gets there first.
+fn mark_disconnected(connected: &Mutex<bool>, on_disconnect: &Mutex<Option<PyObject>>) {
+    let was_connected = std::mem::replace(&mut *connected.lock().unwrap(), false);
+    if !was_connected {
+        return;
+    }
+    let callback = on_disconnect.lock().unwrap().take();
+    if let Some(f) = callback {
+        Python::with_gil(|py| {
+            if let Err(e) = f.call0(py) {
+                log::warn!("on_disconnect failed: {}", e);
+            }
+        });
+    }
+}
+
+/// Best effort: the kernel may clamp (Linux caps at net.core.wmem_max / rmem_max).
+#[cfg(not(target_os = "windows"))]
+fn set_socket_buffers(stream: &UnixStream, (send, recv): (u32, u32)) {
+    use std::os::unix::io::AsRawFd;
+    for (option, size) in [(libc::SO_SNDBUF, send), (libc::SO_RCVBUF, recv)] {
+        let size = size.min(i32::MAX as u32) as libc::c_int;
+        let ok = unsafe {
+            libc::setsockopt(
+                stream.as_raw_fd(),
+                libc::SOL_SOCKET,
+                option,
+                &size as *const libc::c_int as *const libc::c_void,
+                std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+            )
+        } == 0;
+        if !ok {
+            log::warn!("Could not set IPC socket buffer to {} bytes: {}", size, io::Error::last_os_error());
+        }
+    }
+}
+
+/// False when another process is already serving `uid` (e.g. a second app instance).
+/// A stale socket file with no listener counts as available; `listen` replaces it.
+#[pyfunction]
+pub fn is_pipe_available(uid: &str) -> PyResult<bool> {
+    check_uid(uid)?;
+    Ok(pipe_free(uid))
+}
+
+/// Letters, digits and dashes only, so the uid can't escape the pipe namespace or the temp dir.
+fn check_uid(uid: &str) -> PyResult<()> {
+    if uid.is_empty() || uid.len() > MAX_UID_LEN || !uid.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-') {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "invalid IPC uid {:?}: use 1-{} ASCII letters, digits or dashes",
+            uid, MAX_UID_LEN
+        )));
+    }
+    Ok(())
+}
+
+/// Windows: the pipe base name (the pair adds `-in` / `-out`). Elsewhere: the socket file.
+fn channel_path(uid: &str) -> String {
+    if cfg!(target_os = "windows") {
+        format!(r#"\\.\pipe\pytron-{}"#, uid)
+    } else {
+        format!("/tmp/pytron-{}.sock", uid)
+    }
+}
+
+fn pipe_free(uid: &str) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        let base = channel_path(uid);
+        [format!("{}-in", base), format!("{}-out", base)].iter().all(|path| unsafe {
+            let w_path = encode_wide(path);
+            !WaitNamedPipeW(PCWSTR(w_path.as_ptr()), 1).as_bool() && GetLastError() == ERROR_FILE_NOT_FOUND
+        })
+    }
+
+    // Connecting would be accepted as the running instance's peer, so ask the lock instead
+    #[cfg(not(target_os = "windows"))]
+    {
+        matches!(lock_channel(&channel_path(uid)), Ok(Some(_)))
+    }
+}
+
+/// Takes the advisory lock next to the socket. `None` while another listener holds it; the
+/// kernel drops it when that process exits, so a crashed instance never blocks the uid.
+#[cfg(not(target_os = "windows"))]
+fn lock_channel(socket_path: &str) -> io::Result<Option<std::fs::File>> {
+    use std::os::unix::io::AsRawFd;
+
+    let file = std::fs::OpenOptions::new().create(true).write(true).truncate(false).open(format!("{}.lock", socket_path))?;
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } == 0 {
+        return Ok(Some(file));
+    }
+    let e = io::Error::last_os_error();
+    if e.kind() == io::ErrorKind::WouldBlock {
+        Ok(None)
+    } else {
+        Err(e)
+    }
+}
+
+fn pipe_in_use(uid: &str) -> PyErr {
+    PytronIPCError::new_err(format!(
+        "IPC pipe 'pytron-{}' is already in use by another process (is another instance running?)",
+        uid
+    ))
+}
+
+fn encode_frame(kind: u8, body: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + body.len());
+    frame.extend_from_slice(&FRAME_MAGIC);
+    frame.push(FRAME_VERSION);
+    frame.push(kind);
+    frame.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    frame.extend_from_slice(body);
+    frame
+}
+
+/// Reads one frame, skipping garbage up to the next magic.
+/// Protocol violations (lost sync, version, type or length out of range) are `InvalidData`.
+fn read_frame<R: Read>(reader: &mut R) -> io::Result<(u8, Vec<u8>)> {
+    let mut header = [0u8; FRAME_HEADER_LEN];
+    reader.read_exact(&mut header)?;
+
+    let mut skipped = 0;
+    let (kind, len) = loop {
+        if header[..2] == FRAME_MAGIC {
+            let known = matches!(header[3], FRAME_TEXT | FRAME_BINARY | FRAME_CONTROL);
+            match check_header(&header) {
+                Ok(frame) => break frame,
+                // A known type read in sync is a peer we can't talk to; anything else that
+                // merely starts with "PT" is stray bytes, so keep scanning
+                Err(e) if skipped == 0 && known => return Err(e),
+                Err(_) => {}
+            }
+        }
+        if skipped == MAX_RESYNC_BYTES {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "lost frame sync"));
+        }
+        header.copy_within(1.., 0);
+        reader.read_exact(&mut header[FRAME_HEADER_LEN - 1..])?;
+        skipped += 1;
+    };
+    if skipped > 0 {
+        log::warn!("IPC resynced after skipping {} bytes", skipped);
+    }
+
+    let mut body = vec![0u8; len];
+    reader.read_exact(&mut body)?;
+    Ok((kind, body))
+}
+
+/// Validates a header that starts with the magic, returning its type and body length.
+fn check_header(header: &[u8; FRAME_HEADER_LEN]) -> io::Result<(u8, usize)> {
+    if header[2] != FRAME_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unsupported frame version {} (expected {})", header[2], FRAME_VERSION),
+        ));
+    }
+    let kind = header[3];
+    if !matches!(kind, FRAME_TEXT | FRAME_BINARY | FRAME_CONTROL) {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("unknown frame type {}", kind)));
+    }
+    let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("frame of {} bytes exceeds the {} limit", len, MAX_FRAME_LEN)));
+    }
+    Ok((kind, len))
+}
+
+/// `Read` over a pipe handle, so partial `ReadFile`s are retried by `read_exact`.
+#[cfg(target_os = "windows")]
+struct PipeReader(HANDLE);
+
+#[cfg(target_os = "windows")]
+impl Read for PipeReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut bytes_read = 0u32;
+        unsafe { ReadFile(self.0, Some(buf), Some(&mut bytes_read), None) }.map_err(io::Error::other)?;
+        Ok(bytes_read as usize)
+    }
+}
+
+/// `Write` over a pipe handle, so `write_all` retries partial `WriteFile`s.
+#[cfg(target_os = "windows")]
+struct PipeWriter(HANDLE);
+
+#[cfg(target_os = "windows")]
+impl Write for PipeWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut bytes_written = 0u32;
+        unsafe { WriteFile(self.0, Some(buf), Some(&mut bytes_written), None) }.map_err(io::Error::other)?;
+        Ok(bytes_written as usize)
+    }
+
+    /// Blocks until the peer has read everything written so far.
+    fn flush(&mut self) -> io::Result<()> {
+        unsafe { FlushFileBuffers(self.0) }.map_err(io::Error::other)
+    }
+}
+
 #[cfg(target_os = "windows")]
 fn encode_wide(s: &str) -> Vec<u16> {
     use std::os::windows::ffi::OsStrExt;
@@ -1,18 +1,39 @@
 use pyo3::prelude::*;
 
+pub mod dialogs;
+pub mod errors;
 pub mod events;
 pub mod state;
 pub mod utils;
 pub mod protocol;
 pub mod webview;
 pub mod ipc;
+pub mod logger;
+pub mod menu;
+pub mod network;
+pub mod orphans;
+pub mod permissions;
+pub mod power;
+pub mod prewarm;
+pub mod storage;
+pub mod timings;
+pub mod watcher;
 
 use crate::webview::NativeWebview;
 use crate::ipc::ChromeIPC;
 
 #[pymodule]
 fn pytron_native(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    logger::init();
     m.add_class::<NativeWebview>()?;
     m.add_class::<ChromeIPC>()?;
+    errors::register(m)?;
+    m.add_function(wrap_pyfunction!(logger::set_log_level, m)?)?;
+    m.add_function(wrap_pyfunction!(logger::set_log_handler, m)?)?;
+    m.add_function(wrap_pyfunction!(ipc::is_pipe_available, m)?)?;
+    m.add_function(wrap_pyfunction!(orphans::kill_orphaned_webviews, m)?)?;
+    m.add_function(wrap_pyfunction!(prewarm::prewarm, m)?)?;
+    m.add("TRAY_SHOW_ID", menu::TRAY_SHOW_ID)?;
+    m.add("TRAY_QUIT_ID", menu::TRAY_QUIT_ID)?;
     Ok(())
 }
@@ -0,0 +1,67 @@
+use std::sync::{Arc, Mutex};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use pyo3::prelude::*;
+
+// Optional Python callable receiving (level, target, message). Behind an Arc so it can be
+// cloned without the GIL: holding this lock while waiting for the GIL would deadlock against
+// set_log_handler, which takes the lock with the GIL held.
+static HANDLER: Mutex<Option<Arc<PyObject>>> = Mutex::new(None);
+
+struct PytronLogger;
+
+impl Log for PytronLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        // "pytron_native::webview" -> "WEBVIEW", explicit targets like "bridge" -> "BRIDGE"
+        let tag = record.target().rsplit("::").next().unwrap_or("native").to_uppercase();
+        let msg = record.args().to_string();
+
+        let handler = HANDLER.lock().ok().and_then(|h| h.clone());
+        match handler {
+            Some(f) => Python::with_gil(|py| {
+                let _ = f.call1(py, (record.level().as_str(), tag, msg));
+            }),
+            None if record.level() <= Level::Warn => eprintln!("[PYTRON {}] {}", tag, msg),
+            None => println!("[PYTRON {}] {}", tag, msg),
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: PytronLogger = PytronLogger;
+
+pub fn init() {
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(LevelFilter::Info);
+    }
+}
+
+/// Sets the minimum level printed (or forwarded): off, error, warn, info, debug, trace.
+#[pyfunction]
+pub fn set_log_level(level: &str) -> PyResult<()> {
+    let filter = level.parse::<LevelFilter>().map_err(|_| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Unknown log level '{}'", level))
+    })?;
+    log::set_max_level(filter);
+    Ok(())
+}
+
+/// Routes native log records to `handler(level, target, message)` instead of stdout.
+/// Pass `None` to restore console output.
+#[pyfunction]
+#[pyo3(signature = (handler=None))]
+pub fn set_log_handler(handler: Option<PyObject>) {
+    let old = match HANDLER.lock() {
+        Ok(mut h) => std::mem::replace(&mut *h, handler.map(Arc::new)),
+        Err(_) => return,
+    };
+    // Dropped after the lock is released, in case its finalizer logs
+    drop(old);
+}
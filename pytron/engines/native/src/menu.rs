@@ -0,0 +1,239 @@
+use serde::Deserialize;
+use tray_icon::menu::{IsMenuItem, Menu, MenuItemBuilder, PredefinedMenuItem, Submenu};
+use tray_icon::menu::accelerator::{Accelerator, Code, Modifiers};
+
+/// Context-menu item ids are namespaced so the shared muda event channel can
+/// tell them apart from tray menu clicks.
+pub const CONTEXT_MENU_PREFIX: &str = "ctx:";
+
+/// Ids of the tray's default items. Custom tray menus may reuse them: `TRAY_SHOW_ID`
+/// restores the window natively, `TRAY_QUIT_ID` is handled on the Python side.
+pub const TRAY_SHOW_ID: &str = "1000";
+pub const TRAY_QUIT_ID: &str = "1001";
+
+/// Prefix of the ids given to edit-role items where the OS has no native edit command
+/// (Linux); clicking one runs the command on the page's focused element instead.
+pub const EDIT_ROLE_PREFIX: &str = "edit:";
+
+/// `[{"id": "copy", "text": "Copy"}, {"type": "separator"}, {"text": "More", "items": [...]}]`
+/// `{"role": "copy"}` (cut, paste, select_all) acts on the focused input like the shortcut would.
+#[derive(Deserialize, Debug)]
+pub struct MenuItemSpec {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub text: String,
+    #[serde(rename = "type", default)]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub role: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub items: Vec<MenuItemSpec>,
+}
+
+fn default_enabled() -> bool { true }
+
+pub fn parse_menu(json: &str) -> Result<Vec<MenuItemSpec>, String> {
+    serde_json::from_str(json).map_err(|e| format!("Invalid menu JSON: {}", e))
+}
+
+pub fn build_context_menu(specs: &[MenuItemSpec]) -> Menu {
+    build_menu(specs, CONTEXT_MENU_PREFIX)
+}
+
+/// The given items verbatim, or "Show App" / separator / "Quit" when `specs` is None.
+pub fn build_tray_menu(specs: Option<&[MenuItemSpec]>) -> Menu {
+    if let Some(specs) = specs {
+        return build_menu(specs, "");
+    }
+    let menu = Menu::new();
+    let show_item = MenuItemBuilder::new().text("Show App").id(TRAY_SHOW_ID.into()).enabled(true).build();
+    let quit_item = MenuItemBuilder::new().text("Quit").id(TRAY_QUIT_ID.into()).enabled(true).build();
+    let _ = menu.append(&show_item);
+    let _ = menu.append(&PredefinedMenuItem::separator());
+    let _ = menu.append(&quit_item);
+    menu
+}
+
+fn build_menu(specs: &[MenuItemSpec], prefix: &str) -> Menu {
+    let menu = Menu::new();
+    for spec in specs {
+        let _ = append_spec(spec, prefix, |item| menu.append(item));
+    }
+    menu
+}
+
+fn build_submenu(spec: &MenuItemSpec, prefix: &str) -> Submenu {
+    let sub = Submenu::new(&spec.text, spec.enabled);
+    for child in &spec.items {
+        let _ = append_spec(child, prefix, |item| sub.append(item));
+    }
+    sub
+}
+
+fn append_spec<F>(spec: &MenuItemSpec, prefix: &str, append: F) -> tray_icon::menu::Result<()>
+where
+    F: Fn(&dyn IsMenuItem) -> tray_icon::menu::Result<()>,
+{
+    if spec.kind.as_deref() == Some("separator") {
+        return append(&PredefinedMenuItem::separator());
+    }
+    if !spec.items.is_empty() {
+        return append(&build_submenu(spec, prefix));
+    }
+    if let Some(role) = spec.role.as_deref() {
+        let text = (!spec.text.is_empty()).then_some(spec.text.as_str());
+        #[cfg(any(target_os = "windows", target_os = "macos"))]
+        {
+            let item = match role {
+                "copy" => Some(PredefinedMenuItem::copy(text)),
+                "cut" => Some(PredefinedMenuItem::cut(text)),
+                "paste" => Some(PredefinedMenuItem::paste(text)),
+                "select_all" => Some(PredefinedMenuItem::select_all(text)),
+                _ => None,
+            };
+            if let Some(item) = item {
+                return append(&item);
+            }
+        }
+        // GTK's predefined edit items need libxdo, so the page runs the command itself
+        #[cfg(target_os = "linux")]
+        if let Some((label, _)) = edit_command(role) {
+            let item = MenuItemBuilder::new()
+                .text(text.unwrap_or(label))
+                .id(format!("{}{}{}", prefix, EDIT_ROLE_PREFIX, role).into())
+                .enabled(spec.enabled)
+                .build();
+            return append(&item);
+        }
+        log::warn!(target: "native", "Unknown menu role '{}'", role);
+    }
+    let item = MenuItemBuilder::new()
+        .text(&spec.text)
+        .id(format!("{}{}", prefix, spec.id).into())
+        .enabled(spec.enabled)
+        .build();
+    append(&item)
+}
+
+/// Default label and `document.execCommand` name for an edit role.
+pub fn edit_command(role: &str) -> Option<(&'static str, &'static str)> {
+    match role {
+        "copy" => Some(("Copy", "copy")),
+        "cut" => Some(("Cut", "cut")),
+        "paste" => Some(("Paste", "paste")),
+        "select_all" => Some(("Select All", "selectAll")),
+        _ => None,
+    }
+}
+
+/// macOS sends Cmd+C/V/X/A through the app menu; without an Edit menu they do nothing in
+/// the webview. Must be kept alive for as long as it's installed.
+#[cfg(target_os = "macos")]
+pub fn install_app_menu(app_name: &str) -> Menu {
+    let menu = Menu::new();
+    let app = Submenu::with_items(app_name, true, &[
+        &PredefinedMenuItem::hide(None),
+        &PredefinedMenuItem::hide_others(None),
+        &PredefinedMenuItem::show_all(None),
+    ]);
+    let edit = Submenu::with_items("Edit", true, &[
+        &PredefinedMenuItem::undo(None),
+        &PredefinedMenuItem::redo(None),
+        &PredefinedMenuItem::separator(),
+        &PredefinedMenuItem::cut(None),
+        &PredefinedMenuItem::copy(None),
+        &PredefinedMenuItem::paste(None),
+        &PredefinedMenuItem::select_all(None),
+    ]);
+    if let (Ok(app), Ok(edit)) = (app, edit) {
+        let _ = menu.append_items(&[&app, &edit]);
+    }
+    menu.init_for_nsapp();
+    menu
+}
+
+/// Modifiers and key of a shortcut such as "CmdOrCtrl+Shift+K". muda validates the string but
+/// keeps both parts private, and the page's keydown filter needs them.
+pub fn parse_accelerator(text: &str) -> Result<(Modifiers, Code), String> {
+    let accel = text.parse::<Accelerator>().map_err(|e| e.to_string())?;
+    let mut mods = Modifiers::empty();
+    let mut code = None;
+    for token in text.split('+').map(str::trim) {
+        match token.to_ascii_uppercase().as_str() {
+            "OPTION" | "ALT" => mods |= Modifiers::ALT,
+            "CONTROL" | "CTRL" => mods |= Modifiers::CONTROL,
+            "COMMAND" | "CMD" | "SUPER" => mods |= Modifiers::SUPER,
+            "SHIFT" => mods |= Modifiers::SHIFT,
+            "COMMANDORCONTROL" | "COMMANDORCTRL" | "CMDORCTRL" | "CMDORCONTROL" => {
+                mods |= if cfg!(target_os = "macos") { Modifiers::SUPER } else { Modifiers::CONTROL };
+            }
+            _ => code = key_code(token),
+        }
+    }
+    // Cross-checked against muda so both always agree on what the shortcut is
+    match code {
+        Some(code) if accel.matches(mods, code) => Ok((mods, code)),
+        _ => Err(format!("unsupported key in '{}'", text)),
+    }
+}
+
+// muda's spellings for keys whose `KeyboardEvent.code` name differs
+const KEY_ALIASES: &[(&str, &str)] = &[
+    ("`", "Backquote"), ("\\", "Backslash"), ("[", "BracketLeft"), ("]", "BracketRight"), (",", "Comma"),
+    ("=", "Equal"), ("-", "Minus"), (".", "Period"), ("'", "Quote"), (";", "Semicolon"), ("/", "Slash"),
+    ("UP", "ArrowUp"), ("DOWN", "ArrowDown"), ("LEFT", "ArrowLeft"), ("RIGHT", "ArrowRight"), ("ESC", "Escape"),
+    ("NUMADD", "NumpadAdd"), ("NUMPLUS", "NumpadAdd"), ("NUMPADPLUS", "NumpadAdd"), ("NUMDECIMAL", "NumpadDecimal"),
+    ("NUMDIVIDE", "NumpadDivide"), ("NUMENTER", "NumpadEnter"), ("NUMEQUAL", "NumpadEqual"),
+    ("NUMMULTIPLY", "NumpadMultiply"), ("NUMSUBTRACT", "NumpadSubtract"),
+    ("VOLUMEDOWN", "AudioVolumeDown"), ("VOLUMEUP", "AudioVolumeUp"), ("VOLUMEMUTE", "AudioVolumeMute"),
+];
+
+// Other keys muda takes by their `KeyboardEvent.code` name, in any case
+const KEY_NAMES: &[&str] = &[
+    "Backquote", "Backslash", "BracketLeft", "BracketRight", "Comma", "Equal", "Minus", "Period", "Quote",
+    "Semicolon", "Slash", "Backspace", "CapsLock", "Enter", "Space", "Tab", "Delete", "End", "Home", "Insert",
+    "PageDown", "PageUp", "PrintScreen", "ScrollLock", "ArrowDown", "ArrowLeft", "ArrowRight", "ArrowUp",
+    "NumLock", "NumpadAdd", "NumpadDecimal", "NumpadDivide", "NumpadEnter", "NumpadEqual", "NumpadMultiply",
+    "NumpadSubtract", "Escape", "AudioVolumeDown", "AudioVolumeUp", "AudioVolumeMute",
+];
+
+fn key_code(token: &str) -> Option<Code> {
+    let upper = token.to_ascii_uppercase();
+    let name = if let Some((_, name)) = KEY_ALIASES.iter().find(|(alias, _)| *alias == upper) {
+        name.to_string()
+    } else if let Some(name) = KEY_NAMES.iter().find(|name| name.eq_ignore_ascii_case(token)) {
+        name.to_string()
+    } else {
+        // Letters, digits and numpad digits, bare ("K", "5", "Num5") or prefixed ("KeyK", "Digit5")
+        let numpad = upper.strip_prefix("NUMPAD").or_else(|| upper.strip_prefix("NUM")).unwrap_or_default();
+        let letter = upper.strip_prefix("KEY").unwrap_or(&upper);
+        let digit = upper.strip_prefix("DIGIT").unwrap_or(&upper);
+        match (numpad.as_bytes(), letter.as_bytes(), digit.as_bytes()) {
+            ([d], ..) if d.is_ascii_digit() => format!("Numpad{}", numpad),
+            (_, [c], _) if c.is_ascii_alphabetic() => format!("Key{}", letter),
+            (_, _, [d]) if d.is_ascii_digit() => format!("Digit{}", digit),
+            _ => upper.to_string(), // F1-F24
+        }
+    };
+    name.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_accelerator_parts() {
+        let ctrl = if cfg!(target_os = "macos") { Modifiers::SUPER } else { Modifiers::CONTROL };
+        assert_eq!(parse_accelerator("CmdOrCtrl+Shift+K"), Ok((ctrl | Modifiers::SHIFT, Code::KeyK)));
+        assert_eq!(parse_accelerator("alt+pagedown"), Ok((Modifiers::ALT, Code::PageDown)));
+        assert_eq!(parse_accelerator("Ctrl+Num5"), Ok((Modifiers::CONTROL, Code::Numpad5)));
+        assert_eq!(parse_accelerator("Ctrl+/"), Ok((Modifiers::CONTROL, Code::Slash)));
+        assert_eq!(parse_accelerator("F12"), Ok((Modifiers::empty(), Code::F12)));
+        assert_eq!(parse_accelerator("Super+Digit3"), Ok((Modifiers::SUPER, Code::Digit3)));
+        assert!(parse_accelerator("Ctrl+Nope").is_err());
+    }
+}
@@ -0,0 +1,104 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use crate::events::UserEvent;
+
+/// How often the background thread re-checks connectivity.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+static POLLING: AtomicBool = AtomicBool::new(false);
+
+/// Starts the process-wide poller (once) that sends `ConnectivityChanged` to every live webview
+/// when the machine goes online or offline. It stops when no webview is left to tell.
+pub fn ensure_polling() {
+    if POLLING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(|| {
+        let mut last = is_online();
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let now = is_online();
+            if now != last {
+                last = now;
+                log::info!(target: "native", "Network is {}", if now { "online" } else { "offline" });
+                // Reset under the registry lock: a webview binding right after must start a new poller
+                let reset = || POLLING.store(false, Ordering::SeqCst);
+                if crate::state::send_to_all_or(|| UserEvent::ConnectivityChanged(now), reset) == 0 {
+                    break;
+                }
+            }
+        }
+    });
+}
+
+/// Asks the Network List Manager, which probes for actual internet access rather than a link.
+#[cfg(target_os = "windows")]
+pub fn is_online() -> bool {
+    use windows::Win32::Networking::NetworkListManager::{INetworkListManager, NetworkListManager};
+    use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_ALL, COINIT_MULTITHREADED};
+
+    unsafe {
+        // Fails harmlessly on threads that already joined an apartment
+        let initialized = CoInitializeEx(None, COINIT_MULTITHREADED).is_ok();
+        let online = CoCreateInstance::<_, INetworkListManager>(&NetworkListManager, None, CLSCTX_ALL)
+            .and_then(|nlm| nlm.IsConnectedToInternet())
+            .map(|b| b.as_bool())
+            .unwrap_or(true);
+        if initialized {
+            CoUninitialize();
+        }
+        online
+    }
+}
+
+/// Online while the kernel has a default route that isn't over loopback.
+#[cfg(target_os = "linux")]
+pub fn is_online() -> bool {
+    let v4 = std::fs::read_to_string("/proc/net/route").unwrap_or_default();
+    let has_v4 = v4.lines().skip(1).any(|line| {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        // Iface, Destination, Gateway, Flags (RTF_UP = 1)
+        cols.len() > 3 && cols[0] != "lo" && cols[1] == "00000000" && u32::from_str_radix(cols[3], 16).is_ok_and(|f| f & 1 != 0)
+    });
+    let v6 = std::fs::read_to_string("/proc/net/ipv6_route").unwrap_or_default();
+    let has_v6 = v6.lines().any(|line| {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        // Destination, prefix length, ..., device last
+        cols.len() == 10 && cols[0].bytes().all(|b| b == b'0') && cols[1] == "00" && cols[9] != "lo"
+    });
+    has_v4 || has_v6
+}
+
+/// SCNetworkReachability only inspects the local routing state; nothing is sent on the wire.
+#[cfg(target_os = "macos")]
+pub fn is_online() -> bool {
+    use std::ffi::c_void;
+
+    #[link(name = "SystemConfiguration", kind = "framework")]
+    extern "C" {
+        fn SCNetworkReachabilityCreateWithName(allocator: *const c_void, nodename: *const std::ffi::c_char) -> *const c_void;
+        fn SCNetworkReachabilityGetFlags(target: *const c_void, flags: *mut u32) -> u8;
+    }
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFRelease(cf: *const c_void);
+    }
+    const REACHABLE: u32 = 1 << 1;
+    const CONNECTION_REQUIRED: u32 = 1 << 2;
+
+    unsafe {
+        let target = SCNetworkReachabilityCreateWithName(std::ptr::null(), b"apple.com\0".as_ptr() as *const std::ffi::c_char);
+        if target.is_null() {
+            return true;
+        }
+        let mut flags = 0u32;
+        let ok = SCNetworkReachabilityGetFlags(target, &mut flags) != 0;
+        CFRelease(target);
+        !ok || (flags & REACHABLE != 0 && flags & CONNECTION_REQUIRED == 0)
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+pub fn is_online() -> bool {
+    true
+}
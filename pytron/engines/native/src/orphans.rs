@@ -0,0 +1,123 @@
+use pyo3::prelude::*;
+
+/// Terminates WebView2 processes left behind by a crashed or force-killed instance: every
+/// `msedgewebview2.exe` whose `--user-data-dir` lies under `user_data_dir` and whose process
+/// tree no longer has a live host. Webviews of a running instance are left alone.
+/// Returns how many processes were killed. A no-op outside Windows, where the engine's
+/// helper processes exit with their parent.
+#[pyfunction]
+#[allow(unused_variables)]
+pub fn kill_orphaned_webviews(py: Python<'_>, user_data_dir: String) -> usize {
+    #[cfg(target_os = "windows")]
+    {
+        py.allow_threads(|| win::kill_orphans(&user_data_dir))
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        0
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod win {
+    use std::collections::HashMap;
+    use windows::Wdk::System::Threading::{NtQueryInformationProcess, ProcessCommandLineInformation};
+    use windows::Win32::Foundation::{CloseHandle, HANDLE, UNICODE_STRING};
+    use windows::Win32::System::Diagnostics::ToolHelp::{CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS};
+    use windows::Win32::System::Threading::{OpenProcess, TerminateProcess, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_TERMINATE};
+
+    const WEBVIEW_EXE: &str = "msedgewebview2.exe";
+
+    pub fn kill_orphans(user_data_dir: &str) -> usize {
+        let dir = normalize(user_data_dir);
+        if dir.is_empty() {
+            return 0;
+        }
+        let processes = snapshot(); // pid -> (parent pid, exe name)
+        let is_webview = |pid: &u32| processes.get(pid).is_some_and(|(_, exe)| exe.eq_ignore_ascii_case(WEBVIEW_EXE));
+
+        let mut killed = 0;
+        for (&pid, (_, exe)) in &processes {
+            if !exe.eq_ignore_ascii_case(WEBVIEW_EXE) {
+                continue;
+            }
+            // Climb to the browser process; its parent is the host app
+            let mut root = pid;
+            let mut hops = 0;
+            while let Some(&(parent, _)) = processes.get(&root) {
+                if !is_webview(&parent) || hops > 16 {
+                    break;
+                }
+                root = parent;
+                hops += 1;
+            }
+            let host = processes.get(&root).map(|(parent, _)| *parent).unwrap_or(0);
+            if processes.contains_key(&host) {
+                continue;
+            }
+            unsafe {
+                let Ok(handle) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION | PROCESS_TERMINATE, false, pid) else { continue };
+                let ours = command_line(handle).is_some_and(|cmd| uses_data_dir(&normalize(&cmd), &dir));
+                if ours && TerminateProcess(handle, 1).is_ok() {
+                    killed += 1;
+                }
+                let _ = CloseHandle(handle);
+            }
+        }
+        if killed > 0 {
+            log::info!(target: "native", "Killed {} orphaned WebView2 process(es)", killed);
+        }
+        killed
+    }
+
+    fn snapshot() -> HashMap<u32, (u32, String)> {
+        let mut processes = HashMap::new();
+        unsafe {
+            let Ok(snap) = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) else { return processes };
+            let mut entry = PROCESSENTRY32W { dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32, ..Default::default() };
+            let mut ok = Process32FirstW(snap, &mut entry).is_ok();
+            while ok {
+                let len = entry.szExeFile.iter().position(|&c| c == 0).unwrap_or(entry.szExeFile.len());
+                let exe = String::from_utf16_lossy(&entry.szExeFile[..len]);
+                processes.insert(entry.th32ProcessID, (entry.th32ParentProcessID, exe));
+                ok = Process32NextW(snap, &mut entry).is_ok();
+            }
+            let _ = CloseHandle(snap);
+        }
+        processes
+    }
+
+    /// Needs Windows 8.1+; older systems just report no command line.
+    unsafe fn command_line(handle: HANDLE) -> Option<String> {
+        let mut needed = 0u32;
+        let _ = NtQueryInformationProcess(handle, ProcessCommandLineInformation, std::ptr::null_mut(), 0, &mut needed);
+        if needed == 0 {
+            return None;
+        }
+        // u64 backing keeps the UNICODE_STRING header aligned
+        let mut buffer = vec![0u64; (needed as usize).div_ceil(8)];
+        let status = NtQueryInformationProcess(handle, ProcessCommandLineInformation, buffer.as_mut_ptr().cast(), needed, &mut needed);
+        if status.is_err() {
+            return None;
+        }
+        let text = &*(buffer.as_ptr() as *const UNICODE_STRING);
+        if text.Buffer.is_null() {
+            return None;
+        }
+        let chars = std::slice::from_raw_parts(text.Buffer.0, text.Length as usize / 2);
+        Some(String::from_utf16_lossy(chars))
+    }
+
+    fn normalize(path: &str) -> String {
+        path.replace('/', "\\").trim_end_matches('\\').to_lowercase()
+    }
+
+    /// `dir` itself or anything under it, but not a sibling that merely shares the prefix.
+    fn uses_data_dir(cmd: &str, dir: &str) -> bool {
+        cmd.match_indices(dir).any(|(at, _)| {
+            let before = cmd[..at].ends_with("--user-data-dir=") || cmd[..at].ends_with("--user-data-dir=\"");
+            let after = cmd[at + dir.len()..].chars().next();
+            before && matches!(after, None | Some('\\') | Some('"') | Some(' '))
+        })
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use pyo3::prelude::*;
+use wry::WebView;
+
+type Callbacks = Arc<Mutex<HashMap<String, PyObject>>>;
+
+/// Asks `pytron_on_permission(origin, kind) -> bool`.
+/// `None` when no callback is bound, so the webview keeps its own default.
+/// A callback that raises or returns a non-bool denies.
+fn decide(callbacks: &Callbacks, origin: &str, kind: &str) -> Option<bool> {
+    let mut found: Option<PyObject> = None;
+    if let Ok(cbs) = callbacks.lock() {
+        if let Some(f) = cbs.get("pytron_on_permission") {
+            Python::with_gil(|py| { found = Some(f.clone_ref(py)); });
+        }
+    }
+    let f = found?;
+    let allowed = Python::with_gil(|py| {
+        f.call1(py, (origin, kind))
+            .and_then(|r| r.extract::<bool>(py))
+            .unwrap_or_else(|e| {
+                log::warn!(target: "native", "pytron_on_permission failed: {}", e);
+                false
+            })
+    });
+    log::debug!(target: "native", "Permission '{}' for {}: {}", kind, origin, if allowed { "allow" } else { "deny" });
+    Some(allowed)
+}
+
+/// Hooks the platform permission prompt. Kinds passed to Python:
+/// "camera", "microphone", "geolocation", "notifications", "clipboard-read", "sensors", or "other".
+#[allow(unused_variables)]
+pub fn install(webview: &WebView, callbacks: Callbacks) {
+    #[cfg(target_os = "windows")]
+    unsafe {
+        use webview2_com::{take_pwstr, PermissionRequestedEventHandler};
+        use webview2_com::Microsoft::Web::WebView2::Win32::*;
+        use wry::WebViewExtWindows;
+
+        let Ok(core) = webview.controller().CoreWebView2() else { return };
+        let handler = PermissionRequestedEventHandler::create(Box::new(move |_, args| {
+            let Some(args) = args else { return Ok(()) };
+            let mut uri = windows::core::PWSTR::null();
+            args.Uri(&mut uri)?;
+            let origin = take_pwstr(uri);
+            let mut kind = COREWEBVIEW2_PERMISSION_KIND::default();
+            args.PermissionKind(&mut kind)?;
+            let name = match kind {
+                COREWEBVIEW2_PERMISSION_KIND_CAMERA => "camera",
+                COREWEBVIEW2_PERMISSION_KIND_MICROPHONE => "microphone",
+                COREWEBVIEW2_PERMISSION_KIND_GEOLOCATION => "geolocation",
+                COREWEBVIEW2_PERMISSION_KIND_NOTIFICATIONS => "notifications",
+                COREWEBVIEW2_PERMISSION_KIND_CLIPBOARD_READ => "clipboard-read",
+                COREWEBVIEW2_PERMISSION_KIND_OTHER_SENSORS => "sensors",
+                _ => "other",
+            };
+            if let Some(allowed) = decide(&callbacks, &origin, name) {
+                args.SetState(if allowed { COREWEBVIEW2_PERMISSION_STATE_ALLOW } else { COREWEBVIEW2_PERMISSION_STATE_DENY })?;
+            }
+            Ok(())
+        }));
+        let mut token = Default::default();
+        if let Err(e) = core.add_PermissionRequested(&handler, &mut token) {
+            log::warn!(target: "native", "Could not install permission handler: {}", e);
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use webkit2gtk::{
+            GeolocationPermissionRequest, NotificationPermissionRequest, PermissionRequestExt,
+            UserMediaPermissionRequest, UserMediaPermissionRequestExt, WebViewExt,
+        };
+        use gtk::glib::Cast;
+        use wry::WebViewExtUnix;
+
+        webview.webview().connect_permission_request(move |view, req| {
+            let origin = view.uri().map(|u| u.to_string()).unwrap_or_default();
+            let name = if let Some(media) = req.downcast_ref::<UserMediaPermissionRequest>() {
+                if media.is_for_video_device() { "camera" } else { "microphone" }
+            } else if req.is::<GeolocationPermissionRequest>() {
+                "geolocation"
+            } else if req.is::<NotificationPermissionRequest>() {
+                "notifications"
+            } else {
+                "other"
+            };
+            match decide(&callbacks, &origin, name) {
+                Some(true) => { req.allow(); true }
+                Some(false) => { req.deny(); true }
+                None => false,
+            }
+        });
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        // WKWebView's UI delegate is owned by wry and grants media capture itself
+        log::debug!(target: "native", "pytron_on_permission is not consulted on macOS");
+    }
+}
@@ -0,0 +1,132 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use crate::events::UserEvent;
+
+/// How often the background thread re-reads the power source.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PowerState {
+    pub on_battery: bool,
+    pub battery_percent: Option<u8>, // None on desktops or when the OS doesn't say
+    pub charging: bool,
+}
+
+impl PowerState {
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("on_battery", self.on_battery)?;
+        dict.set_item("battery_percent", self.battery_percent)?;
+        dict.set_item("charging", self.charging)?;
+        Ok(dict)
+    }
+}
+
+static POLLING: AtomicBool = AtomicBool::new(false);
+
+/// Starts the process-wide poller (once) that sends `PowerChanged` to every live webview when
+/// the power source or charge changes. It stops when no webview is left to tell.
+pub fn ensure_polling() {
+    if POLLING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(|| {
+        let mut last = read();
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let now = read();
+            if now != last {
+                last = now;
+                // Reset under the registry lock: a webview binding right after must start a new poller
+                let reset = || POLLING.store(false, Ordering::SeqCst);
+                if crate::state::send_to_all_or(|| UserEvent::PowerChanged(now), reset) == 0 {
+                    break;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(target_os = "windows")]
+pub fn read() -> PowerState {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status = SYSTEM_POWER_STATUS::default();
+    if unsafe { GetSystemPowerStatus(&mut status) }.is_err() {
+        return PowerState::default();
+    }
+    // 255 means unknown; BatteryFlag 128 means there is no battery at all
+    let has_battery = status.BatteryFlag != 255 && status.BatteryFlag & 128 == 0;
+    PowerState {
+        on_battery: status.ACLineStatus == 0,
+        battery_percent: (has_battery && status.BatteryLifePercent <= 100).then_some(status.BatteryLifePercent),
+        charging: has_battery && status.BatteryFlag & 8 != 0,
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn read() -> PowerState {
+    let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else { return PowerState::default() };
+    let attr = |dir: &std::path::Path, name: &str| std::fs::read_to_string(dir.join(name)).map(|s| s.trim().to_string()).unwrap_or_default();
+
+    let (mut mains, mut mains_online, mut discharging, mut state) = (false, false, false, PowerState::default());
+    // (percent, full capacity) per pack; laptops with BAT0 + BAT1 report one combined charge
+    let mut packs: Vec<(u64, Option<u64>)> = Vec::new();
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        match attr(&dir, "type").as_str() {
+            "Mains" => {
+                mains = true;
+                mains_online |= attr(&dir, "online") == "1";
+            }
+            // Skip peripheral batteries (mice, headsets) that report scope=Device
+            "Battery" if attr(&dir, "scope") != "Device" => {
+                if let Ok(percent) = attr(&dir, "capacity").parse::<u64>() {
+                    let full = attr(&dir, "energy_full").parse::<u64>()
+                        .or_else(|_| attr(&dir, "charge_full").parse::<u64>())
+                        .ok()
+                        .filter(|full| *full > 0);
+                    packs.push((percent.min(100), full));
+                }
+                let status = attr(&dir, "status");
+                state.charging |= status == "Charging";
+                discharging |= status == "Discharging";
+            }
+            _ => {}
+        }
+    }
+    // Weighted by capacity when every pack reports one, a plain average otherwise
+    let weights: Vec<u64> = match packs.iter().map(|(_, full)| *full).collect::<Option<Vec<u64>>>() {
+        Some(fulls) => fulls,
+        None => vec![1; packs.len()],
+    };
+    let total: u64 = weights.iter().sum();
+    if total > 0 {
+        let weighted: u64 = packs.iter().zip(&weights).map(|((percent, _), w)| percent * w).sum();
+        state.battery_percent = Some((weighted / total) as u8);
+    }
+    state.on_battery = if mains { !mains_online } else { discharging };
+    state
+}
+
+#[cfg(target_os = "macos")]
+pub fn read() -> PowerState {
+    // `pmset -g batt` prints "Now drawing from 'AC Power'" followed by "85%; charging; ..."
+    let Ok(out) = std::process::Command::new("pmset").args(["-g", "batt"]).output() else { return PowerState::default() };
+    let text = String::from_utf8_lossy(&out.stdout);
+    let battery_percent = text
+        .split_whitespace()
+        .find_map(|word| word.trim_end_matches(';').strip_suffix('%')?.parse::<u8>().ok());
+    PowerState {
+        on_battery: text.contains("'Battery Power'"),
+        battery_percent,
+        charging: text.contains("; charging;"),
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+pub fn read() -> PowerState {
+    PowerState::default()
+}
@@ -0,0 +1,97 @@
+use pyo3::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set once a real webview exists; a prewarmed environment is released then.
+static WEBVIEW_CREATED: AtomicBool = AtomicBool::new(false);
+
+pub fn mark_webview_created() {
+    WEBVIEW_CREATED.store(true, Ordering::SeqCst);
+}
+
+/// Starts the WebView2 browser process in the background (e.g. while the splash is up) so the
+/// first window attaches to a running runtime instead of cold-starting one. The arguments must
+/// match the ones the window will be created with, or WebView2 refuses the second environment.
+/// The environment is held until the first webview exists or `keep_alive_ms` passes.
+/// A no-op outside Windows.
+#[pyfunction]
+#[pyo3(signature = (data_directory=None, profile=None, additional_browser_args=None, keep_alive_ms=30000))]
+pub fn prewarm(data_directory: Option<String>, profile: Option<String>, additional_browser_args: Option<String>, keep_alive_ms: u64) -> PyResult<()> {
+    #[cfg(target_os = "windows")]
+    {
+        let dir = crate::webview::profile_data_dir(data_directory, profile)?;
+        // Same arguments the window builds with (see `NativeWebview::new`)
+        let args = match additional_browser_args {
+            Some(extra) => format!("{} {}", crate::webview::WEBVIEW2_DEFAULT_ARGS, extra),
+            None => crate::webview::WEBVIEW2_DEFAULT_ARGS.to_string(),
+        };
+        let keep_alive = std::time::Duration::from_millis(keep_alive_ms);
+        std::thread::spawn(move || win::hold_environment(dir, args, keep_alive));
+    }
+    #[cfg(not(target_os = "windows"))]
+    let _ = (data_directory, profile, additional_browser_args, keep_alive_ms);
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+mod win {
+    use std::path::PathBuf;
+    use std::sync::atomic::Ordering;
+    use std::sync::mpsc;
+    use std::time::{Duration, Instant};
+    use webview2_com::Microsoft::Web::WebView2::Win32::{CreateCoreWebView2EnvironmentWithOptions, ICoreWebView2EnvironmentOptions};
+    use webview2_com::{CoreWebView2EnvironmentOptions, CreateCoreWebView2EnvironmentCompletedHandler};
+    use windows::core::{HSTRING, PCWSTR};
+    use windows::Win32::Foundation::{E_UNEXPECTED, HWND};
+    use windows::Win32::Globalization::{GetUserDefaultUILanguage, LCIDToLocaleName, LOCALE_ALLOW_NEUTRAL_NAMES, MAX_LOCALE_NAME};
+    use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_APARTMENTTHREADED};
+    use windows::Win32::UI::WindowsAndMessaging::{DispatchMessageW, PeekMessageW, TranslateMessage, MSG, PM_REMOVE};
+
+    pub fn hold_environment(dir: Option<PathBuf>, args: String, keep_alive: Duration) {
+        let started = Instant::now();
+        unsafe {
+            let initialized = CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_ok();
+            let data_directory = dir.map(|d| HSTRING::from(d.as_os_str())).unwrap_or_default();
+            let (tx, rx) = mpsc::channel();
+            let created = CreateCoreWebView2EnvironmentCompletedHandler::wait_for_async_operation(
+                Box::new(move |handler| {
+                    let options = CoreWebView2EnvironmentOptions::default();
+                    options.set_additional_browser_arguments(args);
+                    // Matches wry, which fails the real environment if the language differs
+                    let lcid = GetUserDefaultUILanguage();
+                    let mut lang = [0; MAX_LOCALE_NAME as usize];
+                    LCIDToLocaleName(lcid as u32, Some(&mut lang), LOCALE_ALLOW_NEUTRAL_NAMES);
+                    options.set_language(String::from_utf16_lossy(&lang));
+                    CreateCoreWebView2EnvironmentWithOptions(PCWSTR::null(), &data_directory, &ICoreWebView2EnvironmentOptions::from(options), &handler)
+                        .map_err(Into::into)
+                }),
+                Box::new(move |error_code, environment| {
+                    error_code?;
+                    tx.send(environment).map_err(|_| windows::core::Error::from(E_UNEXPECTED))
+                }),
+            );
+            let environment = match created {
+                Ok(()) => rx.recv().ok().flatten(),
+                Err(e) => {
+                    log::warn!(target: "native", "WebView2 prewarm failed: {:?}", e);
+                    None
+                }
+            };
+            if environment.is_some() {
+                log::info!(target: "native", "WebView2 runtime prewarmed in {:.0?}", started.elapsed());
+                // Keep this apartment responsive while holding the browser process open
+                let mut msg = MSG::default();
+                while !super::WEBVIEW_CREATED.load(Ordering::SeqCst) && started.elapsed() < keep_alive {
+                    while PeekMessageW(&mut msg, HWND::default(), 0, 0, PM_REMOVE).as_bool() {
+                        let _ = TranslateMessage(&msg);
+                        DispatchMessageW(&msg);
+                    }
+                    std::thread::sleep(Duration::from_millis(50));
+                }
+            }
+            drop(environment);
+            if initialized {
+                CoUninitialize();
+            }
+        }
+    }
+}
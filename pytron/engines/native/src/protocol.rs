@@ -1,27 +1,349 @@
 use std::borrow::Cow;
-use std::path::PathBuf;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
 use std::collections::HashMap;
 use pyo3::prelude::*;
-use wry::http::{Response, header, StatusCode, Method, Request};
+use unicode_normalization::UnicodeNormalization;
+use wry::http::{Response, header, HeaderMap, HeaderName, HeaderValue, StatusCode, Method, Request};
+use wry::http::response::Builder;
+
+pub struct ProtocolContext {
+    pub root: PathBuf,
+    pub callbacks: Arc<Mutex<HashMap<String, PyObject>>>,
+    /// Origins allowed to read protocol responses. `*` opts into any origin.
+    pub allowed_origins: Vec<String>,
+    /// Replace `window.alert` with the (non-blocking) native message box.
+    pub native_alert: bool,
+    /// Serve the root `index.html` for unknown extension-less paths (client-side routing).
+    pub spa_fallback: bool,
+    /// Status code -> HTML page (relative to `root`). Defaults to `<root>/<code>.html`.
+    pub error_pages: HashMap<u16, String>,
+    /// Glob (`*.js`, `assets/*`, `sw.js`) -> extra response headers.
+    pub header_rules: HashMap<String, HashMap<String, String>>,
+    /// Path of the service worker script, served with `Service-Worker-Allowed: /`.
+    pub service_worker: Option<String>,
+    /// Assets bundled into a single zip, consulted before `root`.
+    pub archive: Option<AssetArchive>,
+    /// Aggregate counters, `None` unless stats were requested.
+    pub stats: Option<Arc<ProtocolStats>>,
+    /// Per-launch secret required by requests that reach Python or change state.
+    pub token: Option<String>,
+}
+
+/// Header the page's fetch/XHR attach the protocol token as.
+pub const TOKEN_HEADER: &str = "x-pytron-token";
+
+/// 128 bits from the OS CSPRNG as hex, fresh every launch.
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 16];
+    // Only fails if the OS has no entropy source at all; there's no safe fallback for a secret
+    getrandom::getrandom(&mut bytes).expect("OS random number generator unavailable");
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Patches fetch/XHR in the top frame to send the token to our own scheme only, so
+/// embedded third-party frames never see it.
+pub fn token_script(scheme: &str, token: &str) -> String {
+    let json = |s: &str| serde_json::to_string(s).unwrap_or_else(|_| "null".to_string());
+    format!(
+        r#"
+    (function() {{
+        if (window.top !== window) return;
+        const token = {token}, header = {header}, scheme = {scheme};
+        const ours = (u) => {{
+            try {{
+                const url = new URL(u, location.href);
+                // WebView2 serves the scheme as https://<scheme>.localhost; nothing broader qualifies
+                return url.protocol === scheme + ':' || (url.protocol === 'https:' && url.host === scheme + '.localhost');
+            }} catch (e) {{ return false; }}
+        }};
+        const origFetch = window.fetch;
+        window.fetch = (input, init = {{}}) => {{
+            const url = input instanceof Request ? input.url : String(input);
+            if (!ours(url)) return origFetch(input, init);
+            const headers = new Headers(init.headers || (input instanceof Request ? input.headers : undefined));
+            headers.set(header, token);
+            return origFetch(input, {{ ...init, headers }});
+        }};
+        const open = XMLHttpRequest.prototype.open, send = XMLHttpRequest.prototype.send;
+        XMLHttpRequest.prototype.open = function(method, url, ...rest) {{
+            this.__pytronOurs = ours(url);
+            return open.call(this, method, url, ...rest);
+        }};
+        XMLHttpRequest.prototype.send = function(body) {{
+            if (this.__pytronOurs) this.setRequestHeader(header, token);
+            return send.call(this, body);
+        }};
+    }})();
+"#,
+        token = json(token),
+        header = json(TOKEN_HEADER),
+        scheme = json(scheme),
+    )
+}
+
+/// In-memory copy of an asset zip, keyed by its `/`-separated entry path.
+pub struct AssetArchive {
+    files: HashMap<String, Vec<u8>>,
+}
+
+impl AssetArchive {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file = std::fs::File::open(path).map_err(|e| format!("Cannot open asset archive {}: {}", path.display(), e))?;
+        let mut zip = zip::ZipArchive::new(file).map_err(|e| format!("Invalid asset archive {}: {}", path.display(), e))?;
+        let mut files = HashMap::with_capacity(zip.len());
+        for i in 0..zip.len() {
+            let mut entry = zip.by_index(i).map_err(|e| e.to_string())?;
+            if entry.is_dir() {
+                continue;
+            }
+            let mut data = Vec::with_capacity(entry.size() as usize);
+            entry.read_to_end(&mut data).map_err(|e| e.to_string())?;
+            files.insert(entry.name().trim_start_matches('/').to_string(), data);
+        }
+        log::info!(target: "protocol", "Loaded {} embedded assets from {}", files.len(), path.display());
+        Ok(Self { files })
+    }
+
+    fn get(&self, key: &str) -> Option<(&str, &Vec<u8>)> {
+        let key = key.trim_matches('/');
+        let index = if key.is_empty() { "index.html".to_string() } else { format!("{}/index.html", key) };
+        self.files
+            .get_key_value(key)
+            .or_else(|| self.files.get_key_value(&index))
+            .map(|(k, v)| (k.as_str(), v))
+    }
+}
+
+struct Asset {
+    path: PathBuf,
+    data: Vec<u8>,
+    archived: bool, // Came from the embedded archive rather than disk
+}
+
+/// Reads `rel` from the archive when present, otherwise from disk (directories map to `index.html`).
+/// Non-ASCII names are retried in composed and decomposed form: macOS stores names decomposed
+/// (NFD) while pages usually request composed (NFC), and copied assets keep whichever they had.
+fn read_asset(ctx: &ProtocolContext, rel: &str) -> std::io::Result<Asset> {
+    let result = read_asset_exact(ctx, rel);
+    if rel.is_ascii() || !matches!(&result, Err(e) if e.kind() == std::io::ErrorKind::NotFound) {
+        return result;
+    }
+    for form in [rel.nfc().collect::<String>(), rel.nfd().collect::<String>()] {
+        if form != rel {
+            if let Ok(found) = read_asset_exact(ctx, &form) {
+                return Ok(found);
+            }
+        }
+    }
+    result
+}
+
+fn read_asset_exact(ctx: &ProtocolContext, rel: &str) -> std::io::Result<Asset> {
+    if let Some((key, data)) = ctx.archive.as_ref().and_then(|a| a.get(rel)) {
+        return Ok(Asset { path: PathBuf::from(key), data: data.clone(), archived: true });
+    }
+    let mut path = ctx.root.join(rel);
+    if path.is_dir() {
+        path = path.join("index.html");
+    }
+    std::fs::read(&path).map(|data| Asset { path, data, archived: false })
+}
+
+#[derive(Default)]
+pub struct ProtocolStats {
+    requests: AtomicU64,
+    bytes_served: AtomicU64,
+    not_found: AtomicU64,
+    errors: AtomicU64,
+    cache_hits: AtomicU64, // Requests served from the embedded archive
+    serve_micros: AtomicU64,
+}
+
+impl ProtocolStats {
+    fn record(&self, status: StatusCode, bytes: usize, micros: u64) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.bytes_served.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.serve_micros.fetch_add(micros, Ordering::Relaxed);
+        if status == StatusCode::NOT_FOUND {
+            self.not_found.fetch_add(1, Ordering::Relaxed);
+        } else if status.is_server_error() {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn snapshot(&self) -> HashMap<&'static str, u64> {
+        HashMap::from([
+            ("requests", self.requests.load(Ordering::Relaxed)),
+            ("bytes_served", self.bytes_served.load(Ordering::Relaxed)),
+            ("not_found", self.not_found.load(Ordering::Relaxed)),
+            ("errors", self.errors.load(Ordering::Relaxed)),
+            ("cache_hits", self.cache_hits.load(Ordering::Relaxed)),
+            ("serve_micros", self.serve_micros.load(Ordering::Relaxed)),
+        ])
+    }
+}
+
+pub const ALERT_OVERRIDE: &str = r#"
+    window.alert = (msg) => {
+        window.__pytron_native_bridge('pytron_message_box', ["Alert", String(msg), "info"]);
+    };
+"#;
+
+/// Tells the page where bundled files live and how to address them through the protocol.
+/// `pytron.asset(path)` takes a path relative to the asset root (or an absolute one inside it)
+/// and returns its URL, or null when the file is outside what the protocol can serve.
+pub fn asset_resolver_script(scheme: &str, root: &Path, meipass: Option<&str>) -> String {
+    // WebView2 serves custom schemes as https://<scheme>.<host>
+    let base = if cfg!(target_os = "windows") { format!("https://{}.localhost/app/", scheme) } else { format!("{}://app/", scheme) };
+    let json = |s: &str| serde_json::to_string(s).unwrap_or_else(|_| "null".to_string());
+    format!(
+        r#"
+    window.pytron = window.pytron || {{}};
+    window.pytron.assetRoot = {root};
+    window.pytron.meipass = {meipass};
+    window.pytron.asset = (path) => {{
+        const norm = (p) => String(p).replace(/\\/g, '/');
+        let rel = norm(path);
+        if (rel.startsWith('/') || /^[A-Za-z]:\//.test(rel)) {{
+            const root = norm(window.pytron.assetRoot).replace(/\/?$/, '/');
+            if (!rel.toLowerCase().startsWith(root.toLowerCase())) return null;
+            rel = rel.slice(root.length);
+        }}
+        rel = rel.replace(/^(\.\/)+/, '').replace(/^\/+/, '');
+        return {base} + rel.split('/').map(encodeURIComponent).join('/');
+    }};
+"#,
+        root = json(&root.to_string_lossy()),
+        meipass = meipass.map(json).unwrap_or_else(|| "null".to_string()),
+        base = json(&base),
+    )
+}
+
+/// Read-only `window.pytron.info = {name, version, author}` for About boxes and footers.
+pub fn app_info_script(info: &HashMap<String, Option<String>>) -> String {
+    let field = |key: &str| serde_json::to_string(&info.get(key).cloned().flatten()).unwrap_or_else(|_| "null".to_string());
+    format!(
+        r#"
+    window.pytron = window.pytron || {{}};
+    window.pytron.info = Object.freeze({{ name: {name}, version: {version}, author: {author} }});
+"#,
+        name = field("name"),
+        version = field("version"),
+        author = field("author"),
+    )
+}
+
+/// `window.pytron.initial_state`, readable synchronously by the page's first script.
+/// Values arrive as JSON text and are re-encoded here so nothing is spliced into JS unescaped.
+pub fn initial_state_script(state: &HashMap<String, String>) -> Result<String, String> {
+    let mut object = serde_json::Map::new();
+    for (key, value) in state {
+        let parsed = serde_json::from_str::<serde_json::Value>(value)
+            .map_err(|e| format!("initial_state['{}'] is not valid JSON: {}", key, e))?;
+        object.insert(key.clone(), parsed);
+    }
+    let json = serde_json::to_string(&object).map_err(|e| e.to_string())?;
+    Ok(format!(
+        r#"
+    window.pytron = window.pytron || {{}};
+    window.pytron.initial_state = {};
+"#,
+        json
+    ))
+}
+
+impl ProtocolContext {
+    /// The app's own origins: `<scheme>://app` on WebKit, `https://<scheme>.<host>` on WebView2.
+    pub fn default_origins(scheme: &str) -> Vec<String> {
+        vec![
+            format!("{}://app", scheme),
+            format!("{}://localhost", scheme),
+            format!("https://{}.app", scheme),
+            format!("https://{}.localhost", scheme),
+        ]
+    }
+
+    fn cors_origin(&self, request: &Request<Vec<u8>>) -> Option<String> {
+        let origin = request.headers().get(header::ORIGIN).and_then(|o| o.to_str().ok());
+        let any = self.allowed_origins.iter().any(|o| o == "*");
+        match origin {
+            Some(o) if any || self.allowed_origins.iter().any(|a| a == o) => Some(o.to_string()),
+            None if any => Some("*".to_string()),
+            _ => None,
+        }
+    }
+}
+
+fn with_cors(builder: Builder, origin: &Option<String>) -> Builder {
+    match origin {
+        Some(o) => builder
+            .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, o.as_str())
+            .header(header::VARY, "Origin"),
+        None => builder,
+    }
+}
 
 pub fn handle_pytron_protocol(
     request: Request<Vec<u8>>,
-    protocol_root: PathBuf,
-    callbacks: Arc<Mutex<HashMap<String, PyObject>>>,
+    ctx: &ProtocolContext,
+) -> Response<Cow<'static, [u8]>> {
+    // Skip the clock entirely unless someone is listening
+    let traced = ctx.stats.is_some() || log::log_enabled!(target: "protocol", log::Level::Debug);
+    if !traced {
+        return serve(request, ctx);
+    }
+
+    let path = request.uri().path().to_string();
+    let started = Instant::now();
+    let response = serve(request, ctx);
+    let elapsed = started.elapsed();
+    let bytes = response.body().len();
+
+    log::debug!(target: "protocol", "{} {} {}B in {:.2?}", response.status().as_u16(), path, bytes, elapsed);
+    if let Some(stats) = &ctx.stats {
+        stats.record(response.status(), bytes, elapsed.as_micros() as u64);
+    }
+    response
+}
+
+fn serve(
+    request: Request<Vec<u8>>,
+    ctx: &ProtocolContext,
 ) -> Response<Cow<'static, [u8]>> {
     let uri = request.uri();
     let method = request.method();
+    let cors_origin = ctx.cors_origin(&request);
     
     // 1. Handle CORS Preflight
     if method == Method::OPTIONS {
-        return Response::builder()
-            .header("Access-Control-Allow-Origin", "*")
-            .header("Access-Control-Allow-Methods", "GET, POST, PUT, DELETE, OPTIONS")
-            .header("Access-Control-Allow-Headers", "*")
+        return with_cors(Response::builder(), &cors_origin)
+            .status(StatusCode::NO_CONTENT)
+            .header(header::ACCESS_CONTROL_ALLOW_METHODS, "GET, POST, PUT, DELETE, OPTIONS")
+            .header(header::ACCESS_CONTROL_ALLOW_HEADERS, "*")
+            .header(header::ACCESS_CONTROL_MAX_AGE, "86400")
+            .header(header::CONTENT_LENGTH, "0")
             .body(Cow::from(Vec::new())).unwrap();
     }
 
+    // Static files stay reachable for <img>/<script>, which can't carry headers; anything
+    // that runs Python or isn't a plain read needs the launch token
+    let authorized = match &ctx.token {
+        Some(token) => {
+            let header = request.headers().get(TOKEN_HEADER).and_then(|v| v.to_str().ok());
+            // `?pytron_token=` covers Python-served URLs used in src attributes
+            let query = uri.query().unwrap_or("").split('&').find_map(|kv| kv.strip_prefix("pytron_token="));
+            header == Some(token.as_str()) || query == Some(token.as_str())
+        }
+        None => true,
+    };
+    if !authorized && method != Method::GET && method != Method::HEAD {
+        return error_response(ctx, StatusCode::FORBIDDEN, &cors_origin);
+    }
+
     // 2. Extract the path correctly
     let path = uri.path().trim_start_matches('/');
     
@@ -35,106 +357,388 @@ pub fn handle_pytron_protocol(
             .unwrap();
     }
 
-    let decoded = urlencoding::decode(clean_path).unwrap_or(Cow::Borrowed(clean_path));
-    
-    // 4. Join with root and handle directories
-    let mut final_path = protocol_root.join(decoded.as_ref());
+    let decoded = decode_path(clean_path);
     
-    if final_path.is_dir() {
-        final_path = final_path.join("index.html");
-    }
-
-    match std::fs::read(&final_path) {
-        Ok(data) => {
-            let mime = mime_guess::from_path(&final_path).first_or_octet_stream();
-            let mime_str = mime.to_string();
-            let mut resp_data = data;
-
-            // Manual Bridge Injection
-            if mime.subtype() == "html" {
-                if let Ok(content) = String::from_utf8(resp_data.clone()) {
-                    let mut method_bindings = String::new();
-                    if let Ok(cbs) = callbacks.lock() {
-                        for name in cbs.keys() {
-                            method_bindings.push_str(&format!(
-                                "window['{}'] = (...args) => window.__pytron_native_bridge('{}', args);\n",
-                                name, name
-                            ));
-                        }
-                    }
-
-                    let bridge_script = format!(r#"
-                    <script>
-                    window.pytron_is_native = true;
-                    window.pytron = window.pytron || {{}};
-                    window.pytron.is_ready = true;
-                    window.__pytron_native_bridge = (method, args) => {{
-                        const seq = Math.random().toString(36).substring(2, 10);
-                        window.ipc.postMessage(JSON.stringify({{id: seq, method: method, params: args}}));
-                        return new Promise((resolve, reject) => {{
-                            window._rpc = window._rpc || {{}};
-                            window._rpc[seq] = {{resolve, reject}};
-                        }});
-                    }};
-                    window.pytron_close = () => window.__pytron_native_bridge('pytron_close', []);
-                    window.pytron_drag = () => window.__pytron_native_bridge('pytron_drag', []);
-                    window.pytron_log = (msg) => window.__pytron_native_bridge('pytron_log', [msg]);
-                    
-                    // Override alert to use native message box
-                    window.alert = (msg) => {{
-                        window.__pytron_native_bridge('pytron_message_box', ["Alert", String(msg), "info"]);
-                    }};
-                    {}
-                    </script>
-                    "#, method_bindings);
-
-                    let injected = if content.contains("</head>") {
-                        content.replace("</head>", &format!("{}</head>", bridge_script))
-                    } else {
-                        content.replace("<body>", &format!("<body>{}", bridge_script))
-                    };
-                    resp_data = injected.into_bytes();
-                }
+    // 4. Resolve against the embedded archive, then the asset root
+    match read_asset(ctx, decoded.as_ref()) {
+        Ok(asset) => {
+            if let Some(stats) = ctx.stats.as_ref().filter(|_| asset.archived) {
+                stats.cache_hits.fetch_add(1, Ordering::Relaxed);
             }
-
-            Response::builder()
-                .status(StatusCode::OK)
-                .header(header::CONTENT_TYPE, mime_str)
-                .header("Access-Control-Allow-Origin", "*")
-                .body(Cow::from(resp_data))
-                .unwrap()
+            file_response(ctx, &asset.path, asset.data, &cors_origin)
+        }
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            log::warn!(target: "protocol", "Failed to read {}: {}", decoded, e);
+            error_response(ctx, StatusCode::INTERNAL_SERVER_ERROR, &cors_origin)
         }
         Err(_) => {
             // Fallback to VAP
             let mut served_data: Option<(Vec<u8>, String)> = None;
-            let func_opt = {
-                if let Ok(cbs) = callbacks.lock() {
-                     cbs.get("pytron_serve_asset").map(|f| Python::with_gil(|py| f.clone_ref(py)))
-                } else {
-                    None
-                }
+            let func_opt = match ctx.callbacks.lock() {
+                Ok(cbs) if authorized => cbs.get("pytron_serve_asset").map(|f| Python::with_gil(|py| f.clone_ref(py))),
+                _ => None,
             };
 
             if let Some(func) = func_opt {
                  Python::with_gil(|py| {
-                     if let Ok(res) = func.call1(py, (decoded.as_ref(),)) {
-                         if let Ok((data, mime)) = res.extract::<(Vec<u8>, String)>(py) {
-                             served_data = Some((data, mime));
+                     match func.call1(py, (decoded.as_ref(),)) {
+                         Ok(res) => {
+                             if let Ok((data, mime)) = res.extract::<(Vec<u8>, String)>(py) {
+                                 served_data = Some((data, mime));
+                             }
                          }
+                         Err(e) => log::warn!(target: "protocol", "pytron_serve_asset failed for '{}': {}", decoded, e),
                      }
                  });
             }
 
             if let Some((data, mime)) = served_data {
-                 Response::builder()
+                 with_cors(Response::builder(), &cors_origin)
                     .status(StatusCode::OK)
                     .header(header::CONTENT_TYPE, mime)
-                    .header("Access-Control-Allow-Origin", "*")
                     .body(Cow::from(data))
                     .unwrap()
+            } else if let Some(index) = spa_index(ctx, decoded.as_ref()) {
+                // Client-side route: let the SPA router resolve it
+                file_response(ctx, &index.path, index.data, &cors_origin)
+            } else if !authorized {
+                log::debug!(target: "protocol", "Rejected {} without a valid token", decoded);
+                error_response(ctx, StatusCode::FORBIDDEN, &cors_origin)
             } else {
-                Response::builder().status(StatusCode::NOT_FOUND).body(Cow::from(Vec::new())).unwrap()
+                log::debug!(target: "protocol", "Not found: {}", decoded);
+                error_response(ctx, StatusCode::NOT_FOUND, &cors_origin)
             }
         }
     }
 }
+
+fn file_response(
+    ctx: &ProtocolContext,
+    final_path: &Path,
+    data: Vec<u8>,
+    cors_origin: &Option<String>,
+) -> Response<Cow<'static, [u8]>> {
+    let mime = mime_guess::from_path(final_path).first_or_octet_stream();
+    let mime_str = mime.to_string();
+    let mut resp_data = data;
+
+    // Manual Bridge Injection
+    if mime.subtype() == "html" {
+        if let Ok(content) = String::from_utf8(resp_data.clone()) {
+            let mut method_bindings = String::new();
+            if let Ok(cbs) = ctx.callbacks.lock() {
+                for name in cbs.keys() {
+                    method_bindings.push_str(&format!(
+                        "window['{}'] = (...args) => window.__pytron_native_bridge('{}', args);\n",
+                        name, name
+                    ));
+                }
+            }
+
+            let bridge_script = format!(r#"
+            <script>
+            window.pytron_is_native = true;
+            window.pytron = window.pytron || {{}};
+            window.pytron.is_ready = true;
+            // Keep the init script's bridge (and its batching) when it already ran
+            window.__pytron_native_bridge = window.__pytron_native_bridge || ((method, args) => {{
+                const seq = Math.random().toString(36).substring(2, 10);
+                window.ipc.postMessage(JSON.stringify({{id: seq, method: method, params: args}}));
+                return new Promise((resolve, reject) => {{
+                    window._rpc = window._rpc || {{}};
+                    window._rpc[seq] = {{resolve, reject}};
+                }});
+            }});
+            window.__pytron_settle = window.__pytron_settle || (results => {{
+                for (const [seq, status, value] of results) {{
+                    const call = window._rpc && window._rpc[seq];
+                    if (!call || status === 2) continue;
+                    delete window._rpc[seq];
+                    status === 0 ? call.resolve(value) : call.reject(value);
+                }}
+            }});
+            window.pytron_close = () => window.__pytron_native_bridge('pytron_close', []);
+            window.pytron_drag = () => window.__pytron_native_bridge('pytron_drag', []);
+            window.pytron_log = (msg) => window.__pytron_native_bridge('pytron_log', [msg]);
+            {}
+            window.prompt = (msg, def) => window.__pytron_native_bridge('pytron_prompt', [String(msg ?? ''), def == null ? '' : String(def)]);
+            {}
+            </script>
+            "#, if ctx.native_alert { ALERT_OVERRIDE } else { "" }, method_bindings);
+
+            let injected = if content.contains("</head>") {
+                content.replace("</head>", &format!("{}</head>", bridge_script))
+            } else {
+                content.replace("<body>", &format!("<body>{}", bridge_script))
+            };
+            resp_data = injected.into_bytes();
+        }
+    }
+
+    let mut builder = with_cors(Response::builder(), cors_origin)
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, mime_str);
+    // HTML is always revalidated so app updates show up; other assets get a short cache
+    if mime.subtype() != "html" {
+        builder = builder.header(header::CACHE_CONTROL, "public, max-age=3600");
+    }
+    if let Some(headers) = builder.headers_mut() {
+        apply_header_rules(ctx, final_path, headers);
+        if ctx.service_worker.as_deref().map(|sw| sw.trim_start_matches('/')) == Some(asset_rel(ctx, final_path).as_str()) {
+            // Let a worker served from a subfolder still claim the whole app
+            headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("text/javascript"));
+            headers.insert(HeaderName::from_static("service-worker-allowed"), HeaderValue::from_static("/"));
+            headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        }
+    }
+    builder.body(Cow::from(resp_data)).unwrap()
+}
+
+// "assets/app.js" for both disk paths under `root` and archive keys
+fn asset_rel(ctx: &ProtocolContext, path: &Path) -> String {
+    let full = path.to_string_lossy().replace('\\', "/");
+    let root = ctx.root.to_string_lossy().replace('\\', "/");
+    full.strip_prefix(root.as_str()).unwrap_or(&full).trim_start_matches('/').to_string()
+}
+
+/// Applies every matching rule, least specific (shortest pattern) first so
+/// that e.g. `sw.js` overrides `*.js`. Rule values replace existing headers.
+fn apply_header_rules(ctx: &ProtocolContext, path: &Path, headers: &mut HeaderMap) {
+    let full = asset_rel(ctx, path);
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+    let mut rules: Vec<_> = ctx.header_rules.iter()
+        .filter(|(pattern, _)| {
+            let target = if pattern.contains('/') { full.as_str() } else { name.as_str() };
+            glob_match(pattern, target)
+        })
+        .collect();
+    rules.sort_by_key(|(pattern, _)| pattern.len());
+
+    for (_, values) in rules {
+        for (k, v) in values {
+            if let (Ok(k), Ok(v)) = (HeaderName::from_bytes(k.as_bytes()), HeaderValue::from_str(v)) {
+                headers.insert(k, v);
+            }
+        }
+    }
+}
+
+/// `*` matches any run of characters, everything else is literal.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = text.strip_prefix(first) else { return false };
+    let tail: Vec<&str> = parts.collect();
+    let Some((last, middle)) = tail.split_last() else { return rest.is_empty() };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Percent-decodes a request path. Webviews send UTF-8 escapes, but a stray Latin-1 one
+/// (`caf%E9.png`) is decoded byte-per-char rather than leaving the whole path encoded.
+fn decode_path(path: &str) -> Cow<'_, str> {
+    match urlencoding::decode_binary(path.as_bytes()) {
+        Cow::Borrowed(_) => Cow::Borrowed(path),
+        Cow::Owned(bytes) => match String::from_utf8(bytes) {
+            Ok(text) => Cow::Owned(text),
+            Err(e) => Cow::Owned(decode_mixed(e.as_bytes())),
+        },
+    }
+}
+
+/// UTF-8 where it's valid; only the invalid runs fall back to Latin-1, so `图片/caf%E9.png`
+/// keeps its CJK segment intact.
+fn decode_mixed(mut bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len());
+    loop {
+        match std::str::from_utf8(bytes) {
+            Ok(valid) => {
+                text.push_str(valid);
+                return text;
+            }
+            Err(e) => {
+                let (valid, rest) = bytes.split_at(e.valid_up_to());
+                text.push_str(std::str::from_utf8(valid).unwrap_or_default());
+                let invalid = e.error_len().unwrap_or(rest.len());
+                text.extend(rest[..invalid].iter().map(|&b| char::from(b)));
+                bytes = &rest[invalid..];
+            }
+        }
+    }
+}
+
+/// Root `index.html` for extension-less misses when SPA fallback is on.
+fn spa_index(ctx: &ProtocolContext, path: &str) -> Option<Asset> {
+    if !ctx.spa_fallback || Path::new(path).extension().is_some() {
+        return None;
+    }
+    read_asset(ctx, "index.html").ok()
+}
+
+/// Schemes the webview engines handle themselves and won't hand to a custom protocol.
+pub const RESERVED_SCHEMES: &[&str] = &["http", "https", "file", "about", "data", "blob", "ws", "wss", "javascript"];
+
+/// Serves one request for a Python-registered scheme: the handler is called as
+/// `handler(method, url, headers, body)` and returns `(status, headers, body)`.
+/// Runs off the UI thread; exceptions become a 500.
+pub fn handle_custom_protocol(handler: &PyObject, scheme: &str, request: Request<Vec<u8>>) -> Response<Cow<'static, [u8]>> {
+    let method = request.method().to_string();
+    let url = request.uri().to_string();
+    let headers: HashMap<String, String> = request
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect();
+    let result = Python::with_gil(|py| {
+        let body = pyo3::types::PyBytes::new(py, request.body());
+        handler
+            .call1(py, (method, url.as_str(), headers, body))?
+            .extract::<(u16, Option<HashMap<String, String>>, Vec<u8>)>(py)
+    });
+    let (status, headers, body) = match result {
+        Ok(response) => response,
+        Err(e) => {
+            log::warn!(target: "protocol", "{}:// handler failed for {}: {}", scheme, url, e);
+            return Response::builder().status(StatusCode::INTERNAL_SERVER_ERROR).body(Cow::from(Vec::new())).unwrap();
+        }
+    };
+    let mut builder = Response::builder().status(status);
+    for (name, value) in headers.unwrap_or_default() {
+        builder = builder.header(name, value);
+    }
+    builder.body(Cow::from(body)).unwrap_or_else(|e| {
+        log::warn!(target: "protocol", "{}:// handler returned an invalid response for {}: {}", scheme, url, e);
+        Response::builder().status(StatusCode::INTERNAL_SERVER_ERROR).body(Cow::from(Vec::new())).unwrap()
+    })
+}
+
+/// The app's page for `status` if it has one, an empty body otherwise.
+fn error_response(
+    ctx: &ProtocolContext,
+    status: StatusCode,
+    cors_origin: &Option<String>,
+) -> Response<Cow<'static, [u8]>> {
+    let page = match ctx.error_pages.get(&status.as_u16()) {
+        Some(p) => p.clone(),
+        None => format!("{}.html", status.as_u16()),
+    };
+    let builder = with_cors(Response::builder(), cors_origin).status(status);
+    match read_asset(ctx, &page) {
+        Ok(asset) => builder
+            .header(header::CONTENT_TYPE, "text/html")
+            .body(Cow::from(asset.data))
+            .unwrap(),
+        Err(_) => builder.body(Cow::from(Vec::new())).unwrap(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(root: PathBuf) -> ProtocolContext {
+        ProtocolContext {
+            root,
+            callbacks: Arc::new(Mutex::new(HashMap::new())),
+            allowed_origins: Vec::new(),
+            native_alert: false,
+            spa_fallback: false,
+            error_pages: HashMap::new(),
+            header_rules: HashMap::new(),
+            service_worker: None,
+            archive: None,
+            stats: None,
+            token: None,
+        }
+    }
+
+    fn get(ctx: &ProtocolContext, uri: &str) -> Response<Cow<'static, [u8]>> {
+        handle_pytron_protocol(Request::builder().uri(uri).body(Vec::new()).unwrap(), ctx)
+    }
+
+    #[test]
+    fn decodes_utf8_and_stray_latin1_escapes() {
+        assert_eq!(decode_path("caf%C3%A9.png"), "café.png");
+        assert_eq!(decode_path("caf%E9.png"), "café.png");
+        assert_eq!(decode_path("%E5%9B%BE%E7%89%87/caf%E9.png"), "图片/café.png");
+        assert_eq!(decode_path("plain/path.png"), "plain/path.png");
+    }
+
+    #[test]
+    fn serves_non_ascii_asset_names() {
+        let root = std::env::temp_dir().join(format!("pytron-assets-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        // Stored decomposed, as macOS does, but requested composed
+        std::fs::write(root.join("cafe\u{301}.png"), b"cafe").unwrap();
+        std::fs::write(root.join("图片.png"), b"cjk").unwrap();
+        let ctx = context(root.clone());
+
+        let cafe = get(&ctx, "pytron://app/caf%C3%A9.png");
+        let cjk = get(&ctx, "pytron://app/%E5%9B%BE%E7%89%87.png");
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(cafe.status(), StatusCode::OK);
+        assert_eq!(cafe.body().as_ref(), b"cafe");
+        assert_eq!(cjk.status(), StatusCode::OK);
+        assert_eq!(cjk.body().as_ref(), b"cjk");
+    }
+
+    #[test]
+    fn token_travels_in_header_or_query() {
+        let root = std::env::temp_dir().join(format!("pytron-token-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("a.txt"), b"a").unwrap();
+        let mut ctx = context(root.clone());
+        ctx.token = Some("t0k".to_string());
+        let post = |uri: &str, token: Option<&str>| {
+            let mut req = Request::builder().method(Method::POST).uri(uri);
+            if let Some(token) = token {
+                req = req.header(TOKEN_HEADER, token);
+            }
+            handle_pytron_protocol(req.body(Vec::new()).unwrap(), &ctx)
+        };
+
+        let plain = get(&ctx, "pytron://app/a.txt");
+        let missing = post("pytron://app/a.txt", None);
+        let wrong = post("pytron://app/a.txt?pytron_token=nope", None);
+        let header = post("pytron://app/a.txt", Some("t0k"));
+        let query = post("pytron://app/a.txt?pytron_token=t0k", None);
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(plain.status(), StatusCode::OK);
+        assert_eq!(missing.status(), StatusCode::FORBIDDEN);
+        assert_eq!(wrong.status(), StatusCode::FORBIDDEN);
+        assert_eq!(header.status(), StatusCode::OK);
+        assert_eq!(query.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn cache_hits_count_only_archive_assets_that_are_served() {
+        let files = HashMap::from([
+            ("index.html".to_string(), b"index".to_vec()),
+            ("caf\u{e9}.png".to_string(), b"cafe".to_vec()),
+            ("404.html".to_string(), b"missing".to_vec()),
+        ]);
+        let mut ctx = context(std::env::temp_dir().join("pytron-no-such-root"));
+        ctx.archive = Some(AssetArchive { files });
+        ctx.spa_fallback = true;
+        let stats = Arc::new(ProtocolStats::default());
+        ctx.stats = Some(stats.clone());
+
+        assert_eq!(get(&ctx, "pytron://app/index.html").status(), StatusCode::OK);
+        // Composed entry found through the decomposed retry: still one hit
+        assert_eq!(get(&ctx, "pytron://app/cafe%CC%81.png").status(), StatusCode::OK);
+        // SPA fallback and error page both come from the archive but aren't the requested asset
+        assert_eq!(get(&ctx, "pytron://app/some/route").body().as_ref(), b"index");
+        assert_eq!(get(&ctx, "pytron://app/gone.png").status(), StatusCode::NOT_FOUND);
+
+        assert_eq!(stats.snapshot()["cache_hits"], 2);
+        assert_eq!(stats.snapshot()["requests"], 4);
+    }
+}
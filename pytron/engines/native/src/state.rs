@@ -1,14 +1,101 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use pyo3::prelude::*;
-use wry::WebView;
+use wry::{WebContext, WebView};
 use tao::window::Window;
-use tray_icon::TrayIcon;
+use tray_icon::{Icon, TrayIcon};
+use tray_icon::menu::Menu;
+use tray_icon::menu::accelerator::{Code, Modifiers};
+use tao::event_loop::EventLoopProxy;
+use crate::events::UserEvent;
+
+/// Every webview created in this process, so `broadcast` can reach windows it doesn't own.
+static LIVE_WEBVIEWS: Mutex<Vec<EventLoopProxy<UserEvent>>> = Mutex::new(Vec::new());
 
 pub struct RuntimeState {
     pub webview: WebView,
     pub window: Window,
     pub callbacks: Arc<Mutex<HashMap<String, PyObject>>>,
+    pub context_callbacks: HashSet<String>, // Bound with pass_context: get the context JSON instead of 0
     pub tray: Option<TrayIcon>,
+    pub tray_icon_path: Option<String>, // Static icon restored when an animation stops
+    pub tray_animation: Option<TrayAnimation>,
+    pub context_menu: Option<Menu>, // Kept alive until the next popup so its click event resolves
     pub prevent_close: bool,
+    pub minimize_to_tray: bool, // Close/minimize hide the window while a tray icon exists
+    pub close_to_tray: bool, // Only close hides; the tray's Quit item exits
+    pub suspended: bool,
+    pub auto_suspend: Option<u64>, // Seconds hidden before the webview is suspended
+    pub visibility_epoch: u64, // Bumped on every show/hide so stale auto-suspend timers are ignored
+    pub current_url: String, // Last fully loaded page, used to recover from renderer crashes
+    pub reload_on_crash: bool,
+    pub accelerators: Vec<(Modifiers, Code, String)>, // Window-local shortcuts and their ids
+    pub quitting: bool, // A graceful quit is in progress; further requests are ignored
+    pub injected_css: Vec<(String, String)>, // (style id, css), re-applied after every page load
+    pub aspect_ratio: Option<(u32, u32)>, // Kept while resizing by adjusting the dimension the user didn't drag
+    pub last_size: (u32, u32), // Inner size before the latest resize, to tell which side moved
+    pub hang_watchdog: Option<HangWatchdog>,
+    pub app_menu: Option<Menu>, // macOS Edit menu, so Cmd+C/V/X/A reach the webview
+    pub pending_returns: Vec<(String, i32, String)>, // (seq, status, json) not yet delivered to the page
+    pub web_context: Option<WebContext>, // Declared after `webview` so it outlives it
+}
+
+/// Decoded frames of a running tray animation. Dropping it stops the timer thread.
+pub struct TrayAnimation {
+    pub frames: Vec<Icon>,
+    pub index: usize,
+    pub running: Arc<AtomicBool>,
+}
+
+impl Drop for TrayAnimation {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Pings the page on a timer and counts unanswered pings. Dropping it stops the timer thread.
+pub struct HangWatchdog {
+    pub misses_allowed: u32,
+    pub missed: u32,
+    pub awaiting: bool, // A ping is out and no pong has come back yet
+    pub reported: bool, // The current hang was already dispatched
+    pub running: Arc<AtomicBool>,
+}
+
+impl Drop for HangWatchdog {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+}
+
+pub fn register_webview(proxy: EventLoopProxy<UserEvent>) {
+    if let Ok(mut live) = LIVE_WEBVIEWS.lock() {
+        live.push(proxy);
+    }
+}
+
+/// Sends `Emit` to every live webview and drops the ones whose loop has exited.
+/// Returns how many webviews received it.
+pub fn broadcast(event: &str, detail: &str) -> usize {
+    send_to_all(|| UserEvent::Emit(event.to_string(), detail.to_string()))
+}
+
+/// Sends a fresh `make()` to every live webview, dropping exited ones. Returns how many got it.
+pub fn send_to_all(make: impl Fn() -> UserEvent) -> usize {
+    send_to_all_or(make, || {})
+}
+
+/// `send_to_all` that runs `when_empty` under the registry lock if no webview is left, so a
+/// webview registering at the same moment sees its effect (e.g. a poller's flag being reset).
+pub fn send_to_all_or(make: impl Fn() -> UserEvent, when_empty: impl FnOnce()) -> usize {
+    let Ok(mut live) = LIVE_WEBVIEWS.lock() else {
+        when_empty();
+        return 0;
+    };
+    live.retain(|proxy| proxy.send_event(make()).is_ok());
+    if live.is_empty() {
+        when_empty();
+    }
+    live.len()
 }
@@ -0,0 +1,428 @@
+use std::sync::mpsc::Sender;
+use wry::WebView;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BrowsingData {
+    Cache,
+    Cookies,
+    All,
+}
+
+/// Starts clearing `kind` from the webview's profile; `done(success)` fires on the
+/// UI thread once the platform reports completion.
+pub fn clear_browsing_data(webview: &WebView, kind: BrowsingData, done: impl FnOnce(bool) + Send + 'static) {
+    if kind == BrowsingData::All {
+        // wry already handles every platform for the full wipe; it has no completion signal
+        let res = webview.clear_all_browsing_data();
+        if let Err(e) = &res {
+            log::warn!(target: "native", "Clearing browsing data failed: {}", e);
+        }
+        done(res.is_ok());
+        return;
+    }
+
+    #[cfg(target_os = "windows")]
+    unsafe {
+        use webview2_com::ClearBrowsingDataCompletedHandler;
+        use webview2_com::Microsoft::Web::WebView2::Win32::*;
+        use windows::core::Interface;
+        use wry::WebViewExtWindows;
+
+        let kinds = match kind {
+            BrowsingData::Cache => COREWEBVIEW2_BROWSING_DATA_KINDS_DISK_CACHE | COREWEBVIEW2_BROWSING_DATA_KINDS_CACHE_STORAGE,
+            _ => COREWEBVIEW2_BROWSING_DATA_KINDS_COOKIES,
+        };
+        let profile = webview
+            .controller()
+            .CoreWebView2()
+            .and_then(|w| w.cast::<ICoreWebView2_13>())
+            .and_then(|w| w.Profile())
+            .and_then(|p| p.cast::<ICoreWebView2Profile2>());
+        match profile {
+            Ok(profile) => {
+                let handler = ClearBrowsingDataCompletedHandler::create(Box::new(move |res| {
+                    done(res.is_ok());
+                    Ok(())
+                }));
+                if let Err(e) = profile.ClearBrowsingData(kinds, &handler) {
+                    log::warn!(target: "native", "ClearBrowsingData failed: {}", e);
+                }
+            }
+            Err(e) => {
+                log::warn!(target: "native", "WebView2 runtime lacks ClearBrowsingData: {}", e);
+                done(false);
+            }
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use webkit2gtk::{WebContextExt, WebViewExt, WebsiteDataManagerExtManual, WebsiteDataTypes};
+        use wry::WebViewExtUnix;
+
+        let types = match kind {
+            BrowsingData::Cache => WebsiteDataTypes::DISK_CACHE | WebsiteDataTypes::MEMORY_CACHE | WebsiteDataTypes::OFFLINE_APPLICATION_CACHE,
+            _ => WebsiteDataTypes::COOKIES,
+        };
+        match webview.webview().context().and_then(|c| c.website_data_manager()) {
+            Some(manager) => manager.clear(
+                types,
+                gtk::glib::TimeSpan::from_seconds(0),
+                None::<&gtk::gio::Cancellable>,
+                move |res| done(res.is_ok()),
+            ),
+            None => done(false),
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    unsafe {
+        use objc::runtime::Object;
+        use objc::{class, msg_send, sel, sel_impl};
+        use wry::WebViewExtMacOS;
+
+        let names: &[&[u8]] = match kind {
+            BrowsingData::Cache => &[b"WKWebsiteDataTypeDiskCache\0", b"WKWebsiteDataTypeMemoryCache\0"],
+            _ => &[b"WKWebsiteDataTypeCookies\0"],
+        };
+        let types: *mut Object = msg_send![class!(NSMutableSet), set];
+        for name in names {
+            let s: *mut Object = msg_send![class!(NSString), stringWithUTF8String: name.as_ptr()];
+            let _: () = msg_send![types, addObject: s];
+        }
+        let config: *mut Object = msg_send![webview.webview(), configuration];
+        let store: *mut Object = msg_send![config, websiteDataStore];
+        let since: *mut Object = msg_send![class!(NSDate), dateWithTimeIntervalSince1970: 0.0];
+        let done = std::cell::Cell::new(Some(done));
+        let handler = block::ConcreteBlock::new(move || {
+            if let Some(f) = done.take() { f(true); }
+        }).copy();
+        let _: () = msg_send![store, removeDataOfTypes: types modifiedSince: since completionHandler: handler];
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CookieData {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    pub path: String,
+    /// Unix seconds, `None` for a session cookie.
+    pub expires: Option<f64>,
+    pub http_only: bool,
+    pub secure: bool,
+}
+
+pub enum CookieCommand {
+    Get(String, Sender<Vec<CookieData>>),           // Url
+    Set(String, CookieData, Sender<bool>),          // Url, cookie (empty domain/path default from url)
+    Delete(String, String, Sender<bool>),           // Url, name
+}
+
+/// Runs a cookie command against the platform cookie store. All of these are
+/// async underneath; results come back through the command's sender.
+pub fn run_cookie_command(webview: &WebView, cmd: CookieCommand) {
+    match cmd {
+        CookieCommand::Get(url, tx) => get_cookies(webview, &url, move |c| { let _ = tx.send(c); }),
+        CookieCommand::Set(url, mut cookie, tx) => {
+            let parsed = url::Url::parse(&url).ok();
+            if cookie.domain.is_empty() {
+                cookie.domain = parsed.as_ref().and_then(|u| u.host_str()).unwrap_or_default().to_string();
+            }
+            if cookie.path.is_empty() {
+                cookie.path = "/".to_string();
+            }
+            set_cookie(webview, &url, cookie, move |ok| { let _ = tx.send(ok); })
+        }
+        CookieCommand::Delete(url, name, tx) => delete_cookie(webview, &url, &name, move |ok| { let _ = tx.send(ok); }),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn cookie_manager(webview: &WebView) -> windows::core::Result<webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2CookieManager> {
+    use webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2_2;
+    use windows::core::Interface;
+    use wry::WebViewExtWindows;
+    unsafe { webview.controller().CoreWebView2()?.cast::<ICoreWebView2_2>()?.CookieManager() }
+}
+
+#[cfg(target_os = "linux")]
+fn cookie_manager(webview: &WebView) -> Option<webkit2gtk::CookieManager> {
+    use webkit2gtk::{WebContextExt, WebViewExt, WebsiteDataManagerExt};
+    use wry::WebViewExtUnix;
+    webview.webview().context()?.website_data_manager()?.cookie_manager()
+}
+
+#[cfg(target_os = "linux")]
+fn from_soup(mut c: soup::Cookie) -> CookieData {
+    CookieData {
+        name: c.name().map(|s| s.to_string()).unwrap_or_default(),
+        value: c.value().map(|s| s.to_string()).unwrap_or_default(),
+        domain: c.domain().map(|s| s.to_string()).unwrap_or_default(),
+        path: c.path().map(|s| s.to_string()).unwrap_or_default(),
+        expires: c.expires().map(|d| d.to_unix() as f64),
+        http_only: c.is_http_only(),
+        secure: c.is_secure(),
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod cocoa_cookies {
+    use objc::runtime::{Object, BOOL, NO};
+    use objc::{class, msg_send, sel, sel_impl};
+    use super::CookieData;
+
+    pub type Id = *mut Object;
+
+    pub unsafe fn ns_string(s: &str) -> Id {
+        let c = std::ffi::CString::new(s).unwrap_or_default();
+        msg_send![class!(NSString), stringWithUTF8String: c.as_ptr()]
+    }
+
+    pub unsafe fn rust_string(s: Id) -> String {
+        if s.is_null() {
+            return String::new();
+        }
+        let ptr: *const std::os::raw::c_char = msg_send![s, UTF8String];
+        std::ffi::CStr::from_ptr(ptr).to_string_lossy().to_string()
+    }
+
+    pub unsafe fn cookie_store(webview: Id) -> Id {
+        let config: Id = msg_send![webview, configuration];
+        let store: Id = msg_send![config, websiteDataStore];
+        msg_send![store, httpCookieStore]
+    }
+
+    pub unsafe fn to_data(c: Id) -> CookieData {
+        let expires: Id = msg_send![c, expiresDate];
+        let http_only: BOOL = msg_send![c, isHTTPOnly];
+        let secure: BOOL = msg_send![c, isSecure];
+        CookieData {
+            name: rust_string(msg_send![c, name]),
+            value: rust_string(msg_send![c, value]),
+            domain: rust_string(msg_send![c, domain]),
+            path: rust_string(msg_send![c, path]),
+            expires: if expires.is_null() { None } else { Some(msg_send![expires, timeIntervalSince1970]) },
+            http_only: http_only != NO,
+            secure: secure != NO,
+        }
+    }
+
+    /// `NSHTTPCookie cookieWithProperties:`; the property keys are plain strings.
+    pub unsafe fn from_data(url: &str, c: &CookieData) -> Id {
+        let props: Id = msg_send![class!(NSMutableDictionary), dictionary];
+        let set = |k: &str, v: Id| { let _: () = msg_send![props, setObject: v forKey: ns_string(k)]; };
+        set("Name", ns_string(&c.name));
+        set("Value", ns_string(&c.value));
+        set("Domain", ns_string(&c.domain));
+        set("Path", ns_string(&c.path));
+        set("OriginURL", ns_string(url));
+        if let Some(exp) = c.expires {
+            set("Expires", msg_send![class!(NSDate), dateWithTimeIntervalSince1970: exp]);
+        }
+        if c.secure {
+            set("Secure", ns_string("TRUE"));
+        }
+        if c.http_only {
+            set("HttpOnly", ns_string("TRUE"));
+        }
+        msg_send![class!(NSHTTPCookie), cookieWithProperties: props]
+    }
+
+    /// Every cookie in the store, handed to `f` on the main thread.
+    pub unsafe fn all_cookies(webview: Id, f: impl FnOnce(Vec<Id>) + 'static) {
+        let f = std::cell::Cell::new(Some(f));
+        let handler = block::ConcreteBlock::new(move |cookies: Id| {
+            let count: usize = msg_send![cookies, count];
+            let list = (0..count).map(|i| msg_send![cookies, objectAtIndex: i]).collect();
+            if let Some(f) = f.take() { f(list); }
+        }).copy();
+        let _: () = msg_send![cookie_store(webview), getAllCookies: handler];
+    }
+
+    pub fn domain_matches(cookie_domain: &str, host: &str) -> bool {
+        let d = cookie_domain.trim_start_matches('.');
+        host == d || host.ends_with(&format!(".{}", d))
+    }
+}
+
+#[allow(unused_variables)]
+fn get_cookies(webview: &WebView, url: &str, done: impl FnOnce(Vec<CookieData>) + Send + 'static) {
+    #[cfg(target_os = "windows")]
+    unsafe {
+        use webview2_com::{take_pwstr, GetCookiesCompletedHandler};
+        use windows::core::{HSTRING, PCWSTR, PWSTR};
+        use windows::Win32::Foundation::BOOL;
+
+        let manager = match cookie_manager(webview) {
+            Ok(m) => m,
+            Err(e) => {
+                log::warn!(target: "native", "Cookie manager unavailable: {}", e);
+                return done(Vec::new());
+            }
+        };
+        let handler = GetCookiesCompletedHandler::create(Box::new(move |res, list| {
+            let mut out = Vec::new();
+            if let (Ok(()), Some(list)) = (res, list) {
+                let mut count = 0u32;
+                list.Count(&mut count)?;
+                for i in 0..count {
+                    let c = list.GetValueAtIndex(i)?;
+                    let read = |f: &dyn Fn(*mut PWSTR) -> windows::core::Result<()>| {
+                        let mut s = PWSTR::null();
+                        f(&mut s).map(|_| take_pwstr(s)).unwrap_or_default()
+                    };
+                    let mut expires = 0f64;
+                    let (mut http_only, mut secure, mut session) = (BOOL::default(), BOOL::default(), BOOL::default());
+                    c.Expires(&mut expires)?;
+                    c.IsHttpOnly(&mut http_only)?;
+                    c.IsSecure(&mut secure)?;
+                    c.IsSession(&mut session)?;
+                    out.push(CookieData {
+                        name: read(&|p| c.Name(p)),
+                        value: read(&|p| c.Value(p)),
+                        domain: read(&|p| c.Domain(p)),
+                        path: read(&|p| c.Path(p)),
+                        expires: if session.as_bool() { None } else { Some(expires) },
+                        http_only: http_only.as_bool(),
+                        secure: secure.as_bool(),
+                    });
+                }
+            }
+            done(out);
+            Ok(())
+        }));
+        let uri = HSTRING::from(url);
+        if let Err(e) = manager.GetCookies(PCWSTR(uri.as_ptr()), &handler) {
+            log::warn!(target: "native", "GetCookies failed: {}", e);
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use webkit2gtk::CookieManagerExt;
+        match cookie_manager(webview) {
+            Some(m) => m.cookies(url, None::<&gtk::gio::Cancellable>, move |res| {
+                done(res.map(|list| list.into_iter().map(from_soup).collect()).unwrap_or_default())
+            }),
+            None => done(Vec::new()),
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    unsafe {
+        use wry::WebViewExtMacOS;
+        let host = url::Url::parse(url).ok().and_then(|u| u.host_str().map(String::from)).unwrap_or_default();
+        cocoa_cookies::all_cookies(webview.webview(), move |list| {
+            let out = list.into_iter()
+                .map(|c| cocoa_cookies::to_data(c))
+                .filter(|c| cocoa_cookies::domain_matches(&c.domain, &host))
+                .collect();
+            done(out);
+        });
+    }
+}
+
+#[allow(unused_variables)]
+fn set_cookie(webview: &WebView, url: &str, cookie: CookieData, done: impl FnOnce(bool) + Send + 'static) {
+    #[cfg(target_os = "windows")]
+    unsafe {
+        use windows::core::{HSTRING, PCWSTR};
+        let res = cookie_manager(webview).and_then(|m| {
+            let (name, value, domain, path) = (
+                HSTRING::from(cookie.name.as_str()),
+                HSTRING::from(cookie.value.as_str()),
+                HSTRING::from(cookie.domain.as_str()),
+                HSTRING::from(cookie.path.as_str()),
+            );
+            let c = m.CreateCookie(PCWSTR(name.as_ptr()), PCWSTR(value.as_ptr()), PCWSTR(domain.as_ptr()), PCWSTR(path.as_ptr()))?;
+            c.SetExpires(cookie.expires.unwrap_or(-1.0))?;
+            c.SetIsHttpOnly(windows::Win32::Foundation::BOOL::from(cookie.http_only))?;
+            c.SetIsSecure(windows::Win32::Foundation::BOOL::from(cookie.secure))?;
+            m.AddOrUpdateCookie(&c)
+        });
+        if let Err(e) = &res {
+            log::warn!(target: "native", "Setting cookie '{}' failed: {}", cookie.name, e);
+        }
+        done(res.is_ok());
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use webkit2gtk::CookieManagerExt;
+        let Some(m) = cookie_manager(webview) else { return done(false) };
+        let mut c = soup::Cookie::new(&cookie.name, &cookie.value, &cookie.domain, &cookie.path, -1);
+        if let Some(exp) = cookie.expires.and_then(|e| gtk::glib::DateTime::from_unix_utc(e as i64).ok()) {
+            c.set_expires(&exp);
+        }
+        c.set_http_only(cookie.http_only);
+        c.set_secure(cookie.secure);
+        m.add_cookie(&mut c, None::<&gtk::gio::Cancellable>, move |res| done(res.is_ok()));
+    }
+
+    #[cfg(target_os = "macos")]
+    unsafe {
+        use objc::{msg_send, sel, sel_impl};
+        use wry::WebViewExtMacOS;
+        let c = cocoa_cookies::from_data(url, &cookie);
+        if c.is_null() {
+            return done(false);
+        }
+        let done = std::cell::Cell::new(Some(done));
+        let handler = block::ConcreteBlock::new(move || {
+            if let Some(f) = done.take() { f(true); }
+        }).copy();
+        let _: () = msg_send![cocoa_cookies::cookie_store(webview.webview()), setCookie: c completionHandler: handler];
+    }
+}
+
+#[allow(unused_variables)]
+fn delete_cookie(webview: &WebView, url: &str, name: &str, done: impl FnOnce(bool) + Send + 'static) {
+    #[cfg(target_os = "windows")]
+    unsafe {
+        use windows::core::{HSTRING, PCWSTR};
+        let (name, uri) = (HSTRING::from(name), HSTRING::from(url));
+        let res = cookie_manager(webview).and_then(|m| m.DeleteCookies(PCWSTR(name.as_ptr()), PCWSTR(uri.as_ptr())));
+        done(res.is_ok());
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use webkit2gtk::CookieManagerExt;
+        let Some(m) = cookie_manager(webview) else { return done(false) };
+        let name = name.to_string();
+        let manager = m.clone();
+        m.cookies(url, None::<&gtk::gio::Cancellable>, move |res| {
+            let ok = res.is_ok();
+            let mut matching: Vec<soup::Cookie> = res
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|c| c.clone().name().as_deref() == Some(name.as_str()))
+                .collect();
+            for c in matching.iter_mut() {
+                manager.delete_cookie(c, None::<&gtk::gio::Cancellable>, |_| {});
+            }
+            done(ok);
+        });
+    }
+
+    #[cfg(target_os = "macos")]
+    unsafe {
+        use objc::{msg_send, sel, sel_impl};
+        use wry::WebViewExtMacOS;
+        let host = url::Url::parse(url).ok().and_then(|u| u.host_str().map(String::from)).unwrap_or_default();
+        let name = name.to_string();
+        let view = webview.webview();
+        cocoa_cookies::all_cookies(view, move |list| {
+            let store = cocoa_cookies::cookie_store(view);
+            for c in list {
+                let data = cocoa_cookies::to_data(c);
+                if data.name == name && cocoa_cookies::domain_matches(&data.domain, &host) {
+                    let noop = block::ConcreteBlock::new(|| {}).copy();
+                    let _: () = msg_send![store, deleteCookie: c completionHandler: noop];
+                }
+            }
+            done(true);
+        });
+    }
+}
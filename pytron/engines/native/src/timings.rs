@@ -0,0 +1,44 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+// Startup phases land in `sys.pytron_timings` as milliseconds since `sys.pytron_start_time`
+// (epoch seconds). The secure loader sets both before importing the app; without it the
+// first recorded phase becomes the zero point.
+
+fn now() -> f64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or_default()
+}
+
+/// Records `phase` once; later calls for the same phase are ignored.
+pub fn record(phase: &str) {
+    let at = now();
+    Python::with_gil(|py| {
+        if let Err(e) = record_at(py, phase, at) {
+            log::debug!(target: "native", "Could not record startup timing '{}': {}", phase, e);
+        }
+    });
+}
+
+fn record_at(py: Python<'_>, phase: &str, at: f64) -> PyResult<()> {
+    let sys = py.import("sys")?;
+    let start = match sys.getattr("pytron_start_time").and_then(|s| s.extract::<f64>()) {
+        Ok(start) => start,
+        Err(_) => {
+            sys.setattr("pytron_start_time", at)?;
+            at
+        }
+    };
+    let timings = match sys.getattr("pytron_timings").and_then(|t| t.downcast_into::<PyDict>().map_err(PyErr::from)) {
+        Ok(t) => t,
+        Err(_) => {
+            let t = PyDict::new(py);
+            sys.setattr("pytron_timings", &t)?;
+            t
+        }
+    };
+    if !timings.contains(phase)? {
+        timings.set_item(phase, ((at - start) * 1000.0).max(0.0))?;
+    }
+    Ok(())
+}
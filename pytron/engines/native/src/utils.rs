@@ -1,4 +1,7 @@
+use std::future::Future;
 use std::panic;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake};
 
 pub fn setup_panic_hook() {
     static ONCE: std::sync::Once = std::sync::Once::new();
@@ -25,6 +28,94 @@ impl<T> SendWrapper<T> {
     pub fn take(self) -> T { self.0 } 
 }
 
+/// Platform tag plus the raw native handle (HWND, NSView, X11 window id, wl_surface).
+pub fn raw_window_handle(window: &tao::window::Window) -> (String, usize) {
+    use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+    let Ok(handle) = window.window_handle() else {
+        return ("unknown".to_string(), 0);
+    };
+    let (tag, raw) = match handle.as_raw() {
+        RawWindowHandle::Win32(h) => ("win32", h.hwnd.get() as usize),
+        RawWindowHandle::AppKit(h) => ("appkit", h.ns_view.as_ptr() as usize),
+        RawWindowHandle::Xlib(h) => ("xlib", h.window as usize),
+        RawWindowHandle::Xcb(h) => ("xcb", h.window.get() as usize),
+        RawWindowHandle::Wayland(h) => ("wayland", h.surface.as_ptr() as usize),
+        _ => ("unknown", 0),
+    };
+    (tag.to_string(), raw)
+}
+
+// 3x5 bitmap digits for taskbar overlay badges
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// Parses `#RRGGBB` or `#RRGGBBAA` (leading `#` optional).
+pub fn parse_hex_color(color: &str) -> Option<(u8, u8, u8, u8)> {
+    let hex = color.trim().trim_start_matches('#');
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Some((channel(0)?, channel(2)?, channel(4)?, alpha))
+}
+
+/// Renders a 16x16 red badge with up to two digits (a plain dot for non-numeric labels).
+pub fn render_badge_rgba(label: &str) -> (Vec<u8>, u32, u32) {
+    const SIZE: usize = 16;
+    let mut rgba = vec![0u8; SIZE * SIZE * 4];
+    let mut put = |x: usize, y: usize, c: [u8; 4]| {
+        if x < SIZE && y < SIZE {
+            rgba[(y * SIZE + x) * 4..(y * SIZE + x) * 4 + 4].copy_from_slice(&c);
+        }
+    };
+
+    // Filled circle
+    let r = SIZE as f32 / 2.0;
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let (dx, dy) = (x as f32 + 0.5 - r, y as f32 + 0.5 - r);
+            if dx * dx + dy * dy <= r * r {
+                put(x, y, [0xE8, 0x11, 0x23, 0xFF]);
+            }
+        }
+    }
+
+    let digits: Vec<usize> = match label.trim().parse::<u64>() {
+        Ok(n) => n.min(99).to_string().chars().filter_map(|c| c.to_digit(10)).map(|d| d as usize).collect(),
+        Err(_) => Vec::new(),
+    };
+    // Single digits are drawn at 2x, two digits at 1x + 1px spacing
+    let scale = if digits.len() == 1 { 2 } else { 1 };
+    let width = digits.len() * 3 * scale + digits.len().saturating_sub(1);
+    let (ox, oy) = ((SIZE - width) / 2, (SIZE - 5 * scale) / 2);
+    for (i, d) in digits.iter().enumerate() {
+        let gx = ox + i * (3 * scale + 1);
+        for (row, bits) in DIGITS[*d].iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) != 0 {
+                    for sy in 0..scale {
+                        for sx in 0..scale {
+                            put(gx + col * scale + sx, oy + row * scale + sy, [0xFF, 0xFF, 0xFF, 0xFF]);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    (rgba, SIZE as u32, SIZE as u32)
+}
+
 pub fn load_icon(path: &std::path::Path) -> Result<tray_icon::Icon, Box<dyn std::error::Error>> {
     let image = image::open(path)?;
     let rgba = image.to_rgba8();
@@ -32,3 +123,21 @@ pub fn load_icon(path: &std::path::Path) -> Result<tray_icon::Icon, Box<dyn std:
     let rgba_bytes = rgba.into_raw();
     Ok(tray_icon::Icon::from_rgba(rgba_bytes, width, height)?)
 }
+
+/// Minimal executor for rfd's async dialogs: parks the current thread until the future resolves.
+pub fn block_on<F: Future>(fut: F) -> F::Output {
+    struct ThreadWaker(std::thread::Thread);
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) { self.0.unpark(); }
+    }
+
+    let waker = Arc::new(ThreadWaker(std::thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut fut = std::pin::pin!(fut);
+    loop {
+        match fut.as_mut().poll(&mut cx) {
+            Poll::Ready(v) => return v,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
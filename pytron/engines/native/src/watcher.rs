@@ -0,0 +1,76 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+use notify::{EventKind, RecursiveMode, Watcher};
+use tao::event_loop::EventLoopProxy;
+use crate::events::UserEvent;
+
+/// Quiet period after the last change before a reload is sent.
+const DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Dispatched on `window` before reloading. Calling `preventDefault()` lets the page
+/// apply the change itself; stylesheet-only changes are swapped in place by default.
+pub const HMR_SCRIPT: &str = r#"(function(paths) {
+    const ev = new CustomEvent('pytron:hmr', { detail: { paths }, cancelable: true });
+    if (!window.dispatchEvent(ev)) return;
+    if (paths.every(p => p.endsWith('.css'))) {
+        const stamp = Date.now();
+        document.querySelectorAll('link[rel="stylesheet"]').forEach(link => {
+            const url = new URL(link.href);
+            url.searchParams.set('pytron_hmr', stamp);
+            link.href = url.toString();
+        });
+        return;
+    }
+    window.location.reload();
+})"#;
+
+/// Watches `root` on a background thread and sends `AssetsChanged` with the
+/// changed paths (relative to `root`) once a burst of edits settles.
+pub fn spawn(root: PathBuf, proxy: EventLoopProxy<UserEvent>) {
+    std::thread::spawn(move || {
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = match notify::recommended_watcher(tx) {
+            Ok(w) => w,
+            Err(e) => {
+                log::warn!(target: "native", "Asset watcher unavailable: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = watcher.watch(&root, RecursiveMode::Recursive) {
+            log::warn!(target: "native", "Could not watch {}: {}", root.display(), e);
+            return;
+        }
+        log::info!(target: "native", "Watching {} for changes", root.display());
+
+        let mut pending: Vec<String> = Vec::new();
+        loop {
+            // Block for the first change, then keep draining until things go quiet
+            let next = if pending.is_empty() { rx.recv().map_err(|_| ()) } else { rx.recv_timeout(DEBOUNCE).map_err(|_| ()) };
+            match next {
+                Ok(Ok(event)) => {
+                    if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+                        for path in event.paths {
+                            let rel = relative(&root, &path);
+                            if !pending.contains(&rel) {
+                                pending.push(rel);
+                            }
+                        }
+                    }
+                }
+                Ok(Err(e)) => log::debug!(target: "native", "Watcher error: {}", e),
+                Err(()) if pending.is_empty() => return, // Watcher dropped
+                Err(()) => {
+                    log::debug!(target: "native", "Assets changed: {:?}", pending);
+                    if proxy.send_event(UserEvent::AssetsChanged(std::mem::take(&mut pending))).is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    });
+}
+
+fn relative(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/")
+}
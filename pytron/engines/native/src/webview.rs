@@ -1,6 +1,7 @@
 use pyo3::prelude::*;
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Condvar, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::path::PathBuf;
 
 use tao::{
@@ -8,16 +9,22 @@ use tao::{
     event_loop::{ControlFlow, EventLoopBuilder, EventLoopProxy, EventLoop},
     window::WindowBuilder,
 };
-use tray_icon::{TrayIconBuilder, menu::{Menu, MenuItemBuilder, PredefinedMenuItem}};
-use wry::WebViewBuilder;
+use tray_icon::{TrayIconBuilder, menu::{ContextMenu, accelerator::{Code, Modifiers}}};
+use wry::{WebContext, WebViewBuilder};
 
 #[cfg(target_os = "windows")]
 use wry::WebViewBuilderExtWindows; 
 
+use crate::errors::{PytronProtocolError, PytronWindowError};
 use crate::events::UserEvent;
-use crate::state::RuntimeState;
-use crate::utils::{setup_panic_hook, SendWrapper, load_icon};
-use crate::protocol::handle_pytron_protocol;
+use crate::state::{HangWatchdog, RuntimeState, TrayAnimation};
+use crate::utils::{setup_panic_hook, SendWrapper, load_icon, block_on, raw_window_handle, parse_hex_color};
+#[cfg(target_os = "windows")]
+use crate::utils::render_badge_rgba;
+use crate::menu::{build_context_menu, build_tray_menu, edit_command, parse_accelerator, parse_menu, CONTEXT_MENU_PREFIX, EDIT_ROLE_PREFIX, TRAY_SHOW_ID};
+use crate::protocol::{ALERT_OVERRIDE, AssetArchive, app_info_script, asset_resolver_script, initial_state_script, generate_token, handle_custom_protocol, handle_pytron_protocol, token_script, ProtocolContext, ProtocolStats, RESERVED_SCHEMES};
+use crate::storage::{clear_browsing_data, run_cookie_command, BrowsingData, CookieCommand, CookieData};
+use crate::dialogs::{parse_buttons, parse_level, result_label, show_file_dialog, file_dialog_json, FileDialogKind, FileDialogOptions};
 
 #[pyclass]
 pub struct NativeWebview {
@@ -25,40 +32,140 @@ pub struct NativeWebview {
     runner: Mutex<Option<EventLoop<UserEvent>>>,
     state_ptr: Mutex<Option<usize>>, 
     hwnd: usize,
+    window_handle: (String, usize),
     callbacks: Arc<Mutex<HashMap<String, PyObject>>>,
+    protocol_stats: Option<Arc<ProtocolStats>>,
+    protocol_token: Option<String>,
+    ready: Arc<(Mutex<bool>, Condvar)>, // Set when the current page's bridge posts pytron_ready
 }
 
 unsafe impl Send for NativeWebview {}
 unsafe impl Sync for NativeWebview {}
 
+impl NativeWebview {
+    fn clear_data(&self, py: Python<'_>, kind: BrowsingData) -> bool {
+        let (tx, rx) = std::sync::mpsc::channel();
+        if self.proxy.send_event(UserEvent::ClearBrowsingData(kind, tx)).is_err() {
+            return false;
+        }
+        py.allow_threads(move || rx.recv_timeout(std::time::Duration::from_secs(10)).unwrap_or(false))
+    }
+
+    // Nothing answers events until run() hands the loop over
+    fn loop_started(&self) -> bool {
+        self.runner.lock().unwrap().is_none()
+    }
+
+    // Cookie stores answer asynchronously on the UI thread; wait for the reply off the GIL
+    fn cookie_request<T: Send>(&self, py: Python<'_>, cmd: CookieCommand, rx: std::sync::mpsc::Receiver<T>) -> Option<T> {
+        self.proxy.send_event(UserEvent::Cookies(cmd)).ok()?;
+        py.allow_threads(move || rx.recv_timeout(std::time::Duration::from_secs(10)).ok())
+    }
+
+    // Set/delete before run(): the proxy buffers the event and the loop applies it on its
+    // first iteration, so there is no reply to wait for
+    fn cookie_change(&self, py: Python<'_>, cmd: CookieCommand, rx: std::sync::mpsc::Receiver<bool>) -> bool {
+        if self.loop_started() {
+            return self.cookie_request(py, cmd, rx).unwrap_or(false);
+        }
+        self.proxy.send_event(UserEvent::Cookies(cmd)).is_ok()
+    }
+}
+
 #[pymethods]
 impl NativeWebview {
     #[new]
-    pub fn new(debug: bool, url_str: String, root_path: String, resizable: bool, frameless: bool) -> PyResult<Self> {
+    #[pyo3(signature = (debug, url_str, root_path, resizable, frameless, scheme=String::from("pytron"), cors_origins=None, native_alert=true, spa_fallback=false, error_pages=None, protocol_stats=false, asset_archive=None, headers=None, service_worker=None, start_maximized=false, start_fullscreen=false, auto_show_on_ready=false, reload_on_crash=false, watch=false, shadow=true, corners=None, background_color=None, additional_browser_args=None, navigation_allowlist=None, title=None, protocol_token=false, initial_css=None, app_id=None, data_directory=None, profile=None, ipc_batching=false, initial_state=None, title_bar_style=None, traffic_light_position=None, custom_protocols=None))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(debug: bool, url_str: String, root_path: String, resizable: bool, frameless: bool, scheme: String, cors_origins: Option<Vec<String>>, native_alert: bool, spa_fallback: bool, error_pages: Option<HashMap<u16, String>>, protocol_stats: bool, asset_archive: Option<String>, headers: Option<HashMap<String, HashMap<String, String>>>, service_worker: Option<String>, start_maximized: bool, start_fullscreen: bool, auto_show_on_ready: bool, reload_on_crash: bool, watch: bool, shadow: bool, corners: Option<String>, background_color: Option<String>, additional_browser_args: Option<String>, navigation_allowlist: Option<Vec<String>>, title: Option<String>, protocol_token: bool, initial_css: Option<String>, app_id: Option<String>, data_directory: Option<String>, profile: Option<String>, ipc_batching: bool, initial_state: Option<HashMap<String, String>>, title_bar_style: Option<String>, traffic_light_position: Option<(f64, f64)>, custom_protocols: Option<HashMap<String, PyObject>>) -> PyResult<Self> {
         setup_panic_hook();
 
+        let scheme_prefix = format!("{}://", scheme);
         let safe_url = if url_str == "about:blank" {
              url_str
-        } else if url_str.starts_with("pytron://") {
+        } else if url_str.starts_with(&scheme_prefix) {
              url_str
         } else if url_str.starts_with("http") {
              url_str
         } else {
-             format!("pytron://app/{}", url_str.trim_start_matches('/'))
+             format!("{}app/{}", scheme_prefix, url_str.trim_start_matches('/'))
         };
 
-        println!("[PYTRON NATIVE] Init. Target: {} | Root: {}", safe_url, root_path);
+        log::info!(target: "native", "Init. Target: {} | Root: {}", safe_url, root_path);
+
+        let background = background_color
+            .map(|c| parse_hex_color(&c).ok_or_else(|| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid background_color '{}', expected #RRGGBB or #RRGGBBAA", c))))
+            .transpose()?;
+
+        // {name, version, author} from the loader's Settings (or settings.json in dev runs)
+        let app_info = Python::with_gil(read_app_info);
+        // Titled up front so the taskbar never shows a placeholder before Python calls set_title
+        let title = title
+            .or_else(|| app_info.get("name").cloned().flatten())
+            .unwrap_or_else(|| "Pytron App".to_string());
+
+        // Before any window exists, so the taskbar groups it under the app rather than python.exe
+        if let Some(id) = &app_id {
+            set_process_app_id(id)?;
+        }
 
         let event_loop = EventLoopBuilder::<UserEvent>::with_user_event().build();
         let proxy = event_loop.create_proxy();
+        #[cfg(target_os = "macos")]
+        let app_menu = Some(crate::menu::install_app_menu(&title));
+        #[cfg(not(target_os = "macos"))]
+        let app_menu = None;
         
-        let window = WindowBuilder::new()
-            .with_title("Pytron App")
+        // "hidden" keeps the macOS traffic lights over a full-height page instead of dropping the frame
+        let hidden_titlebar = match title_bar_style.as_deref() {
+            None | Some("default") => false,
+            Some("hidden") => cfg!(target_os = "macos"),
+            Some(other) => return Err(pyo3::exceptions::PyValueError::new_err(format!("Unknown title_bar_style '{}' (expected 'default' or 'hidden')", other))),
+        };
+        let window_builder = WindowBuilder::new()
+            .with_title(&title)
             .with_visible(false)
             .with_resizable(resizable)
-            .with_decorations(!frameless)
+            .with_decorations(!frameless || hidden_titlebar)
+            // Applied before the first show so there's no windowed-size flash
+            .with_maximized(start_maximized)
+            .with_fullscreen(start_fullscreen.then_some(tao::window::Fullscreen::Borderless(None)));
+        // Paint the theme color before the first page renders, instead of white
+        let window_builder = match background {
+            Some(color) => window_builder.with_background_color(color),
+            None => window_builder,
+        };
+        // Frameless windows keep the native drop shadow unless disabled
+        #[cfg(target_os = "windows")]
+        let window_builder = {
+            use tao::platform::windows::WindowBuilderExtWindows;
+            window_builder.with_undecorated_shadow(shadow)
+        };
+        #[cfg(target_os = "macos")]
+        let window_builder = {
+            use tao::platform::macos::WindowBuilderExtMacOS;
+            let window_builder = window_builder.with_has_shadow(shadow);
+            let window_builder = if hidden_titlebar {
+                window_builder
+                    .with_titlebar_transparent(true)
+                    .with_title_hidden(true)
+                    .with_fullsize_content_view(true)
+            } else {
+                window_builder
+            };
+            match traffic_light_position {
+                Some((x, y)) => window_builder.with_traffic_light_inset(tao::dpi::LogicalPosition::new(x, y)),
+                None => window_builder,
+            }
+        };
+        #[cfg(not(target_os = "macos"))]
+        let _ = traffic_light_position;
+        #[cfg(target_os = "linux")]
+        let _ = shadow; // Up to the compositor
+        let window = window_builder
             .build(&event_loop)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to create window: {}", e)))?;
+            .map_err(|e| PytronWindowError::new_err(format!("Failed to create window: {}", e)))?;
+        crate::timings::record("window_created");
         
         #[cfg(target_os = "windows")]
         let hwnd = {
@@ -67,191 +174,620 @@ impl NativeWebview {
         };
         #[cfg(not(target_os = "windows"))]
         let hwnd = 0;
+        // Windows 11 only rounds decorated windows by default; match it for frameless ones
+        let corners = match corners.or_else(|| frameless.then(|| "round".to_string())) {
+            Some(name) => Some(corner_preference(&name)?),
+            None => None,
+        };
+        if let Some(pref) = corners {
+            apply_corner_preference(&window, pref);
+        }
+        let window_handle = raw_window_handle(&window);
 
         let root = PathBuf::from(&root_path);
         let callbacks = Arc::new(Mutex::new(HashMap::<String, PyObject>::new()));
-        let cbs_for_ipc = callbacks.clone();
-        let proxy_for_ipc = proxy.clone();
-
-        let mut builder = WebViewBuilder::new(&window)
-            .with_devtools(debug)
-            .with_url(&safe_url);
 
         // --- Custom Protocol Handler ---
-        let protocol_root = root.clone();
-        let cbs_for_protocol = callbacks.clone();
-        
-        builder = builder.with_custom_protocol("pytron".into(), move |request| {
-            handle_pytron_protocol(request, protocol_root.clone(), cbs_for_protocol.clone())
+        let stats = protocol_stats.then(|| Arc::new(ProtocolStats::default()));
+        let archive = asset_archive
+            .map(|p| AssetArchive::open(&root.join(p)))
+            .transpose()
+            .map_err(PytronProtocolError::new_err)?;
+        let protocol_ctx = Arc::new(ProtocolContext {
+            root: root.clone(),
+            callbacks: callbacks.clone(),
+            allowed_origins: cors_origins.unwrap_or_else(|| ProtocolContext::default_origins(&scheme)),
+            native_alert,
+            spa_fallback,
+            error_pages: error_pages.unwrap_or_default(),
+            header_rules: headers.unwrap_or_default(),
+            service_worker,
+            archive,
+            stats: stats.clone(),
+            token: protocol_token.then(generate_token),
         });
         
-        #[cfg(target_os = "windows")]
-        {
-             builder = builder.with_https_scheme(true);
+
+        // Frozen builds point sys._MEIPASS at the bundle; the page gets it alongside the asset root
+        let meipass = Python::with_gil(|py| {
+            py.import("sys").and_then(|sys| sys.getattr("_MEIPASS")).and_then(|p| p.extract::<String>()).ok()
+        });
+        let resolver_script = asset_resolver_script(&scheme, &root, meipass.as_deref());
+        let info_script = app_info_script(&app_info);
+        let state_script = initial_state
+            .map(|state| initial_state_script(&state))
+            .transpose()
+            .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+        let token_js = protocol_ctx.token.as_deref().map(|t| token_script(&scheme, t));
+
+        // Extra schemes served by Python, e.g. media:// next to the app's own scheme
+        let custom_protocols: Vec<(String, Arc<PyObject>)> = custom_protocols.unwrap_or_default().into_iter().map(|(name, handler)| (name, Arc::new(handler))).collect();
+        for (name, _) in &custom_protocols {
+            let valid = name.starts_with(|c: char| c.is_ascii_lowercase())
+                && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '+' | '-' | '.'));
+            if !valid || *name == scheme || RESERVED_SCHEMES.contains(&name.as_str()) {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Cannot register custom protocol '{}': use a lowercase scheme other than '{}' and the built-in web schemes",
+                    name, scheme
+                )));
+            }
         }
 
-        let proxy_for_nav = proxy.clone();
-        builder = builder.with_navigation_handler(move |url: String| {
-            // Check if it's an internal application link or an external one
-            if !url.starts_with("pytron://") && !url.starts_with("https://pytron.") && url != "about:blank" {
-                // External! Send to system browser
-                let _ = proxy_for_nav.send_event(UserEvent::OpenExternal(url.clone()));
-                return false; // Prevent internal navigation
+        let ready = Arc::new((Mutex::new(false), Condvar::new()));
+
+        // Cookies, localStorage and caches live here; the profile is fixed for the webview's lifetime
+        let mut web_context = profile_data_dir(data_directory, profile)?.map(|dir| WebContext::new(Some(dir)));
+
+        // Everything the builder needs is re-created per attempt, so a failed build can be retried
+        let mut build_webview = |software_rendering: bool| -> wry::Result<wry::WebView> {
+            let mut builder = WebViewBuilder::new(&window)
+                .with_devtools(debug)
+                .with_url(&safe_url);
+            // Edit-role menu items run execCommand on Linux, which WebKit only allows with this
+            #[cfg(target_os = "linux")]
+            {
+                builder = builder.with_clipboard(true);
+            }
+            if let Some(context) = web_context.as_mut() {
+                builder = builder.with_web_context(context);
+            }
+            if let Some(color) = background {
+                builder = builder.with_background_color(color);
             }
-            true // Allow internal navigation
-        });
 
-        let proxy_for_new_window = proxy.clone();
-        builder = builder.with_new_window_req_handler(move |url: String| {
-            // For new windows (target="_blank"), always prefer external browser
-            let _ = proxy_for_new_window.send_event(UserEvent::OpenExternal(url.clone()));
-            false // Prevent internal window creation
-        });
+            let protocol_ctx = protocol_ctx.clone();
+            builder = builder.with_custom_protocol(scheme.clone(), move |request| {
+                handle_pytron_protocol(request, &protocol_ctx)
+            });
+            for (name, handler) in &custom_protocols {
+                let (name, handler) = (name.clone(), handler.clone());
+                builder = builder.with_asynchronous_custom_protocol(name.clone(), move |request, responder| {
+                    // Python may be slow (or streaming a large file); keep the UI thread free
+                    let (name, handler) = (name.clone(), handler.clone());
+                    std::thread::spawn(move || responder.respond(handle_custom_protocol(&handler, &name, request)));
+                });
+            }
+        
+            #[cfg(target_os = "windows")]
+            {
+                 builder = builder.with_https_scheme(true);
+            }
 
-        builder = builder.with_initialization_script(r#"
-            window.pytron_is_native = true;
-            
-            // --- DE-BROWSERIFY CORE ---
-            (function() {
-                const isDebug = window.location.search.includes('debug=true') || window.__PYTRON_DEBUG__;
-                
-                // 1. Kill Context Menu (Unless debugging)
-                if (!isDebug) {
-                    document.addEventListener('contextmenu', e => e.preventDefault());
+            // WebView2 serves custom schemes as https://<scheme>.<host>
+            let https_prefix = format!("https://{}.", scheme);
+            let scheme_prefix = scheme_prefix.clone();
+            let allowlist = navigation_allowlist.clone().unwrap_or_default();
+            let is_internal = move |url: &str| {
+                url.starts_with(&scheme_prefix) || url.starts_with(&https_prefix) || is_allowlisted(url, &allowlist)
+            };
+            let is_internal_nav = is_internal.clone();
+            let proxy_for_nav = proxy.clone();
+            // Document the page-load handler last saw, so anchor jumps within it are never sent elsewhere
+            let current_doc = Arc::new(Mutex::new(String::new()));
+            let current_doc_nav = current_doc.clone();
+            builder = builder.with_navigation_handler(move |url: String| {
+                if current_doc_nav.lock().map(|doc| is_same_document(&url, &doc)).unwrap_or(false) {
+                    return true;
                 }
+                // Check if it's an internal application link or an external one
+                if !is_internal_nav(&url) && url != "about:blank" {
+                    // External! Send to system browser
+                    let _ = proxy_for_nav.send_event(UserEvent::OpenExternal(url.clone()));
+                    return false; // Prevent internal navigation
+                }
+                true // Allow internal navigation
+            });
 
-                // 2. Kill "Ghost" Drags (images/links flying around)
-                document.addEventListener('dragstart', e => {
-                    if (e.target.tagName === 'IMG' || e.target.tagName === 'A') e.preventDefault();
-                });
+            let proxy_for_new_window = proxy.clone();
+            builder = builder.with_new_window_req_handler(move |url: String| {
+                // The system browser can't resolve our scheme, so keep internal links in-app
+                if is_internal(&url) {
+                    let _ = proxy_for_new_window.send_event(UserEvent::Navigate(url));
+                    return false;
+                }
+                // For new windows (target="_blank"), always prefer external browser
+                let _ = proxy_for_new_window.send_event(UserEvent::OpenExternal(url.clone()));
+                false // Prevent internal window creation
+            });
 
-                // 3. Kill Browser Shortcuts
-                window.addEventListener('keydown', e => {
-                    const forbidden = ['r', 'p', 's', 'j', 'u', 'f'];
-                    if (e.ctrlKey && forbidden.includes(e.key.toLowerCase())) e.preventDefault();
-                    if (e.key === 'F5' || e.key === 'F3' || (e.ctrlKey && e.key === 'f')) e.preventDefault();
-                    // Block Zoom
-                    if (e.ctrlKey && (e.key === '=' || e.key === '-' || e.key === '0')) e.preventDefault();
-                }, true);
-
-                // 4. Kill System UI Styles (Selection, Outlines, Rubber-banding)
-                const style = document.createElement('style');
-                style.textContent = `
-                    * { 
-                        -webkit-user-select: none; 
-                        user-select: none;
-                        -webkit-user-drag: none; 
-                        -webkit-tap-highlight-color: transparent;
-                        outline: none !important;
-                    }
-                    input, textarea, [contenteditable], [contenteditable] * { 
-                        -webkit-user-select: text !important; 
-                        user-select: text !important;
+            // Downloads: Python may return a path (save there), False (cancel) or anything else (default location)
+            let cbs_for_download = callbacks.clone();
+            builder = builder.with_download_started_handler(move |url: String, path: &mut PathBuf| {
+                let mut found: Option<PyObject> = None;
+                if let Ok(cbs) = cbs_for_download.lock() {
+                    if let Some(f) = cbs.get("pytron_on_download") {
+                        Python::with_gil(|py| { found = Some(f.clone_ref(py)); });
                     }
-                    html, body {
-                        overscroll-behavior: none !important;
-                        cursor: default;
+                }
+                let Some(f) = found else { return true };
+                Python::with_gil(|py| {
+                    match f.call1(py, (url, path.to_string_lossy().to_string())) {
+                        Ok(res) => {
+                            if let Ok(p) = res.extract::<String>(py) {
+                                *path = PathBuf::from(p);
+                                true
+                            } else {
+                                res.extract::<bool>(py).unwrap_or(true)
+                            }
+                        }
+                        Err(e) => {
+                            log::warn!(target: "native", "pytron_on_download failed: {}", e);
+                            false
+                        }
                     }
-                    a, button, input[type="button"], input[type="submit"] {
-                        cursor: pointer;
+                })
+            });
+            let cbs_for_download_done = callbacks.clone();
+            builder = builder.with_download_completed_handler(move |_url: String, path: Option<PathBuf>, success: bool| {
+                let mut found: Option<PyObject> = None;
+                if let Ok(cbs) = cbs_for_download_done.lock() {
+                    if let Some(f) = cbs.get("pytron_on_download_complete") {
+                        Python::with_gil(|py| { found = Some(f.clone_ref(py)); });
                     }
-                `;
-                document.head ? document.head.appendChild(style) : document.addEventListener('DOMContentLoaded', () => document.head.appendChild(style));
-            })();
-
-            window.pytron = window.pytron || {};
-            window.pytron.is_ready = true;
-            window.__pytron_native_bridge = (method, args) => {
-                const seq = Math.random().toString(36).substring(2, 10);
-                window.ipc.postMessage(JSON.stringify({id: seq, method: method, params: args}));
-                return new Promise((resolve, reject) => {
-                    window._rpc = window._rpc || {};
-                    window._rpc[seq] = {resolve, reject};
-                });
-            };
-            window.pytron_close = () => window.__pytron_native_bridge('pytron_close', []);
-            window.pytron_drag = () => window.__pytron_native_bridge('pytron_drag', []);
-            window.pytron_log = (msg) => window.__pytron_native_bridge('pytron_log', [msg]);
+                }
+                if let Some(f) = found {
+                    let path = path.map(|p| p.to_string_lossy().to_string());
+                    Python::with_gil(|py| { let _ = f.call1(py, (path, success)); });
+                }
+            });
 
-            // Override alert to use native message box
-            window.alert = (msg) => {
-                window.__pytron_native_bridge('pytron_message_box', ["Alert", String(msg), "info"]);
-            };
-        "#);
-
-        builder = builder.with_ipc_handler(move |request| {
-            let msg = request.body().clone();
-            if let Ok(val) = serde_json::from_str::<serde_json::Value>(&msg) {
-                let seq = val["id"].as_str().unwrap_or("").to_string();
-                let method = val["method"].as_str().unwrap_or("").to_string();
-                let params = val["params"].to_string(); 
-                
-                // 1. Check Special Native Methods (Zero Overhead / Native Speed)
-                if method == "pytron_drag" || method == "drag" {
-                    let _ = proxy_for_ipc.send_event(UserEvent::DragWindow);
-                    return;
+            let proxy_for_load = proxy.clone();
+            let ready_for_load = ready.clone();
+            let shown = std::sync::atomic::AtomicBool::new(!auto_show_on_ready);
+            let painted = std::sync::atomic::AtomicBool::new(false);
+            builder = builder.with_on_page_load_handler(move |event, url| {
+                if let Ok(mut doc) = current_doc.lock() {
+                    doc.clone_from(&url);
+                }
+                // A new document needs its own bridge before it counts as ready
+                if matches!(event, wry::PageLoadEvent::Started) {
+                    if let Ok(mut r) = ready_for_load.0.lock() {
+                        *r = false;
+                    }
                 }
-                if method == "pytron_close" || method == "close" || method == "app_quit" {
-                    let _ = proxy_for_ipc.send_event(UserEvent::Quit);
+                if !matches!(event, wry::PageLoadEvent::Finished) || url == "about:blank" {
                     return;
                 }
+                if !painted.swap(true, std::sync::atomic::Ordering::SeqCst) {
+                    crate::timings::record("first_paint");
+                }
+                // Reveal the window once the first real page has rendered, avoiding the white flash
+                if !shown.swap(true, std::sync::atomic::Ordering::SeqCst) {
+                    let _ = proxy_for_load.send_event(UserEvent::SetVisible(true));
+                }
+                let _ = proxy_for_load.send_event(UserEvent::PageLoaded(url));
+            });
+
+            builder = builder.with_initialization_script(r#"
+                window.pytron_is_native = true;
+            
+                // --- DE-BROWSERIFY CORE ---
+                (function() {
+                    const isDebug = window.location.search.includes('debug=true') || window.__PYTRON_DEBUG__;
+                
+                    // 1. Kill Context Menu (Unless debugging)
+                    if (!isDebug) {
+                        document.addEventListener('contextmenu', e => e.preventDefault());
+                    }
+
+                    // 2. Kill "Ghost" Drags (images/links flying around)
+                    document.addEventListener('dragstart', e => {
+                        if (e.target.tagName === 'IMG' || e.target.tagName === 'A') e.preventDefault();
+                    });
+
+                    // 3. Forward registered shortcuts to native accelerators before anything below can swallow them
+                    window.addEventListener('keydown', e => {
+                        if (e.repeat || !(e.ctrlKey || e.altKey || e.metaKey || /^F\d+$/.test(e.code))) return;
+                        const keys = window.__pytron_accelerators;
+                        const key = `${+e.ctrlKey}${+e.altKey}${+e.shiftKey}${+e.metaKey}:${e.code}`;
+                        if (!keys || !keys.has(key)) return;
+                        window.ipc.postMessage(JSON.stringify({id: '', method: 'pytron_key', params: {
+                            code: e.code, ctrl: e.ctrlKey, alt: e.altKey, shift: e.shiftKey, meta: e.metaKey
+                        }}));
+                    }, true);
+
+                    // 4. Kill Browser Shortcuts
+                    window.addEventListener('keydown', e => {
+                        const forbidden = ['r', 'p', 's', 'j', 'u', 'f'];
+                        if (e.ctrlKey && forbidden.includes(e.key.toLowerCase())) e.preventDefault();
+                        if (e.key === 'F5' || e.key === 'F3' || (e.ctrlKey && e.key === 'f')) e.preventDefault();
+                        // Block Zoom
+                        if (e.ctrlKey && (e.key === '=' || e.key === '-' || e.key === '0')) e.preventDefault();
+                    }, true);
+
+                    // 5. Kill System UI Styles (Selection, Outlines, Rubber-banding)
+                    const style = document.createElement('style');
+                    style.textContent = `
+                        * { 
+                            -webkit-user-select: none; 
+                            user-select: none;
+                            -webkit-user-drag: none; 
+                            -webkit-tap-highlight-color: transparent;
+                            outline: none !important;
+                        }
+                        input, textarea, [contenteditable], [contenteditable] * { 
+                            -webkit-user-select: text !important; 
+                            user-select: text !important;
+                        }
+                        html, body {
+                            overscroll-behavior: none !important;
+                            cursor: default;
+                        }
+                        a, button, input[type="button"], input[type="submit"] {
+                            cursor: pointer;
+                        }
+                    `;
+                    document.head ? document.head.appendChild(style) : document.addEventListener('DOMContentLoaded', () => document.head.appendChild(style));
+                })();
 
-                // Native handling for parameterized system calls
-                if method == "system_notification" || method == "pytron_system_notification" {
-                    if let Ok(args) = serde_json::from_str::<Vec<String>>(&params) {
-                        if args.len() >= 2 {
-                            let _ = proxy_for_ipc.send_event(UserEvent::Notification(args[0].clone(), args[1].clone()));
-                            return;
+                window.pytron = window.pytron || {};
+                window.pytron.is_ready = true;
+                // Opt-in batching: calls made in the same task go out as one message after a microtask
+                let pending = null;
+                const flushCalls = () => {
+                    const calls = pending;
+                    pending = null;
+                    const msg = calls.length === 1 ? calls[0] : {id: '', method: 'pytron_batch', params: calls};
+                    window.ipc.postMessage(JSON.stringify(msg));
+                };
+                window.__pytron_native_bridge = (method, args) => {
+                    const seq = Math.random().toString(36).substring(2, 10);
+                    const call = {id: seq, method: method, params: args};
+                    if (window.__PYTRON_IPC_BATCH__) {
+                        if (!pending) {
+                            pending = [];
+                            queueMicrotask(flushCalls);
                         }
+                        pending.push(call);
+                    } else {
+                        window.ipc.postMessage(JSON.stringify(call));
                     }
-                }
+                    const entry = {chunks: [], controller: null, finish: null};
+                    const promise = new Promise((resolve, reject) => {
+                        entry.resolve = resolve;
+                        entry.reject = reject;
+                        window._rpc = window._rpc || {};
+                        window._rpc[seq] = entry;
+                    });
+                    // Chunks from streaming handlers; buffered until the page starts reading
+                    promise.stream = () => new ReadableStream({
+                        start(controller) {
+                            (entry.chunks || []).forEach(c => controller.enqueue(c));
+                            entry.chunks = null;
+                            entry.finish ? entry.finish(controller) : (entry.controller = controller);
+                        }
+                    });
+                    promise[Symbol.asyncIterator] = () => {
+                        const reader = promise.stream().getReader();
+                        return {next: () => reader.read(), return: async () => { reader.releaseLock(); return {done: true}; }};
+                    };
+                    return promise;
+                };
+                // Results arrive as [[seq, status, value], ...]; status 2 is a stream chunk
+                window.__pytron_settle = (results) => {
+                    for (const [seq, status, value] of results) {
+                        const call = window._rpc && window._rpc[seq];
+                        if (!call) continue;
+                        if (status === 2) {
+                            call.controller ? call.controller.enqueue(value) : call.chunks && call.chunks.push(value);
+                            continue;
+                        }
+                        delete window._rpc[seq];
+                        status === 0 ? call.resolve(value) : call.reject(value);
+                        const finish = status === 0 ? c => c.close() : c => c.error(value);
+                        call.controller ? finish(call.controller) : (call.finish = finish);
+                    }
+                };
+                window.pytron_close = () => window.__pytron_native_bridge('pytron_close', []);
+                window.pytron_drag = () => window.__pytron_native_bridge('pytron_drag', []);
+                window.pytron_log = (msg) => window.__pytron_native_bridge('pytron_log', [msg]);
+                window.pytron_dialog_open_file = (...a) => window.__pytron_native_bridge('pytron_dialog_open_file', a);
+                window.pytron_dialog_open_files = (...a) => window.__pytron_native_bridge('pytron_dialog_open_files', a);
+                window.pytron_dialog_save_file = (...a) => window.__pytron_native_bridge('pytron_dialog_save_file', a);
+                window.pytron_dialog_open_folder = (...a) => window.__pytron_native_bridge('pytron_dialog_open_folder', a);
+
+                // Native text input; resolves to null on cancel
+                window.prompt = (msg, def) => window.__pytron_native_bridge('pytron_prompt', [String(msg ?? ''), def == null ? '' : String(def)]);
+
+                // Releases wait_until_ready() once the real page (not the initial about:blank) has parsed
+                const signalReady = () => {
+                    if (location.href !== 'about:blank') window.ipc.postMessage(JSON.stringify({id: '', method: 'pytron_ready', params: []}));
+                };
+                document.readyState === 'loading' ? document.addEventListener('DOMContentLoaded', signalReady) : signalReady();
+            "#);
+
+            builder = builder.with_initialization_script(&resolver_script);
+            builder = builder.with_initialization_script(&info_script);
+            if let Some(script) = &state_script {
+                builder = builder.with_initialization_script(script);
+            }
+            if let Some(script) = &token_js {
+                builder = builder.with_initialization_script(script);
+            }
+            // Applied before the first paint, so the page never renders unstyled
+            if let Some(css) = &initial_css {
+                builder = builder.with_initialization_script(&css_script(INITIAL_CSS_ID, css));
+            }
+
+            if ipc_batching {
+                builder = builder.with_initialization_script("window.__PYTRON_IPC_BATCH__ = true;");
+            }
 
-                if method == "set_taskbar_progress" || method == "pytron_set_taskbar_progress" {
-                    if let Ok(args) = serde_json::from_str::<Vec<i32>>(&params) {
-                         if args.len() >= 3 {
-                             let _ = proxy_for_ipc.send_event(UserEvent::TaskbarProgress(args[0], args[1], args[2]));
-                             return;
-                         }
+            // The webview covers the whole GTK window and swallows the pointer events tao
+            // would use for undecorated resizing, so the page reports edge drags instead
+            #[cfg(target_os = "linux")]
+            if frameless && resizable {
+                builder = builder.with_initialization_script(FRAMELESS_RESIZE_SCRIPT);
+            }
+
+            // The native alert resolves asynchronously, so code relying on a blocking
+            // alert() can opt out and keep the webview's own dialog.
+            if native_alert {
+                builder = builder.with_initialization_script(ALERT_OVERRIDE);
+            }
+
+            let cbs_for_ipc = callbacks.clone();
+            let proxy_for_ipc = proxy.clone();
+            let ready_for_ipc = ready.clone();
+            builder = builder.with_ipc_handler(move |request| {
+                let msg = request.body().clone();
+                let origin = request.uri().to_string();
+                let Ok(val) = serde_json::from_str::<serde_json::Value>(&msg) else { return };
+                // A batch carries several calls in one message; each is handled as if posted alone
+                let calls = match val["method"].as_str() {
+                    Some("pytron_batch") => val["params"].as_array().cloned().unwrap_or_default(),
+                    _ => vec![val],
+                };
+                for val in calls {
+                    let seq = val["id"].as_str().unwrap_or("").to_string();
+                    let method = val["method"].as_str().unwrap_or("").to_string();
+                    let params = val["params"].to_string(); 
+                
+                    // 1. Check Special Native Methods (Zero Overhead / Native Speed)
+                    if method == "pytron_drag" || method == "drag" {
+                        let _ = proxy_for_ipc.send_event(UserEvent::DragWindow);
+                        continue;
+                    }
+                    if method == "pytron_resize" {
+                        if let Some(direction) = val["params"][0].as_str().and_then(resize_direction) {
+                            let _ = proxy_for_ipc.send_event(UserEvent::DragResize(direction));
+                        }
+                        continue;
+                    }
+                    if method == "pytron_close" || method == "close" || method == "app_quit" {
+                        let _ = proxy_for_ipc.send_event(UserEvent::RequestQuit);
+                        continue;
                     }
-                }
 
-                // Native Handling for message boxes (blocking is fine as it runs on native thread, but we use a specialized event for it)
-                if method == "pytron_message_box" || method == "message_box" {
-                    if let Ok(args) = serde_json::from_str::<Vec<String>>(&params) {
-                        if args.len() >= 3 {
-                             let _ = proxy_for_ipc.send_event(UserEvent::MessageBox(args[0].clone(), args[1].clone(), args[2].clone(), seq));
-                             return;
+                    // Native handling for parameterized system calls
+                    if method == "system_notification" || method == "pytron_system_notification" {
+                        if let Ok(args) = serde_json::from_str::<Vec<String>>(&params) {
+                            if args.len() >= 2 {
+                                let _ = proxy_for_ipc.send_event(UserEvent::Notification(args[0].clone(), args[1].clone()));
+                                continue;
+                            }
+                        }
+                    }
+
+                    if method == "set_taskbar_progress" || method == "pytron_set_taskbar_progress" {
+                        if let Ok(args) = serde_json::from_str::<Vec<i32>>(&params) {
+                             if args.len() >= 3 {
+                                 let _ = proxy_for_ipc.send_event(UserEvent::TaskbarProgress(args[0], args[1], args[2]));
+                                 continue;
+                             }
                         }
                     }
-                }
 
-                // 2. Search for bound Python Functions
-                let mut found_func: Option<PyObject> = None;
-                if let Ok(cbs) = cbs_for_ipc.lock() {
-                    if let Some(f) = cbs.get(&method) {
-                        Python::with_gil(|py| { found_func = Some(f.clone_ref(py)); });
+                    if method == "pytron_print_done" {
+                        let _ = proxy_for_ipc.send_event(UserEvent::PrintDone);
+                        continue;
+                    }
+
+                    if method == "pytron_pong" {
+                        let _ = proxy_for_ipc.send_event(UserEvent::HangPong);
+                        continue;
+                    }
+
+                    if method == "pytron_ready" {
+                        let (lock, cvar) = &*ready_for_ipc;
+                        if let Ok(mut r) = lock.lock() {
+                            *r = true;
+                            cvar.notify_all();
+                        }
+                        continue;
+                    }
+
+                    if method == "pytron_key" {
+                        let p = &val["params"];
+                        if let Ok(code) = p["code"].as_str().unwrap_or("").parse::<Code>() {
+                            let mut mods = Modifiers::empty();
+                            mods.set(Modifiers::CONTROL, p["ctrl"].as_bool().unwrap_or(false));
+                            mods.set(Modifiers::ALT, p["alt"].as_bool().unwrap_or(false));
+                            mods.set(Modifiers::SHIFT, p["shift"].as_bool().unwrap_or(false));
+                            mods.set(Modifiers::SUPER, p["meta"].as_bool().unwrap_or(false));
+                            let _ = proxy_for_ipc.send_event(UserEvent::KeyDown(mods, code));
+                        }
+                        continue;
+                    }
+
+                    if method == "pytron_log" {
+                        let msg = match val["params"].get(0) {
+                            Some(serde_json::Value::String(m)) => m.clone(),
+                            Some(other) => other.to_string(),
+                            None => String::new(),
+                        };
+                        let _ = proxy_for_ipc.send_event(UserEvent::Log(msg));
+                        let _ = proxy_for_ipc.send_event(UserEvent::Return(seq, 0, "null".to_string()));
+                        continue;
+                    }
+
+                    if method == "pytron_show_context_menu" {
+                        // [menu (array or JSON string), x, y]
+                        if let Some(items) = val["params"].get(0) {
+                            let menu_json = items.as_str().map(|m| m.to_string()).unwrap_or_else(|| items.to_string());
+                            let x = val["params"][1].as_f64().unwrap_or(0.0) as i32;
+                            let y = val["params"][2].as_f64().unwrap_or(0.0) as i32;
+                            let _ = proxy_for_ipc.send_event(UserEvent::ShowContextMenu(menu_json, x, y));
+                            let _ = proxy_for_ipc.send_event(UserEvent::Return(seq, 0, "null".to_string()));
+                            continue;
+                        }
+                    }
+
+                    if method == "pytron_prompt" {
+                        if let Ok(args) = serde_json::from_str::<Vec<String>>(&params) {
+                            let label = args.first().cloned().unwrap_or_default();
+                            let default = args.get(1).cloned().unwrap_or_default();
+                            let proxy = proxy_for_ipc.clone();
+                            // The input box blocks, keep it off the event loop
+                            std::thread::spawn(move || {
+                                let res = crate::dialogs::prompt("Input", &label, &default);
+                                let json = serde_json::to_string(&res).unwrap_or_else(|_| "null".to_string());
+                                let _ = proxy.send_event(UserEvent::Return(seq, 0, json));
+                            });
+                            continue;
+                        }
                     }
-                }
 
-                if let Some(func) = found_func {
-                    let _ = proxy_for_ipc.send_event(UserEvent::CallPython(func, seq, params, method));
-                } else {
-                    // Method not found - return error to JS
-                    let error_msg = format!("\"Method '{}' not found.\"", method);
-                    let _ = proxy_for_ipc.send_event(UserEvent::Return(seq, 1, error_msg));
+                    let dialog_kind = match method.as_str() {
+                        "pytron_dialog_open_file" => Some(FileDialogKind::OpenFile),
+                        "pytron_dialog_open_files" => Some(FileDialogKind::OpenFiles),
+                        "pytron_dialog_save_file" => Some(FileDialogKind::SaveFile),
+                        "pytron_dialog_open_folder" => Some(FileDialogKind::OpenFolder),
+                        _ => None,
+                    };
+                    if let Some(kind) = dialog_kind {
+                        // [title, dir, filters] or [title, dir, name, filters] for save
+                        let args = serde_json::from_str::<Vec<Option<String>>>(&params).unwrap_or_default();
+                        let arg = |i: usize| args.get(i).cloned().flatten();
+                        let opts = match kind {
+                            FileDialogKind::SaveFile => FileDialogOptions { title: arg(0).unwrap_or_default(), dir: arg(1), name: arg(2), filters: arg(3) },
+                            _ => FileDialogOptions { title: arg(0).unwrap_or_default(), dir: arg(1), name: None, filters: arg(2) },
+                        };
+                        let _ = proxy_for_ipc.send_event(UserEvent::FileDialog(kind, opts, seq));
+                        continue;
+                    }
+
+                    // Native Handling for message boxes (blocking is fine as it runs on native thread, but we use a specialized event for it)
+                    if method == "pytron_message_box" || method == "message_box" {
+                        if let Ok(args) = serde_json::from_str::<Vec<String>>(&params) {
+                            if args.len() >= 3 {
+                                 let _ = proxy_for_ipc.send_event(UserEvent::MessageBox(args[0].clone(), args[1].clone(), args[2].clone(), seq, args.get(3).cloned()));
+                                 continue;
+                            }
+                        }
+                    }
+
+                    // 2. Search for bound Python Functions
+                    let mut found_func: Option<PyObject> = None;
+                    if let Ok(cbs) = cbs_for_ipc.lock() {
+                        if let Some(f) = cbs.get(&method) {
+                            Python::with_gil(|py| { found_func = Some(f.clone_ref(py)); });
+                        }
+                    }
+
+                    if let Some(func) = found_func {
+                        let _ = proxy_for_ipc.send_event(UserEvent::CallPython(func, seq, params, method, origin.clone()));
+                    } else {
+                        // Method not found - return error to JS
+                        let error_msg = format!("\"Method '{}' not found.\"", method);
+                        let _ = proxy_for_ipc.send_event(UserEvent::Return(seq, 1, error_msg));
+                    }
+                }
+            });
+            // Setting any args replaces wry's defaults, so those are always passed along
+            #[cfg(target_os = "windows")]
+            if software_rendering || additional_browser_args.is_some() {
+                let mut args = String::from(WEBVIEW2_DEFAULT_ARGS);
+                if software_rendering {
+                    args.push(' ');
+                    args.push_str(SOFTWARE_RENDERING_ARGS);
                 }
+                if let Some(extra) = &additional_browser_args {
+                    args.push(' ');
+                    args.push_str(extra);
+                }
+                builder = builder.with_additional_browser_args(args);
             }
-        });
+            #[cfg(not(target_os = "windows"))]
+            let _ = software_rendering;
+            // WebKit has no command line; map the flags that have an equivalent setting
+            if additional_browser_args.as_deref().is_some_and(|a| a.contains("--autoplay-policy=no-user-gesture-required")) {
+                builder = builder.with_autoplay(true);
+            }
+            builder.build()
+        };
+        let webview = build_webview(false)
+            .or_else(|e| {
+                if !is_backend_init_error(&e) {
+                    return Err(e);
+                }
+                // Broken GPU drivers can fail WebView2 before it ever renders
+                log::warn!(target: "native", "WebView init failed ({}), retrying with software rendering", e);
+                // A prewarmed runtime was started with the old arguments; let it go
+                crate::prewarm::mark_webview_created();
+                build_webview(true)
+            })
+            .map_err(|e| {
+                alert_webview_failure(&e);
+                PytronWindowError::new_err(format!("Failed to build WebView: {}", e))
+            })?;
+        crate::timings::record("webview_created");
+        crate::prewarm::mark_webview_created();
+        crate::permissions::install(&webview, callbacks.clone());
+        install_crash_handler(&webview, proxy.clone());
+        // Dev-only: never watch the filesystem in production builds
+        if watch && debug {
+            crate::watcher::spawn(root.clone(), proxy.clone());
+        }
 
-        let webview = builder.build()
-             .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to build WebView: {}", e)))?;
+        crate::state::register_webview(proxy.clone());
 
         let state = Box::into_raw(Box::new(RuntimeState { 
             webview, 
             window, 
             callbacks: callbacks.clone(), 
+            context_callbacks: std::collections::HashSet::new(),
             tray: None, 
-            prevent_close: false 
+            tray_icon_path: None,
+            tray_animation: None,
+            context_menu: None,
+            prevent_close: false,
+            minimize_to_tray: false,
+            close_to_tray: false,
+            suspended: false,
+            auto_suspend: None,
+            visibility_epoch: 0,
+            current_url: String::new(),
+            reload_on_crash,
+            accelerators: Vec::new(),
+            quitting: false,
+            injected_css: Vec::new(),
+            aspect_ratio: None,
+            last_size: (0, 0),
+            hang_watchdog: None,
+            app_menu,
+            pending_returns: Vec::new(),
+            web_context,
         }));
 
         Ok(NativeWebview {
@@ -259,7 +795,11 @@ impl NativeWebview {
             runner: Mutex::new(Some(event_loop)),
             state_ptr: Mutex::new(Some(state as usize)),
             hwnd,
+            window_handle,
             callbacks,
+            protocol_stats: stats,
+            protocol_token: protocol_ctx.token.clone(),
+            ready,
         })
     }
 
@@ -273,14 +813,49 @@ impl NativeWebview {
             let w_el = SendWrapper::new(el);
             let w_state = SendWrapper::new(state);
 
+            let proxy_for_loop = self.proxy.clone();
+
+            // Listener threads poll this so they wind down once the loop has exited
+            let listening = Arc::new(AtomicBool::new(true));
+            const LISTENER_POLL: std::time::Duration = std::time::Duration::from_millis(250);
+
             // Spawn Menu Event Listener Thread
             let proxy_for_menu = self.proxy.clone();
+            let menu_listening = listening.clone();
             std::thread::spawn(move || {
                 let receiver = tray_icon::menu::MenuEvent::receiver();
-                loop {
-                    if let Ok(event) = receiver.recv() {
-                        let id = event.id.0;
-                         let _ = proxy_for_menu.send_event(UserEvent::TrayMenuClick(id));
+                while menu_listening.load(Ordering::SeqCst) {
+                    match receiver.recv_timeout(LISTENER_POLL) {
+                        Ok(event) => {
+                            let id = event.id.0;
+                            match id.strip_prefix(CONTEXT_MENU_PREFIX) {
+                                Some(ctx_id) => { let _ = proxy_for_menu.send_event(UserEvent::MenuClick(ctx_id.to_string())); }
+                                None => { let _ = proxy_for_menu.send_event(UserEvent::TrayMenuClick(id)); }
+                            }
+                        }
+                        Err(e) if e.is_timeout() => {}
+                        Err(_) => break,
+                    }
+                }
+            });
+
+            // Spawn Tray Icon Event Listener Thread (left button only; right-click opens the menu)
+            let proxy_for_tray = self.proxy.clone();
+            let tray_listening = listening.clone();
+            std::thread::spawn(move || {
+                use tray_icon::{MouseButton, MouseButtonState, TrayIconEvent};
+                let receiver = TrayIconEvent::receiver();
+                while tray_listening.load(Ordering::SeqCst) {
+                    match receiver.recv_timeout(LISTENER_POLL) {
+                        Ok(TrayIconEvent::Click { position, button: MouseButton::Left, button_state: MouseButtonState::Up, .. }) => {
+                            let _ = proxy_for_tray.send_event(UserEvent::TrayIconClick(position.x, position.y));
+                        }
+                        Ok(TrayIconEvent::DoubleClick { position, button: MouseButton::Left, .. }) => {
+                            let _ = proxy_for_tray.send_event(UserEvent::TrayIconDoubleClick(position.x, position.y));
+                        }
+                        Ok(_) => {}
+                        Err(e) if e.is_timeout() => {}
+                        Err(_) => break,
                     }
                 }
             });
@@ -296,20 +871,36 @@ impl NativeWebview {
                         Event::UserEvent(ue) => {
                              // DEBUG LOGGING
                              match &ue {
-                                 UserEvent::CallPython(_, seq, _, method) => {
-                                     println!("[PYTRON BRIDGE] CALL: {} (seq={})", method, seq);
+                                 UserEvent::CallPython(_, seq, _, method, _) => {
+                                     log::debug!(target: "bridge", "CALL: {} (seq={})", method, seq);
                                  },
                                  UserEvent::Eval(_) => { /* Mute eval logs, too spammy for state sync */ },
-                                 UserEvent::Navigate(u) => println!("[PYTRON NAVIGATE] Request: '{}'", u),
-                                 UserEvent::Return(_seq, _status, _) => {
-                                     // println!("[PYTRON BRIDGE] RETURN: seq={} status={}", seq, status);
+                                 UserEvent::Navigate(u) | UserEvent::NavigateWithHeaders(u, _) => log::debug!(target: "navigate", "Request: '{}'", u),
+                                 UserEvent::Return(seq, status, _) => {
+                                     log::trace!(target: "bridge", "RETURN: seq={} status={}", seq, status);
                                  },
                                  _ => {},
                              }
                              
                              match ue {
                                 UserEvent::Quit => *control_flow = ControlFlow::Exit,
-                                UserEvent::Eval(js) => { let _ = state.webview.evaluate_script(&js); }
+                                UserEvent::RequestQuit => {
+                                    if !state.quitting {
+                                        state.quitting = true;
+                                        let (cbs, proxy) = (cbs_arc.clone(), proxy_for_loop.clone());
+                                        // Quit is queued behind whatever the handler emitted, so pending
+                                        // returns and evals still reach the page before the loop exits
+                                        std::thread::spawn(move || {
+                                            run_quit_handler(&cbs);
+                                            let _ = proxy.send_event(UserEvent::Quit);
+                                        });
+                                    }
+                                }
+                                UserEvent::Eval(js) => {
+                                    // Keep results ahead of anything the callee evaluated after returning
+                                    flush_returns(&mut state);
+                                    let _ = state.webview.evaluate_script(&js);
+                                }
                                 UserEvent::SetTitle(t) => { state.window.set_title(&t); }
                                 UserEvent::SetSize(w, h, _) => { state.window.set_inner_size(tao::dpi::LogicalSize::new(w, h)); }
                                 
@@ -317,24 +908,56 @@ impl NativeWebview {
                                     let _ = state.webview.load_url(&u);
                                 }
 
-                                UserEvent::Bind(name, _) => {
+                                UserEvent::NavigateWithHeaders(u, headers) => {
+                                    let mut map = wry::http::HeaderMap::new();
+                                    for (k, v) in headers {
+                                        match (wry::http::HeaderName::from_bytes(k.as_bytes()), wry::http::HeaderValue::from_str(&v)) {
+                                            (Ok(k), Ok(v)) => { map.insert(k, v); }
+                                            _ => log::warn!(target: "navigate", "Skipping invalid header '{}'", k),
+                                        }
+                                    }
+                                    let _ = state.webview.load_url_with_headers(&u, map);
+                                }
+
+                                UserEvent::Bind(name, _, pass_context) => {
                                     // Map is already updated in NativeWebview::bind
+                                    // The system pollers only run once something listens; binding
+                                    // here, after register_webview, means they always have a target
+                                    match name.as_str() {
+                                        "pytron_on_power_changed" => crate::power::ensure_polling(),
+                                        "pytron_on_connectivity_changed" => crate::network::ensure_polling(),
+                                        _ => {}
+                                    }
+                                    if pass_context {
+                                        state.context_callbacks.insert(name.clone());
+                                    } else {
+                                        state.context_callbacks.remove(&name);
+                                    }
                                     let js = format!(r#"window['{}'] = (...args) => window.__pytron_native_bridge('{}', args);"#, name, name);
                                     let _ = state.webview.evaluate_script(&js);
                                 }
-                                UserEvent::CallPython(f, seq, args, _) => { 
-                                    Python::with_gil(|py| { let _ = f.call1(py, (seq, args, 0)); }); 
+                                UserEvent::CallPython(f, seq, args, method, origin) => { 
+                                    let context = state.context_callbacks.contains(&method).then(|| call_context(&method, &origin));
+                                    call_bound(&f, seq, args, context);
                                 }
-                                UserEvent::Dispatch(f, seq, _) => { 
-                                     Python::with_gil(|py| { let _ = f.call1(py, (seq, "[]", 0)); }); 
+                                UserEvent::Dispatch(f, seq, method) => { 
+                                    let context = state.context_callbacks.contains(&method).then(|| call_context(&method, &state.current_url));
+                                    call_bound(&f, seq, "[]".to_string(), context);
                                 }
-                                UserEvent::DispatchData(f, seq, args, _) => { 
-                                     Python::with_gil(|py| { let _ = f.call1(py, (seq, args, 0)); }); 
+                                UserEvent::DispatchData(f, seq, args, method) => { 
+                                    let context = state.context_callbacks.contains(&method).then(|| call_context(&method, &state.current_url));
+                                    call_bound(&f, seq, args, context);
                                 }
 
                                 UserEvent::Return(seq, status, res) => {
-                                    let js = format!(r#"if (window._rpc && window._rpc['{seq}']) {{ if ({status} === 0) window._rpc['{seq}'].resolve({res}); else window._rpc['{seq}'].reject({res}); delete window._rpc['{seq}']; }}"#, seq=seq, status=status, res=res);
-                                    let _ = state.webview.evaluate_script(&js);
+                                    // Settled together once the queue drains, one script per loop pass
+                                    state.pending_returns.push((seq, status, res));
+                                }
+                                UserEvent::StreamChunk(seq, chunk) => {
+                                    state.pending_returns.push((seq, STREAM_CHUNK_STATUS, chunk));
+                                }
+                                UserEvent::StreamEnd(seq) => {
+                                    state.pending_returns.push((seq, 0, "null".to_string()));
                                 }
                                 UserEvent::SetVisible(v) => { 
                                     state.window.set_visible(v); 
@@ -342,6 +965,14 @@ impl NativeWebview {
                                         state.window.set_focus(); 
                                         state.window.set_minimized(false); 
                                     } 
+                                    on_visibility_changed(&mut state, v, &proxy_for_loop);
+                                }
+                                UserEvent::SetSuspended(s) => { set_suspended(&mut state, s); }
+                                UserEvent::SetAutoSuspend(secs) => { state.auto_suspend = secs; }
+                                UserEvent::AutoSuspend(epoch) => {
+                                    if epoch == state.visibility_epoch && !state.window.is_visible() {
+                                        set_suspended(&mut state, true);
+                                    }
                                 }
                                 UserEvent::Minimize => { state.window.set_minimized(true); }
                                 UserEvent::SetMaximized(m) => { 
@@ -351,20 +982,40 @@ impl NativeWebview {
                                          state.window.set_maximized(false);
                                     }
                                 }
+                                UserEvent::ToggleMaximize => {
+                                    let m = state.window.is_maximized();
+                                    state.window.set_maximized(!m);
+                                }
                                 UserEvent::DragWindow => { let _ = state.window.drag_window(); }
+                                UserEvent::DragResize(direction) => {
+                                    if state.window.is_resizable() && !state.window.is_maximized() && state.window.fullscreen().is_none() {
+                                        let _ = state.window.drag_resize_window(direction);
+                                    }
+                                }
                                 
                                 UserEvent::SetAlwaysOnTop(t) => { state.window.set_always_on_top(t); }
                                 UserEvent::SetResizable(r) => { state.window.set_resizable(r); }
+                                UserEvent::SetAspectRatio(ratio) => {
+                                    state.aspect_ratio = ratio;
+                                    let size = state.window.inner_size();
+                                    state.last_size = (size.width, 0); // Treat it as a width change so the height follows
+                                    enforce_aspect_ratio(&mut state, size);
+                                }
                                 UserEvent::SetFullscreen(f) => { 
                                     if f { state.window.set_fullscreen(Some(tao::window::Fullscreen::Borderless(None))); } 
                                     else { state.window.set_fullscreen(None); }
                                 }
+                                UserEvent::SetFullscreenEx(enabled, monitor_index, exclusive) => {
+                                    let mode = enabled.then(|| fullscreen_mode(&state.window, monitor_index, exclusive));
+                                    state.window.set_fullscreen(mode);
+                                }
                                 UserEvent::CenterWindow => {
+                                     // All physical: the monitor's origin and size, and the outer frame including decorations
                                      if let Some(monitor) = state.window.current_monitor() {
-                                         let screen_size = monitor.size();
-                                         let window_size = state.window.inner_size();
-                                         let x = (screen_size.width - window_size.width) / 2;
-                                         let y = (screen_size.height - window_size.height) / 2;
+                                         let (origin, screen_size) = (monitor.position(), monitor.size());
+                                         let window_size = state.window.outer_size();
+                                         let x = origin.x + (screen_size.width as i32 - window_size.width as i32).max(0) / 2;
+                                         let y = origin.y + (screen_size.height as i32 - window_size.height as i32).max(0) / 2;
                                          state.window.set_outer_position(tao::dpi::PhysicalPosition::new(x, y));
                                      }
                                 }
@@ -399,20 +1050,72 @@ impl NativeWebview {
                                     }
                                 }
 
-                                UserEvent::CreateTray(icon_path, tooltip) => {
+                                UserEvent::CreateTray(icon_path, tooltip, menu_json) => {
                                     if let Ok(ic) = load_icon(std::path::Path::new(&icon_path)) {
-                                        let menu = Menu::new();
-                                        let show_item = MenuItemBuilder::new().text("Show App").id("1000".into()).enabled(true).build();
-                                        let quit_item = MenuItemBuilder::new().text("Quit").id("1001".into()).enabled(true).build();
-                                        let _ = menu.append(&show_item);
-                                        let _ = menu.append(&PredefinedMenuItem::separator());
-                                        let _ = menu.append(&quit_item);
-
-                                        let tray_res = TrayIconBuilder::new().with_menu(Box::new(menu)).with_tooltip(&tooltip).with_icon(ic).build();
-                                        if let Ok(t) = tray_res { state.tray = Some(t); }
+                                        let specs = menu_json.as_deref().map(parse_menu).transpose().unwrap_or_else(|e| {
+                                            log::warn!("{}", e);
+                                            None
+                                        });
+                                        let mut builder = TrayIconBuilder::new().with_tooltip(&tooltip).with_icon(ic);
+                                        // An empty custom menu means no menu at all
+                                        if !matches!(specs.as_deref(), Some([])) {
+                                            builder = builder.with_menu(Box::new(build_tray_menu(specs.as_deref())));
+                                        }
+                                        if let Ok(t) = builder.build() { state.tray = Some(t); }
+                                    }
+                                    state.tray_icon_path = Some(icon_path);
+                                }
+                                UserEvent::SetTrayAnimation(paths, interval_ms) => {
+                                    let frames: Vec<_> = paths.iter().filter_map(|p| match load_icon(std::path::Path::new(p)) {
+                                        Ok(ic) => Some(ic),
+                                        Err(e) => { log::warn!(target: "native", "Skipping tray frame '{}': {}", p, e); None }
+                                    }).collect();
+                                    // Replacing the previous animation stops its timer
+                                    state.tray_animation = None;
+                                    if !frames.is_empty() {
+                                        let running = Arc::new(AtomicBool::new(true));
+                                        let (flag, proxy) = (running.clone(), proxy_for_loop.clone());
+                                        std::thread::spawn(move || {
+                                            let interval = std::time::Duration::from_millis(interval_ms.max(16));
+                                            while flag.load(Ordering::SeqCst) {
+                                                std::thread::sleep(interval);
+                                                if !flag.load(Ordering::SeqCst) || proxy.send_event(UserEvent::TrayAnimationTick).is_err() {
+                                                    break;
+                                                }
+                                            }
+                                        });
+                                        if let Some(tray) = &state.tray {
+                                            let _ = tray.set_icon(Some(frames[0].clone()));
+                                        }
+                                        state.tray_animation = Some(TrayAnimation { frames, index: 0, running });
+                                    }
+                                }
+                                UserEvent::TrayAnimationTick => {
+                                    if let (Some(anim), Some(tray)) = (state.tray_animation.as_mut(), state.tray.as_ref()) {
+                                        anim.index = (anim.index + 1) % anim.frames.len();
+                                        let _ = tray.set_icon(Some(anim.frames[anim.index].clone()));
                                     }
                                 }
+                                UserEvent::StopTrayAnimation => {
+                                    if state.tray_animation.take().is_some() {
+                                        if let (Some(tray), Some(path)) = (&state.tray, &state.tray_icon_path) {
+                                            if let Ok(ic) = load_icon(std::path::Path::new(path)) {
+                                                let _ = tray.set_icon(Some(ic));
+                                            }
+                                        }
+                                    }
+                                }
+                                UserEvent::SetPreventClose(p) => { state.prevent_close = p; }
+                                UserEvent::SetMinimizeToTray(m) => { state.minimize_to_tray = m; }
+                                UserEvent::SetCloseToTray(c) => { state.close_to_tray = c; }
                                 UserEvent::TrayMenuClick(id) => {
+                                    if id == TRAY_SHOW_ID {
+                                        // "Show App"
+                                        state.window.set_visible(true);
+                                        state.window.set_minimized(false);
+                                        state.window.set_focus();
+                                        on_visibility_changed(&mut state, true, &proxy_for_loop);
+                                    }
                                     let mut found: Option<PyObject> = None;
                                     if let Ok(cbs) = cbs_arc.lock() {
                                         if let Some(f) = cbs.get("pytron_tray_click") {
@@ -423,194 +1126,1348 @@ impl NativeWebview {
                                         Python::with_gil(|py| { let _ = f.call1(py, (id,)); }); 
                                     }
                                 }
+                                UserEvent::TrayIconClick(x, y) | UserEvent::TrayIconDoubleClick(x, y) => {
+                                    let name = match ue {
+                                        UserEvent::TrayIconClick(..) => "pytron_tray_icon_click",
+                                        _ => "pytron_tray_icon_double_click",
+                                    };
+                                    let mut found: Option<PyObject> = None;
+                                    if let Ok(cbs) = cbs_arc.lock() {
+                                        if let Some(f) = cbs.get(name) {
+                                             Python::with_gil(|py| { found = Some(f.clone_ref(py)); });
+                                        }
+                                    }
+                                    if let Some(f) = found {
+                                        Python::with_gil(|py| { let _ = f.call1(py, (x, y)); });
+                                    }
+                                }
 
                                 UserEvent::SetDecorations(d) => { state.window.set_decorations(d); }
-
-                                UserEvent::MessageBox(title, msg, level, seq) => {
-                                    let l = match level.as_str() {
-                                        "error" => rfd::MessageLevel::Error,
-                                        "warning" => rfd::MessageLevel::Warning,
-                                        _ => rfd::MessageLevel::Info,
-                                    };
-                                    let res = rfd::MessageDialog::new()
-                                        .set_title(&title)
-                                        .set_description(&msg)
-                                        .set_level(l)
-                                        .show();
-                                    
-                                    let ret = match res {
-                                        rfd::MessageDialogResult::Ok | rfd::MessageDialogResult::Yes => "true",
-                                        _ => "false"
-                                    };
-                                    
-                                    if !seq.is_empty() {
-                                        let js = format!(r#"if (window._rpc && window._rpc['{seq}']) {{ window._rpc['{seq}'].resolve({ret}); delete window._rpc['{seq}']; }}"#, seq=seq, ret=ret);
-                                        let _ = state.webview.evaluate_script(&js);
+                                UserEvent::SetIgnoreCursorEvents(ignore) => {
+                                    if let Err(e) = state.window.set_ignore_cursor_events(ignore) {
+                                        log::warn!(target: "native", "Click-through not supported: {}", e);
+                                    }
+                                }
+                                UserEvent::SetCornerPreference(pref) => apply_corner_preference(&state.window, pref),
+                                UserEvent::SetShadow(enabled) => {
+                                    #[cfg(target_os = "windows")]
+                                    {
+                                        use tao::platform::windows::WindowExtWindows;
+                                        state.window.set_undecorated_shadow(enabled);
+                                    }
+                                    #[cfg(target_os = "macos")]
+                                    {
+                                        use tao::platform::macos::WindowExtMacOS;
+                                        state.window.set_has_shadow(enabled);
                                     }
+                                    #[cfg(target_os = "linux")]
+                                    let _ = enabled;
                                 }
 
-                                UserEvent::OpenExternal(url) => {
+                                UserEvent::SetBadge(label) => {
                                     #[cfg(target_os = "windows")]
                                     {
-                                        // Use powershell to ensure the URL is handled correctly by the default browser
-                                        let _ = std::process::Command::new("powershell")
-                                            .arg("-NoProfile")
-                                            .arg("-Command")
-                                            .arg(format!("Start-Process '{}'", url))
-                                            .spawn();
+                                        use tao::platform::windows::WindowExtWindows;
+                                        let icon = label.as_deref().and_then(|l| {
+                                            let (rgba, w, h) = render_badge_rgba(l);
+                                            tao::window::Icon::from_rgba(rgba, w, h).ok()
+                                        });
+                                        state.window.set_overlay_icon(icon.as_ref());
                                     }
                                     #[cfg(target_os = "macos")]
                                     {
-                                        let _ = std::process::Command::new("open")
-                                            .arg(&url)
-                                            .spawn();
+                                        use tao::platform::macos::WindowExtMacOS;
+                                        state.window.set_badge_label(label);
                                     }
                                     #[cfg(target_os = "linux")]
                                     {
-                                        let _ = std::process::Command::new("xdg-open")
-                                            .arg(&url)
-                                            .spawn();
+                                        use tao::platform::unix::WindowExtUnix;
+                                        let count = label.and_then(|l| l.trim().parse::<i64>().ok());
+                                        state.window.set_badge_count(count, None);
                                     }
                                 }
 
-                                _ => {} 
-                            }
-                        }
-                        
-                        Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => {
-                             if state.prevent_close {
-                                 let mut found: Option<PyObject> = None;
-                                 if let Ok(cbs) = cbs_arc.lock() {
-                                     if let Some(f) = cbs.get("pytron_on_close") {
-                                         Python::with_gil(|py| { found = Some(f.clone_ref(py)); });
-                                     }
-                                 }
-                                 if let Some(f) = found {
-                                     Python::with_gil(|py| { let _ = f.call0(py); }); 
-                                 }
-                                 *control_flow = ControlFlow::Wait;
-                             } else {
-                                 *control_flow = ControlFlow::Exit; 
-                             }
-                        }
-                        _ => (),
-                    }
-                });
-            });
-        }
-        Ok(())
-    }
+                                UserEvent::ClearBrowsingData(kind, tx) => {
+                                    clear_browsing_data(&state.webview, kind, move |ok| { let _ = tx.send(ok); });
+                                }
 
-    pub fn set_title(&self, t: String) { let _ = self.proxy.send_event(UserEvent::SetTitle(t)); }
-    pub fn set_size(&self, w: i32, h: i32, hints: u32) { let _ = self.proxy.send_event(UserEvent::SetSize(w, h, hints)); }
+                                UserEvent::Print(force_light) => {
+                                    if force_light {
+                                        emulate_light_scheme(&state.webview, true);
+                                        let _ = state.webview.evaluate_script(PRINT_LIGHT_SCRIPT);
+                                    }
+                                    let _ = state.webview.print();
+                                }
+                                UserEvent::ConnectivityChanged(online) => {
+                                    let _ = state.webview.evaluate_script(&format!(
+                                        "window.dispatchEvent(new CustomEvent('pytron:connectivity', {{ detail: {{ online: {} }} }}));",
+                                        online
+                                    ));
+                                    let mut found: Option<PyObject> = None;
+                                    if let Ok(cbs) = cbs_arc.lock() {
+                                        if let Some(f) = cbs.get("pytron_on_connectivity_changed") {
+                                            Python::with_gil(|py| { found = Some(f.clone_ref(py)); });
+                                        }
+                                    }
+                                    if let Some(f) = found {
+                                        Python::with_gil(|py| {
+                                            if let Err(e) = f.call1(py, (online,)) {
+                                                log::warn!(target: "native", "pytron_on_connectivity_changed failed: {}", e);
+                                            }
+                                        });
+                                    }
+                                }
+                                UserEvent::PowerChanged(power) => {
+                                    let mut found: Option<PyObject> = None;
+                                    if let Ok(cbs) = cbs_arc.lock() {
+                                        if let Some(f) = cbs.get("pytron_on_power_changed") {
+                                            Python::with_gil(|py| { found = Some(f.clone_ref(py)); });
+                                        }
+                                    }
+                                    if let Some(f) = found {
+                                        Python::with_gil(|py| {
+                                            if let Err(e) = power.to_dict(py).and_then(|d| f.call1(py, (d,))) {
+                                                log::warn!(target: "native", "pytron_on_power_changed failed: {}", e);
+                                            }
+                                        });
+                                    }
+                                }
+                                UserEvent::PrintDone => emulate_light_scheme(&state.webview, false),
+                                UserEvent::GetSize(tx) => {
+                                    let size = state.window.inner_size().to_logical::<f64>(state.window.scale_factor());
+                                    let _ = tx.send((size.width, size.height));
+                                }
+                                UserEvent::GetUrl(tx) => {
+                                    // The engine also sees pushState and fragment changes that never reach the handlers
+                                    let url = state.webview.url().unwrap_or_else(|_| state.current_url.clone());
+                                    let _ = tx.send(url);
+                                }
+                                UserEvent::GetWebviewVersion(tx) => {
+                                    let _ = tx.send(webview_version(&state.webview));
+                                }
+
+                                UserEvent::PageLoaded(url) => {
+                                    state.current_url = url;
+                                    reset_watchdog(&mut state);
+                                    for (id, css) in &state.injected_css {
+                                        let _ = state.webview.evaluate_script(&css_script(id, css));
+                                    }
+                                    sync_accelerators(&state);
+                                }
+                                UserEvent::InsertCss(id, css) => {
+                                    let _ = state.webview.evaluate_script(&css_script(&id, &css));
+                                    match state.injected_css.iter_mut().find(|(i, _)| *i == id) {
+                                        Some(entry) => entry.1 = css,
+                                        None => state.injected_css.push((id, css)),
+                                    }
+                                }
+                                UserEvent::RemoveCss(id) => {
+                                    state.injected_css.retain(|(i, _)| *i != id);
+                                    let id = serde_json::to_string(&id).unwrap_or_default();
+                                    let _ = state.webview.evaluate_script(&format!("document.getElementById({})?.remove();", id));
+                                }
+                                UserEvent::Emit(event, detail) => emit_js(&state.webview, &event, &detail),
+                                UserEvent::AssetsChanged(paths) => {
+                                    let paths = serde_json::to_string(&paths).unwrap_or_else(|_| "[]".to_string());
+                                    let _ = state.webview.evaluate_script(&format!("{}({});", crate::watcher::HMR_SCRIPT, paths));
+                                }
+                                UserEvent::RendererCrashed(reason) => {
+                                    log::error!(target: "native", "Renderer process gone: {}", reason);
+                                    let mut found: Option<PyObject> = None;
+                                    if let Ok(cbs) = cbs_arc.lock() {
+                                        if let Some(f) = cbs.get("pytron_on_renderer_crash") {
+                                             Python::with_gil(|py| { found = Some(f.clone_ref(py)); });
+                                        }
+                                    }
+                                    // The callback can force a reload by returning True
+                                    let wants_reload = found.map(|f| Python::with_gil(|py| {
+                                        f.call1(py, (reason.clone(),)).and_then(|r| r.extract::<bool>(py)).unwrap_or(false)
+                                    })).unwrap_or(false);
+                                    if reason == "render-unresponsive" {
+                                        report_page_hang(&cbs_arc, &reason);
+                                    }
+                                    if (wants_reload || state.reload_on_crash) && !state.current_url.is_empty() {
+                                        log::info!(target: "native", "Reloading {}", state.current_url);
+                                        let _ = state.webview.load_url(&state.current_url);
+                                    }
+                                }
+                                UserEvent::SetHangWatchdog(config) => {
+                                    // Replacing the previous watchdog stops its timer
+                                    state.hang_watchdog = None;
+                                    if let Some((interval_ms, misses)) = config {
+                                        let running = Arc::new(AtomicBool::new(true));
+                                        let (flag, proxy) = (running.clone(), proxy_for_loop.clone());
+                                        std::thread::spawn(move || {
+                                            let interval = std::time::Duration::from_millis(interval_ms.max(100));
+                                            while flag.load(Ordering::SeqCst) {
+                                                std::thread::sleep(interval);
+                                                if !flag.load(Ordering::SeqCst) || proxy.send_event(UserEvent::HangPing).is_err() {
+                                                    break;
+                                                }
+                                            }
+                                        });
+                                        state.hang_watchdog = Some(HangWatchdog { misses_allowed: misses.max(1), missed: 0, awaiting: false, reported: false, running });
+                                    }
+                                }
+                                UserEvent::HangPing => {
+                                    // Suspended or loading pages can't answer; don't count those as hangs
+                                    if state.suspended || state.current_url.is_empty() {
+                                        reset_watchdog(&mut state);
+                                    } else if let Some(dog) = state.hang_watchdog.as_mut() {
+                                        if dog.awaiting {
+                                            dog.missed += 1;
+                                        }
+                                        if dog.missed >= dog.misses_allowed && !dog.reported {
+                                            dog.reported = true;
+                                            report_page_hang(&cbs_arc, "unresponsive");
+                                        }
+                                        dog.awaiting = true;
+                                        let _ = state.webview.evaluate_script(r#"window.ipc.postMessage('{"id":"","method":"pytron_pong","params":[]}')"#);
+                                    }
+                                }
+                                UserEvent::HangPong => reset_watchdog(&mut state),
+                                UserEvent::Cookies(cmd) => { run_cookie_command(&state.webview, cmd); }
+
+                                UserEvent::ShowContextMenu(json, x, y) => {
+                                    match parse_menu(&json) {
+                                        Ok(specs) => {
+                                            let menu = build_context_menu(&specs);
+                                            let pos = Some(tray_icon::menu::dpi::Position::Logical(
+                                                tray_icon::menu::dpi::LogicalPosition::new(x as f64, y as f64),
+                                            ));
+                                            #[cfg(target_os = "windows")]
+                                            unsafe {
+                                                use tao::platform::windows::WindowExtWindows;
+                                                menu.show_context_menu_for_hwnd(state.window.hwnd() as _, pos);
+                                            }
+                                            #[cfg(target_os = "linux")]
+                                            {
+                                                use gtk::prelude::*;
+                                                use tao::platform::unix::WindowExtUnix;
+                                                menu.show_context_menu_for_gtk_window(state.window.gtk_window().upcast_ref(), pos);
+                                            }
+                                            #[cfg(target_os = "macos")]
+                                            unsafe {
+                                                use tao::platform::macos::WindowExtMacOS;
+                                                menu.show_context_menu_for_nsview(state.window.ns_view() as _, pos);
+                                            }
+                                            state.context_menu = Some(menu);
+                                        }
+                                        Err(e) => log::warn!("{}", e),
+                                    }
+                                }
+                                UserEvent::MenuClick(id) => {
+                                    if let Some((_, command)) = id.strip_prefix(EDIT_ROLE_PREFIX).and_then(edit_command) {
+                                        let _ = state.webview.evaluate_script(&format!("document.execCommand('{}')", command));
+                                        return;
+                                    }
+                                    let mut found: Option<PyObject> = None;
+                                    if let Ok(cbs) = cbs_arc.lock() {
+                                        if let Some(f) = cbs.get("pytron_menu_click") {
+                                             Python::with_gil(|py| { found = Some(f.clone_ref(py)); });
+                                        }
+                                    }
+                                    if let Some(f) = found {
+                                        Python::with_gil(|py| { let _ = f.call1(py, (id.clone(),)); });
+                                    }
+                                    emit_js(&state.webview, "pytron:menu-click", &serde_json::to_string(&id).unwrap_or_default());
+                                }
+                                UserEvent::RegisterAccelerator(mods, code, id) => {
+                                    state.accelerators.retain(|(_, _, existing)| *existing != id);
+                                    state.accelerators.push((mods, code, id));
+                                    sync_accelerators(&state);
+                                }
+                                UserEvent::UnregisterAccelerator(id) => {
+                                    state.accelerators.retain(|(_, _, existing)| *existing != id);
+                                    sync_accelerators(&state);
+                                }
+                                UserEvent::KeyDown(mods, code) => {
+                                    let matched = state.accelerators.iter().find(|(m, c, _)| *m == mods && *c == code).map(|(_, _, id)| id.clone());
+                                    if let Some(id) = matched {
+                                        let mut found: Option<PyObject> = None;
+                                        if let Ok(cbs) = cbs_arc.lock() {
+                                            if let Some(f) = cbs.get("pytron_on_accelerator") {
+                                                 Python::with_gil(|py| { found = Some(f.clone_ref(py)); });
+                                            }
+                                        }
+                                        if let Some(f) = found {
+                                            Python::with_gil(|py| {
+                                                if let Err(e) = f.call1(py, (id.clone(),)) {
+                                                    log::warn!(target: "native", "pytron_on_accelerator failed: {}", e);
+                                                }
+                                            });
+                                        }
+                                        emit_js(&state.webview, "pytron:accelerator", &serde_json::to_string(&id).unwrap_or_default());
+                                    }
+                                }
+                                UserEvent::Custom(json) => {
+                                    let mut found: Option<PyObject> = None;
+                                    if let Ok(cbs) = cbs_arc.lock() {
+                                        if let Some(f) = cbs.get("pytron_on_custom") {
+                                             Python::with_gil(|py| { found = Some(f.clone_ref(py)); });
+                                        }
+                                    }
+                                    match found {
+                                        Some(f) => Python::with_gil(|py| {
+                                            if let Err(e) = f.call1(py, (json,)) {
+                                                log::warn!(target: "native", "pytron_on_custom failed: {}", e);
+                                            }
+                                        }),
+                                        None => log::debug!(target: "native", "Custom event dropped, no pytron_on_custom bound"),
+                                    }
+                                }
+
+                                UserEvent::Log(msg) => {
+                                    let mut found: Option<PyObject> = None;
+                                    if let Ok(cbs) = cbs_arc.lock() {
+                                        if let Some(f) = cbs.get("pytron_log") {
+                                             Python::with_gil(|py| { found = Some(f.clone_ref(py)); });
+                                        }
+                                    }
+                                    match found {
+                                        Some(f) => Python::with_gil(|py| { let _ = f.call1(py, (msg,)); }),
+                                        None => log::info!(target: "js", "{}", msg),
+                                    }
+                                }
+
+                                UserEvent::MessageBox(title, msg, level, seq, buttons) => {
+                                    let mut dialog = rfd::AsyncMessageDialog::new()
+                                        .set_title(&title)
+                                        .set_description(&msg)
+                                        .set_level(parse_level(&level))
+                                        .set_parent(&state.window);
+                                    if let Some(b) = &buttons {
+                                        dialog = dialog.set_buttons(parse_buttons(b));
+                                    }
+                                    // Await off-loop so the window keeps painting behind the dialog
+                                    let proxy = proxy_for_loop.clone();
+                                    std::thread::spawn(move || {
+                                        let res = block_on(dialog.show());
+                                        // Explicit button sets resolve with the button name, legacy calls with a bool
+                                        let ret = match (&buttons, res) {
+                                            (Some(_), r) => serde_json::to_string(&result_label(r)).unwrap_or_default(),
+                                            (None, rfd::MessageDialogResult::Ok | rfd::MessageDialogResult::Yes) => "true".to_string(),
+                                            (None, _) => "false".to_string(),
+                                        };
+                                        if !seq.is_empty() {
+                                            let _ = proxy.send_event(UserEvent::Return(seq, 0, ret));
+                                        }
+                                    });
+                                }
+
+                                UserEvent::ShowAbout(name, version, author) => {
+                                    // The standard panel takes name, version and icon from Info.plist
+                                    #[cfg(target_os = "macos")]
+                                    unsafe {
+                                        use objc::runtime::Object;
+                                        use objc::{class, msg_send, sel, sel_impl};
+                                        let _ = (name, version, author);
+                                        let app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+                                        let _: () = msg_send![app, orderFrontStandardAboutPanel: std::ptr::null_mut::<Object>()];
+                                    }
+                                    #[cfg(not(target_os = "macos"))]
+                                    {
+                                        let name = name.unwrap_or_else(|| state.window.title());
+                                        let mut text = name.clone();
+                                        if let Some(v) = version { text.push_str(&format!("\nVersion {}", v)); }
+                                        if let Some(a) = author { text.push_str(&format!("\n\u{a9} {}", a)); }
+                                        let dialog = rfd::AsyncMessageDialog::new()
+                                            .set_title(format!("About {}", name))
+                                            .set_description(text)
+                                            .set_level(rfd::MessageLevel::Info)
+                                            .set_parent(&state.window);
+                                        std::thread::spawn(move || { block_on(dialog.show()); });
+                                    }
+                                }
+
+                                UserEvent::FileDialog(kind, opts, seq) => {
+                                    let dialog = opts.builder().set_parent(&state.window);
+                                    let proxy = proxy_for_loop.clone();
+                                    std::thread::spawn(move || {
+                                        let res = block_on(show_file_dialog(kind, dialog));
+                                        let _ = proxy.send_event(UserEvent::Return(seq, 0, file_dialog_json(kind, res)));
+                                    });
+                                }
+
+                                UserEvent::OpenExternal(url) => {
+                                    #[cfg(target_os = "windows")]
+                                    {
+                                        // Use powershell to ensure the URL is handled correctly by the default browser
+                                        let _ = std::process::Command::new("powershell")
+                                            .arg("-NoProfile")
+                                            .arg("-Command")
+                                            .arg(format!("Start-Process '{}'", url))
+                                            .spawn();
+                                    }
+                                    #[cfg(target_os = "macos")]
+                                    {
+                                        let _ = std::process::Command::new("open")
+                                            .arg(&url)
+                                            .spawn();
+                                    }
+                                    #[cfg(target_os = "linux")]
+                                    {
+                                        let _ = std::process::Command::new("xdg-open")
+                                            .arg(&url)
+                                            .spawn();
+                                    }
+                                }
+
+                                _ => {} 
+                            }
+                        }
+                        
+                        Event::WindowEvent { event: WindowEvent::Resized(size), .. } => {
+                             enforce_aspect_ratio(&mut state, size);
+                             if state.minimize_to_tray && state.tray.is_some() && state.window.is_minimized() {
+                                 state.window.set_visible(false);
+                                 on_visibility_changed(&mut state, false, &proxy_for_loop);
+                             }
+                        }
+                        Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => {
+                             if (state.minimize_to_tray || state.close_to_tray) && state.tray.is_some() {
+                                 state.window.set_visible(false);
+                                 on_visibility_changed(&mut state, false, &proxy_for_loop);
+                             } else if state.prevent_close {
+                                 let mut found: Option<PyObject> = None;
+                                 if let Ok(cbs) = cbs_arc.lock() {
+                                     if let Some(f) = cbs.get("pytron_on_close") {
+                                         Python::with_gil(|py| { found = Some(f.clone_ref(py)); });
+                                     }
+                                 }
+                                 if let Some(f) = found {
+                                     Python::with_gil(|py| { let _ = f.call0(py); }); 
+                                 }
+                                 *control_flow = ControlFlow::Wait;
+                             } else {
+                                 let _ = proxy_for_loop.send_event(UserEvent::RequestQuit);
+                             }
+                        }
+                        // macOS delivers documents opened from Finder (kAEOpenDocuments) here instead of argv
+                        Event::Opened { urls } => {
+                            let mut found: Option<PyObject> = None;
+                            if let Ok(cbs) = cbs_arc.lock() {
+                                if let Some(f) = cbs.get("pytron_on_open_file") {
+                                    Python::with_gil(|py| { found = Some(f.clone_ref(py)); });
+                                }
+                            }
+                            let paths = urls.iter().filter_map(|u| u.to_file_path().ok());
+                            match found {
+                                Some(f) => Python::with_gil(|py| {
+                                    for path in paths {
+                                        if let Err(e) = f.call1(py, (path.to_string_lossy().into_owned(),)) {
+                                            log::warn!(target: "native", "pytron_on_open_file failed: {}", e);
+                                        }
+                                    }
+                                }),
+                                None => log::debug!(target: "native", "Opened {} file(s), no pytron_on_open_file bound", paths.count()),
+                            }
+                        }
+                        Event::MainEventsCleared => flush_returns(&mut state),
+                        Event::LoopDestroyed => listening.store(false, Ordering::SeqCst),
+                        _ => (),
+                    }
+                });
+            });
+        }
+        Ok(())
+    }
+
+    pub fn set_title(&self, t: String) { let _ = self.proxy.send_event(UserEvent::SetTitle(t)); }
+    /// Inner (content) size in logical pixels, i.e. before the display's scale factor.
+    pub fn set_size(&self, w: i32, h: i32, hints: u32) { let _ = self.proxy.send_event(UserEvent::SetSize(w, h, hints)); }
+    /// Inner size in logical pixels, the same units `set_size` takes. None if the event loop isn't running.
+    pub fn get_size(&self, py: Python<'_>) -> Option<(f64, f64)> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.proxy.send_event(UserEvent::GetSize(tx)).ok()?;
+        py.allow_threads(move || rx.recv_timeout(std::time::Duration::from_secs(2)).ok())
+    }
     pub fn navigate(&self, u: String) { let _ = self.proxy.send_event(UserEvent::Navigate(u)); }
+    /// Like `navigate`, attaching extra request headers (e.g. Authorization) to the initial load.
+    pub fn navigate_with_headers(&self, u: String, headers: HashMap<String, String>) {
+        let _ = self.proxy.send_event(UserEvent::NavigateWithHeaders(u, headers.into_iter().collect()));
+    }
     pub fn eval(&self, j: String) { let _ = self.proxy.send_event(UserEvent::Eval(j)); }
-    pub fn bind(&self, n: String, f: PyObject) { 
+    /// With `pass_context`, page calls pass `{"window_id", "origin", "method"}` as JSON in the
+    /// third argument; otherwise it stays `0` as it always was.
+    #[pyo3(signature = (n, f, pass_context=false))]
+    pub fn bind(&self, n: String, f: PyObject, pass_context: bool) { 
         if let Ok(mut cbs) = self.callbacks.lock() {
             Python::with_gil(|py| { cbs.insert(n.clone(), f.clone_ref(py)); });
         }
-        let _ = self.proxy.send_event(UserEvent::Bind(n, f)); 
+        let _ = self.proxy.send_event(UserEvent::Bind(n, f, pass_context)); 
     }
     pub fn return_result(&self, s: String, st: i32, r: String) { let _ = self.proxy.send_event(UserEvent::Return(s, st, r)); }
+    /// Delivers one JSON chunk of a streaming call; the page reads them through `call.stream()`
+    /// or `for await`. `end_stream` (or an error `return_result`) closes the stream.
+    pub fn emit_chunk(&self, seq: String, data: String) { let _ = self.proxy.send_event(UserEvent::StreamChunk(seq, data)); }
+    pub fn end_stream(&self, seq: String) { let _ = self.proxy.send_event(UserEvent::StreamEnd(seq)); }
+    /// Injects `css` as a `<style>` element that survives navigation. Reusing an `id` replaces
+    /// that sheet in place; returns the id for `remove_css`.
+    #[pyo3(signature = (css, id=None))]
+    pub fn insert_css(&self, css: String, id: Option<String>) -> String {
+        static NEXT_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+        let id = id.unwrap_or_else(|| format!("pytron-css-{}", NEXT_ID.fetch_add(1, Ordering::SeqCst)));
+        let _ = self.proxy.send_event(UserEvent::InsertCss(id.clone(), css));
+        id
+    }
+
+    pub fn remove_css(&self, id: String) {
+        let _ = self.proxy.send_event(UserEvent::RemoveCss(id));
+    }
+
+    /// Blocks (GIL released) until the current page's bridge is up, so `eval`/`emit` aren't dropped.
+    /// Returns false if `timeout_ms` elapsed first.
+    pub fn wait_until_ready(&self, py: Python<'_>, timeout_ms: u64) -> PyResult<bool> {
+        let ready = self.ready.clone();
+        Ok(py.allow_threads(move || {
+            let (lock, cvar) = &*ready;
+            let Ok(guard) = lock.lock() else { return false };
+            cvar.wait_timeout_while(guard, std::time::Duration::from_millis(timeout_ms), |r| !*r)
+                .map(|(r, _)| *r)
+                .unwrap_or(false)
+        }))
+    }
+
+    /// Windows AppUserModelID (taskbar grouping, icon, jump lists). No-op elsewhere.
+    pub fn set_app_id(&self, id: String) -> PyResult<()> {
+        set_process_app_id(&id)
+    }
+
+    /// Hard exit: the loop stops without running `pytron_on_quit`.
     pub fn terminate(&self) { let _ = self.proxy.send_event(UserEvent::Quit); }
+    /// Graceful exit: `pytron_on_quit` gets up to `QUIT_TIMEOUT` to save state, then the loop stops.
+    pub fn quit(&self) { let _ = self.proxy.send_event(UserEvent::RequestQuit); }
     pub fn show(&self) { let _ = self.proxy.send_event(UserEvent::SetVisible(true)); }
     pub fn hide(&self) { let _ = self.proxy.send_event(UserEvent::SetVisible(false)); }
+    /// Freezes rendering, timers and script while the window is hidden. Resumed automatically on show.
+    pub fn suspend(&self) { let _ = self.proxy.send_event(UserEvent::SetSuspended(true)); }
+    pub fn resume(&self) { let _ = self.proxy.send_event(UserEvent::SetSuspended(false)); }
+    /// Suspend after the window has stayed hidden for `seconds`; `None` turns it off.
+    #[pyo3(signature = (seconds=None))]
+    pub fn set_auto_suspend(&self, seconds: Option<u64>) { let _ = self.proxy.send_event(UserEvent::SetAutoSuspend(seconds)); }
+    /// Pings the page every `interval_ms`; after `misses` unanswered pings in a row
+    /// `pytron_on_page_hang("unresponsive")` is called once per hang. None stops the watchdog.
+    #[pyo3(signature = (interval_ms, misses=3))]
+    pub fn set_hang_watchdog(&self, interval_ms: Option<u64>, misses: u32) {
+        let _ = self.proxy.send_event(UserEvent::SetHangWatchdog(interval_ms.map(|ms| (ms, misses))));
+    }
     pub fn minimize(&self) { let _ = self.proxy.send_event(UserEvent::Minimize); }
     pub fn maximize(&self) { let _ = self.proxy.send_event(UserEvent::SetMaximized(true)); }
     pub fn unmaximize(&self) { let _ = self.proxy.send_event(UserEvent::SetMaximized(false)); }
+    pub fn toggle_maximize(&self) { let _ = self.proxy.send_event(UserEvent::ToggleMaximize); }
+    /// Hide to the tray instead of closing/minimizing (only while a tray icon exists).
+    pub fn set_minimize_to_tray(&self, m: bool) { let _ = self.proxy.send_event(UserEvent::SetMinimizeToTray(m)); }
+    /// The close button hides the window while a tray icon exists; without one it closes as usual.
+    pub fn set_close_to_tray(&self, c: bool) { let _ = self.proxy.send_event(UserEvent::SetCloseToTray(c)); }
     pub fn start_drag(&self) { let _ = self.proxy.send_event(UserEvent::DragWindow); }
     pub fn system_notification(&self, t: String, m: String) { let _ = self.proxy.send_event(UserEvent::Notification(t, m)); }
     pub fn set_taskbar_progress(&self, s: i32, v: i32, m: i32) { let _ = self.proxy.send_event(UserEvent::TaskbarProgress(s, v, m)); }
+    /// Whether the OS reports internet access. `pytron_on_connectivity_changed(online)` and a
+    /// `pytron:connectivity` DOM event follow every change.
+    pub fn is_online(&self, py: Python<'_>) -> bool { py.allow_threads(crate::network::is_online) }
+    /// `on_battery`, `battery_percent` (None without a battery) and `charging`.
+    /// `pytron_on_power_changed` receives the same dict whenever it changes.
+    pub fn get_power_state<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        crate::power::read().to_dict(py)
+    }
+    /// `backend` ("webview2" | "wkwebview" | "webkitgtk") and the engine's `version`, for bug reports.
+    /// On Windows this is the runtime the window actually runs on, which may be a fixed-version one.
+    pub fn get_webview_version<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let version = if self.proxy.send_event(UserEvent::GetWebviewVersion(tx)).is_ok() {
+            py.allow_threads(move || rx.recv_timeout(std::time::Duration::from_secs(2)).ok())
+        } else {
+            None
+        };
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("backend", WEBVIEW_BACKEND)?;
+        dict.set_item("version", version.unwrap_or_else(|| wry::webview_version().unwrap_or_default()))?;
+        Ok(dict)
+    }
+    /// The page's current URL, including in-page route changes. Empty if the event loop isn't running.
+    pub fn get_url(&self, py: Python<'_>) -> String {
+        let (tx, rx) = std::sync::mpsc::channel();
+        if self.proxy.send_event(UserEvent::GetUrl(tx)).is_err() {
+            return String::new();
+        }
+        py.allow_threads(move || rx.recv_timeout(std::time::Duration::from_secs(2)).unwrap_or_default())
+    }
     pub fn get_hwnd(&self) -> usize { self.hwnd }
+    /// ("win32" | "appkit" | "xlib" | "xcb" | "wayland", raw handle)
+    pub fn get_window_handle(&self) -> (String, usize) { self.window_handle.clone() }
+    /// Custom protocol counters, or `None` when created without `protocol_stats=True`.
+    /// `cache_hits` counts requests served from the embedded archive.
+    pub fn get_protocol_stats(&self) -> Option<HashMap<&'static str, u64>> {
+        self.protocol_stats.as_ref().map(|s| s.snapshot())
+    }
+    /// The per-launch protocol token, or `None` without `protocol_token=True`. Python appends it
+    /// as `?pytron_token=` to URLs it serves, since `src` attributes can't send headers.
+    pub fn protocol_token(&self) -> Option<String> { self.protocol_token.clone() }
     
+    /// Opens the print dialog for the page. `force_light` prints dark-themed pages in a
+    /// light scheme: `prefers-color-scheme` is emulated as light on Windows, and a print-only
+    /// white background is injected everywhere, both undone once the dialog closes.
+    #[pyo3(signature = (force_light=false))]
+    pub fn print(&self, force_light: bool) { let _ = self.proxy.send_event(UserEvent::Print(force_light)); }
     pub fn set_fullscreen(&self, e: bool) { let _ = self.proxy.send_event(UserEvent::SetFullscreen(e)); }
+    /// Fullscreen on the `monitor_index`-th display (as the OS enumerates them; default: the
+    /// window's current one). `exclusive` switches the display to a video mode at its native
+    /// resolution instead of covering it with a borderless window.
+    #[pyo3(signature = (enabled, monitor_index=None, exclusive=false))]
+    pub fn set_fullscreen_ex(&self, enabled: bool, monitor_index: Option<usize>, exclusive: bool) {
+        let _ = self.proxy.send_event(UserEvent::SetFullscreenEx(enabled, monitor_index, exclusive));
+    }
     pub fn set_always_on_top(&self, e: bool) { let _ = self.proxy.send_event(UserEvent::SetAlwaysOnTop(e)); }
     pub fn set_resizable(&self, e: bool) { let _ = self.proxy.send_event(UserEvent::SetResizable(e)); }
+    /// Keeps the window's inner size at `w:h` while the user resizes it. Call with no
+    /// arguments (or None) to allow any shape again.
+    #[pyo3(signature = (w=None, h=None))]
+    pub fn set_aspect_ratio(&self, w: Option<u32>, h: Option<u32>) -> PyResult<()> {
+        let ratio = match (w, h) {
+            (Some(w), Some(h)) if w > 0 && h > 0 => Some((w, h)),
+            (None, None) => None,
+            _ => return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>("Aspect ratio needs a positive width and height")),
+        };
+        let _ = self.proxy.send_event(UserEvent::SetAspectRatio(ratio));
+        Ok(())
+    }
     pub fn set_decorations(&self, e: bool) { let _ = self.proxy.send_event(UserEvent::SetDecorations(e)); }
+    /// Click-through: the window stays drawn but mouse input goes to whatever is beneath it.
+    pub fn set_ignore_cursor_events(&self, ignore: bool) { let _ = self.proxy.send_event(UserEvent::SetIgnoreCursorEvents(ignore)); }
+    /// Windows 11 corner style: "round", "small", "square" or "default". No-op elsewhere.
+    pub fn set_corner_preference(&self, pref: String) -> PyResult<()> {
+        let _ = self.proxy.send_event(UserEvent::SetCornerPreference(corner_preference(&pref)?));
+        Ok(())
+    }
+    /// Drop shadow for frameless windows (Windows, macOS). No effect on Linux.
+    pub fn set_shadow(&self, e: bool) { let _ = self.proxy.send_event(UserEvent::SetShadow(e)); }
     pub fn center(&self) { let _ = self.proxy.send_event(UserEvent::CenterWindow); }
 
     #[pyo3(signature = (title, dir=None, filters=None))]
-    pub fn dialog_open_file(&self, title: String, dir: Option<String>, filters: Option<String>) -> PyResult<Option<String>> {
-        #[cfg(target_os = "windows")]
-        {
-            let mut d = rfd::FileDialog::new().set_title(&title);
-            if let Some(p) = dir { d = d.set_directory(PathBuf::from(p)); }
-            if let Some(f) = filters {
-                 for group in f.split(';') {
-                     let parts: Vec<&str> = group.split(':').collect();
-                     if parts.len() == 2 {
-                         let exts: Vec<&str> = parts[1].split(',').collect();
-                         d = d.add_filter(parts[0], &exts);
-                     }
-                 }
-            }
-            let res = d.pick_file();
-            Ok(res.map(|p| p.to_string_lossy().to_string()))
-        }
-        #[cfg(not(target_os = "windows"))]
-        { Ok(None) }
+    pub fn dialog_open_file(&self, py: Python<'_>, title: String, dir: Option<String>, filters: Option<String>) -> PyResult<Option<String>> {
+        let opts = FileDialogOptions { title, dir, name: None, filters };
+        Ok(run_file_dialog(py, FileDialogKind::OpenFile, opts).and_then(|p| p.into_iter().next()))
+    }
+
+    #[pyo3(signature = (title, dir=None, filters=None))]
+    pub fn dialog_open_files(&self, py: Python<'_>, title: String, dir: Option<String>, filters: Option<String>) -> PyResult<Option<Vec<String>>> {
+        let opts = FileDialogOptions { title, dir, name: None, filters };
+        Ok(run_file_dialog(py, FileDialogKind::OpenFiles, opts))
     }
 
     #[pyo3(signature = (title, dir=None, name=None, filters=None))]
-    pub fn dialog_save_file(&self, title: String, dir: Option<String>, name: Option<String>, filters: Option<String>) -> PyResult<Option<String>> {
-         #[cfg(target_os = "windows")]
-        {
-            let mut d = rfd::FileDialog::new().set_title(&title);
-            if let Some(p) = dir { d = d.set_directory(PathBuf::from(p)); }
-            if let Some(n) = name { d = d.set_file_name(&n); }
-             if let Some(f) = filters {
-                 for group in f.split(';') {
-                     let parts: Vec<&str> = group.split(':').collect();
-                     if parts.len() == 2 {
-                         let exts: Vec<&str> = parts[1].split(',').collect();
-                         d = d.add_filter(parts[0], &exts);
-                     }
-                 }
-            }
-            let res = d.save_file();
-            Ok(res.map(|p| p.to_string_lossy().to_string()))
-        }
-        #[cfg(not(target_os = "windows"))]
-        { Ok(None) }
+    pub fn dialog_save_file(&self, py: Python<'_>, title: String, dir: Option<String>, name: Option<String>, filters: Option<String>) -> PyResult<Option<String>> {
+        let opts = FileDialogOptions { title, dir, name, filters };
+        Ok(run_file_dialog(py, FileDialogKind::SaveFile, opts).and_then(|p| p.into_iter().next()))
     }
     
     #[pyo3(signature = (title, dir=None))]
-    pub fn dialog_open_folder(&self, title: String, dir: Option<String>) -> PyResult<Option<String>> {
-         #[cfg(target_os = "windows")]
-        {
-            let mut d = rfd::FileDialog::new().set_title(&title);
-            if let Some(p) = dir { d = d.set_directory(PathBuf::from(p)); }
-            let res = d.pick_folder();
-            Ok(res.map(|p| p.to_string_lossy().to_string()))
-        }
-        #[cfg(not(target_os = "windows"))]
-        { Ok(None) }
+    pub fn dialog_open_folder(&self, py: Python<'_>, title: String, dir: Option<String>) -> PyResult<Option<String>> {
+        let opts = FileDialogOptions { title, dir, name: None, filters: None };
+        Ok(run_file_dialog(py, FileDialogKind::OpenFolder, opts).and_then(|p| p.into_iter().next()))
     }
 
-    pub fn message_box(&self, title: String, msg: String, level: String) -> PyResult<bool> {
+    pub fn message_box(&self, py: Python<'_>, title: String, msg: String, level: String) -> PyResult<bool> {
+        let res = self.message_box_ex(py, title, msg, level, "ok".to_string())?;
+        Ok(res == "ok" || res == "yes")
+    }
+
+    /// Native About box with the name, version and author from the app's settings.
+    /// macOS shows the standard About panel instead.
+    pub fn show_about(&self, py: Python<'_>) {
+        let mut info = read_app_info(py);
+        let mut take = |key: &str| info.remove(key).flatten();
+        let _ = self.proxy.send_event(UserEvent::ShowAbout(take("name"), take("version"), take("author")));
+    }
+
+    /// Returns the pressed button: "ok", "cancel", "yes", "no", or a custom label.
+    #[pyo3(signature = (title, msg, level=String::from("info"), buttons=String::from("okcancel")))]
+    pub fn message_box_ex(&self, py: Python<'_>, title: String, msg: String, level: String, buttons: String) -> PyResult<String> {
         #[cfg(target_os = "windows")]
         {
-             let l = match level.as_str() {
-                 "error" => rfd::MessageLevel::Error,
-                 "warning" => rfd::MessageLevel::Warning,
-                 _ => rfd::MessageLevel::Info,
-             };
-             let res = rfd::MessageDialog::new().set_title(&title).set_description(&msg).set_level(l).show();
-             let ret = match res {
-                 rfd::MessageDialogResult::Ok | rfd::MessageDialogResult::Yes => true,
-                 _ => false
-             };
-             Ok(ret)
+             let dialog = rfd::AsyncMessageDialog::new()
+                 .set_title(&title)
+                 .set_description(&msg)
+                 .set_level(parse_level(&level))
+                 .set_buttons(parse_buttons(&buttons));
+             Ok(py.allow_threads(move || result_label(block_on(dialog.show()))))
         }
          #[cfg(not(target_os = "windows"))]
-        { Ok(false) }
+        { let _ = (py, title, msg, level, buttons); Ok("cancel".to_string()) }
+    }
+
+    #[pyo3(signature = (title, label, default=None))]
+    pub fn dialog_prompt(&self, py: Python<'_>, title: String, label: String, default: Option<String>) -> PyResult<Option<String>> {
+        let default = default.unwrap_or_default();
+        Ok(py.allow_threads(move || crate::dialogs::prompt(&title, &label, &default)))
+    }
+
+    /// Pops up a native menu at window-relative logical (x, y).
+    /// Clicks arrive at the `pytron_menu_click` callback and as a `pytron:menu-click` DOM event.
+    pub fn show_context_menu(&self, menu_json: String, x: i32, y: i32) -> PyResult<()> {
+        parse_menu(&menu_json).map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+        let _ = self.proxy.send_event(UserEvent::ShowContextMenu(menu_json, x, y));
+        Ok(())
+    }
+
+    /// Dock badge text on macOS, a rendered overlay icon on the Windows taskbar
+    /// (numbers only), launcher count on Linux. `None` clears it.
+    #[pyo3(signature = (label=None))]
+    pub fn set_badge(&self, label: Option<String>) { let _ = self.proxy.send_event(UserEvent::SetBadge(label)); }
+
+    /// Dispatches `CustomEvent(event_name, { detail })` on this window. `json` must be valid JSON.
+    pub fn emit(&self, event_name: String, json: String) -> PyResult<()> {
+        check_json(&json)?;
+        let _ = self.proxy.send_event(UserEvent::Emit(event_name, json));
+        Ok(())
+    }
+
+    /// Window-local shortcut such as "CmdOrCtrl+Shift+K". While this window is focused, pressing it
+    /// calls `pytron_on_accelerator(id)` and dispatches `pytron:accelerator`. Re-registering an id replaces it.
+    pub fn register_accelerator(&self, accel: String, id: String) -> PyResult<()> {
+        let (mods, code) = parse_accelerator(&accel).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid accelerator '{}': {}", accel, e))
+        })?;
+        let _ = self.proxy.send_event(UserEvent::RegisterAccelerator(mods, code, id));
+        Ok(())
+    }
+    pub fn unregister_accelerator(&self, id: String) { let _ = self.proxy.send_event(UserEvent::UnregisterAccelerator(id)); }
+
+    /// Queues `json` for `pytron_on_custom(json)` on the event loop thread. Safe from any thread;
+    /// Rust extensions can send `UserEvent::Custom` through `proxy` directly.
+    pub fn post_custom(&self, json: String) -> PyResult<()> {
+        check_json(&json)?;
+        let _ = self.proxy.send_event(UserEvent::Custom(json));
+        Ok(())
+    }
+
+    /// Like `emit`, but for every live webview in the process. Returns how many were reached.
+    pub fn broadcast(&self, event_name: String, json: String) -> PyResult<usize> {
+        check_json(&json)?;
+        Ok(crate::state::broadcast(&event_name, &json))
+    }
+
+    /// Clears the HTTP cache. Blocks until the webview reports completion.
+    pub fn clear_cache(&self, py: Python<'_>) -> bool { self.clear_data(py, BrowsingData::Cache) }
+    /// Deletes every cookie in the webview profile.
+    pub fn clear_cookies(&self, py: Python<'_>) -> bool { self.clear_data(py, BrowsingData::Cookies) }
+    /// Wipes cache, cookies, storage and history.
+    pub fn clear_all_data(&self, py: Python<'_>) -> bool { self.clear_data(py, BrowsingData::All) }
+
+    /// Cookies visible to `url`, as dicts with name, value, domain, path, expires (unix seconds or None), http_only, secure.
+    /// Raises RuntimeError before `run()`, since the store can't be read until the loop starts.
+    pub fn get_cookies<'py>(&self, py: Python<'py>, url: String) -> PyResult<Vec<Bound<'py, pyo3::types::PyDict>>> {
+        if !self.loop_started() {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("get_cookies() needs a running event loop; call it after run()"));
+        }
+        let (tx, rx) = std::sync::mpsc::channel();
+        let cookies = self.cookie_request(py, CookieCommand::Get(url, tx), rx).unwrap_or_default();
+        cookies.into_iter().map(|c| {
+            let d = pyo3::types::PyDict::new(py);
+            d.set_item("name", c.name)?;
+            d.set_item("value", c.value)?;
+            d.set_item("domain", c.domain)?;
+            d.set_item("path", c.path)?;
+            d.set_item("expires", c.expires)?;
+            d.set_item("http_only", c.http_only)?;
+            d.set_item("secure", c.secure)?;
+            Ok(d)
+        }).collect()
+    }
+
+    /// Domain and path default to the url's host and "/". Before `run()` the cookie is queued and
+    /// applied as the loop starts, and this returns True once it is queued.
+    #[pyo3(signature = (url, name, value, domain=None, path=None, expires=None, http_only=false, secure=false))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_cookie(&self, py: Python<'_>, url: String, name: String, value: String, domain: Option<String>, path: Option<String>, expires: Option<f64>, http_only: bool, secure: bool) -> bool {
+        let cookie = CookieData {
+            name,
+            value,
+            domain: domain.unwrap_or_default(),
+            path: path.unwrap_or_default(),
+            expires,
+            http_only,
+            secure,
+        };
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.cookie_change(py, CookieCommand::Set(url, cookie, tx), rx)
+    }
+
+    /// True once the store has handled the request, whether or not a cookie by that name existed.
+    /// Queued like `set_cookie` before `run()`.
+    pub fn delete_cookie(&self, py: Python<'_>, url: String, name: String) -> bool {
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.cookie_change(py, CookieCommand::Delete(url, name, tx), rx)
     }
 
     pub fn set_prevent_close(&self, p: bool) {
         let _ = self.proxy.send_event(UserEvent::SetPreventClose(p));
     }
     
-    pub fn create_tray(&self, icon_path: String, tooltip: String) {
-        let _ = self.proxy.send_event(UserEvent::CreateTray(icon_path, tooltip));
+    /// Cycles the tray icon through `frames` (image paths) every `interval_ms` until
+    /// `stop_tray_animation`, which restores the static icon.
+    pub fn set_tray_animation(&self, frames: Vec<String>, interval_ms: u64) -> PyResult<()> {
+        if frames.is_empty() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>("Tray animation needs at least one frame"));
+        }
+        let _ = self.proxy.send_event(UserEvent::SetTrayAnimation(frames, interval_ms));
+        Ok(())
+    }
+
+    pub fn stop_tray_animation(&self) {
+        let _ = self.proxy.send_event(UserEvent::StopTrayAnimation);
+    }
+
+    /// `menu_json` uses the context-menu format and replaces the default Show App / Quit items;
+    /// pass `"[]"` for a tray icon without a menu.
+    #[pyo3(signature = (icon_path, tooltip, menu_json=None))]
+    pub fn create_tray(&self, icon_path: String, tooltip: String, menu_json: Option<String>) -> PyResult<()> {
+        if let Some(json) = &menu_json {
+            parse_menu(json).map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+        }
+        let _ = self.proxy.send_event(UserEvent::CreateTray(icon_path, tooltip, menu_json));
+        Ok(())
+    }
+}
+
+// Blocks the calling Python thread (GIL released) until the dialog closes.
+fn run_file_dialog(py: Python<'_>, kind: FileDialogKind, opts: FileDialogOptions) -> Option<Vec<String>> {
+    #[cfg(target_os = "windows")]
+    {
+        let dialog = opts.builder();
+        py.allow_threads(move || block_on(show_file_dialog(kind, dialog)))
+    }
+    #[cfg(not(target_os = "windows"))]
+    { let _ = (py, kind, opts); None }
+}
+
+fn check_json(json: &str) -> PyResult<()> {
+    serde_json::from_str::<serde_json::Value>(json)
+        .map(|_| ())
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid JSON: {}", e)))
+}
+
+#[cfg(target_os = "windows")]
+fn set_process_app_id(id: &str) -> PyResult<()> {
+    let id = windows::core::HSTRING::from(id);
+    unsafe { windows::Win32::UI::Shell::SetCurrentProcessExplicitAppUserModelID(&id) }
+        .map_err(|e| PytronWindowError::new_err(format!("Failed to set AppUserModelID: {}", e)))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn set_process_app_id(_id: &str) -> PyResult<()> {
+    Ok(())
+}
+
+/// Resolves the webview's data directory. Each profile gets its own folder under
+/// `data_directory/profiles`, so two profiles never share cookies or storage.
+pub(crate) fn profile_data_dir(data_directory: Option<String>, profile: Option<String>) -> PyResult<Option<PathBuf>> {
+    let Some(profile) = profile else {
+        return Ok(data_directory.map(PathBuf::from));
+    };
+    let Some(base) = data_directory else {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>("A profile requires a data_directory"));
+    };
+    let valid = !profile.is_empty()
+        && profile != "."
+        && profile != ".."
+        && !profile.contains(['/', '\\', ':']);
+    if !valid {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid profile name '{}'", profile)));
+    }
+    let dir = PathBuf::from(base).join("profiles").join(profile);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| PytronWindowError::new_err(format!("Could not create profile directory {}: {}", dir.display(), e)))?;
+    Ok(Some(dir))
+}
+
+/// Style element id used for the constructor's `initial_css`.
+const INITIAL_CSS_ID: &str = "pytron-initial-css";
+
+/// Creates `<style id=..>`, or replaces its text, so swapping a theme is a single repaint.
+fn css_script(id: &str, css: &str) -> String {
+    let id = serde_json::to_string(id).unwrap_or_default();
+    let css = serde_json::to_string(css).unwrap_or_default();
+    format!(
+        r#"(function() {{
+            const apply = () => {{
+                let el = document.getElementById({id});
+                if (!el) {{
+                    el = document.createElement('style');
+                    el.id = {id};
+                    (document.head || document.documentElement).appendChild(el);
+                }}
+                el.textContent = {css};
+            }};
+            if (document.documentElement) return apply();
+            // Initialization scripts can run before the root element exists
+            new MutationObserver((_, obs) => {{
+                if (document.documentElement) {{ obs.disconnect(); apply(); }}
+            }}).observe(document, {{ childList: true }});
+        }})();"#
+    )
+}
+
+fn emit_js(webview: &wry::WebView, event: &str, detail: &str) {
+    let event = serde_json::to_string(event).unwrap_or_default();
+    let js = format!("window.dispatchEvent(new CustomEvent({}, {{ detail: {} }}));", event, detail);
+    let _ = webview.evaluate_script(&js);
+}
+
+fn fullscreen_mode(window: &tao::window::Window, monitor_index: Option<usize>, exclusive: bool) -> tao::window::Fullscreen {
+    use tao::window::Fullscreen;
+    let monitor = match monitor_index {
+        Some(i) => window.available_monitors().nth(i).or_else(|| {
+            log::warn!(target: "native", "No monitor at index {}, using the current one", i);
+            window.current_monitor()
+        }),
+        None => window.current_monitor(),
+    };
+    if !exclusive {
+        return Fullscreen::Borderless(monitor);
+    }
+    // Native resolution at the best refresh rate; the largest mode if the native one isn't listed
+    let video_mode = monitor.as_ref().and_then(|m| {
+        let native = m.size();
+        m.video_modes()
+            .filter(|v| v.size() == native)
+            .max_by_key(|v| (v.refresh_rate(), v.bit_depth()))
+            .or_else(|| m.video_modes().max_by_key(|v| (v.size().width * v.size().height, v.refresh_rate())))
+    });
+    match video_mode {
+        Some(mode) => Fullscreen::Exclusive(mode),
+        None => Fullscreen::Borderless(monitor),
+    }
+}
+
+/// Snaps `size` back to the configured aspect ratio. Whichever side changed more since the
+/// last resize is taken as the one being dragged; the other follows it.
+fn enforce_aspect_ratio(state: &mut RuntimeState, size: tao::dpi::PhysicalSize<u32>) {
+    let (prev_w, prev_h) = std::mem::replace(&mut state.last_size, (size.width, size.height));
+    let Some((rw, rh)) = state.aspect_ratio else { return };
+    if size.width == 0 || size.height == 0 || state.window.is_maximized() || state.window.is_minimized() || state.window.fullscreen().is_some() {
+        return;
+    }
+    let width_moved = size.width.abs_diff(prev_w) as u64 * rh as u64 >= size.height.abs_diff(prev_h) as u64 * rw as u64;
+    let target = if width_moved {
+        (size.width, (size.width as u64 * rh as u64 / rw as u64) as u32)
+    } else {
+        ((size.height as u64 * rw as u64 / rh as u64) as u32, size.height)
+    };
+    // Rounding leaves a pixel of slack; without it the correction would resize forever
+    if target.0.abs_diff(size.width) > 1 || target.1.abs_diff(size.height) > 1 {
+        state.last_size = target;
+        state.window.set_inner_size(tao::dpi::PhysicalSize::new(target.0, target.1));
+    }
+}
+
+/// Resize cursors and edge drags for frameless windows, within `BORDER` CSS px of the edge.
+#[cfg(target_os = "linux")]
+const FRAMELESS_RESIZE_SCRIPT: &str = r#"
+(() => {
+    const BORDER = 5;
+    const CURSORS = {n: 'ns-resize', s: 'ns-resize', e: 'ew-resize', w: 'ew-resize', ne: 'nesw-resize', sw: 'nesw-resize', nw: 'nwse-resize', se: 'nwse-resize'};
+    const edgeAt = (x, y) => {
+        // Maximized windows have no edges to drag
+        if (window.outerWidth >= screen.availWidth && window.outerHeight >= screen.availHeight) return '';
+        const ns = y < BORDER ? 'n' : y >= window.innerHeight - BORDER ? 's' : '';
+        const ew = x < BORDER ? 'w' : x >= window.innerWidth - BORDER ? 'e' : '';
+        return ns + ew;
+    };
+    let style = null;
+    window.addEventListener('mousemove', (e) => {
+        const edge = e.buttons === 0 ? edgeAt(e.clientX, e.clientY) : '';
+        if (edge) {
+            style = style || document.createElement('style');
+            style.textContent = `* { cursor: ${CURSORS[edge]} !important; }`;
+            if (!style.isConnected) document.documentElement.appendChild(style);
+        } else if (style && style.isConnected) {
+            style.remove();
+        }
+    }, true);
+    window.addEventListener('mousedown', (e) => {
+        const edge = e.button === 0 ? edgeAt(e.clientX, e.clientY) : '';
+        if (!edge) return;
+        e.preventDefault();
+        e.stopPropagation();
+        window.ipc.postMessage(JSON.stringify({id: '', method: 'pytron_resize', params: [edge]}));
+    }, true);
+})();
+"#;
+
+fn resize_direction(edge: &str) -> Option<tao::window::ResizeDirection> {
+    use tao::window::ResizeDirection::*;
+    Some(match edge {
+        "n" => North,
+        "s" => South,
+        "e" => East,
+        "w" => West,
+        "ne" => NorthEast,
+        "nw" => NorthWest,
+        "se" => SouthEast,
+        "sw" => SouthWest,
+        _ => return None,
+    })
+}
+
+/// Print-only light stylesheet, removed (and the emulation reset) after printing.
+const PRINT_LIGHT_SCRIPT: &str = r#"
+(() => {
+    const style = document.createElement('style');
+    style.textContent = '@media print { :root { color-scheme: light !important; } html, body { background: #fff !important; color: #000 !important; } }';
+    document.head.appendChild(style);
+    window.addEventListener('afterprint', () => {
+        style.remove();
+        window.ipc.postMessage(JSON.stringify({id: '', method: 'pytron_print_done', params: []}));
+    }, {once: true});
+})();
+"#;
+
+/// Tells the page's keydown filter which shortcuts are registered, so other Ctrl/Alt/F-key
+/// presses never leave the page.
+fn sync_accelerators(state: &RuntimeState) {
+    let keys: Vec<String> = state.accelerators.iter().map(|(mods, code, _)| accelerator_key(*mods, *code)).collect();
+    let keys = serde_json::to_string(&keys).unwrap_or_else(|_| "[]".to_string());
+    let _ = state.webview.evaluate_script(&format!("window.__pytron_accelerators = new Set({});", keys));
+}
+
+/// `"<ctrl><alt><shift><meta>:<code>"` (flags as 0/1) as the page builds it from a keydown.
+fn accelerator_key(mods: Modifiers, code: Code) -> String {
+    let flag = |m: Modifiers| u8::from(mods.contains(m));
+    format!("{}{}{}{}:{}", flag(Modifiers::CONTROL), flag(Modifiers::ALT), flag(Modifiers::SHIFT), flag(Modifiers::SUPER), code)
+}
+
+/// Emulates `prefers-color-scheme: light` through the DevTools protocol, so themes keyed on
+/// the media query switch too. WebView2 only; elsewhere the stylesheet has to do.
+#[allow(unused_variables)]
+fn emulate_light_scheme(webview: &wry::WebView, enabled: bool) {
+    #[cfg(target_os = "windows")]
+    unsafe {
+        use webview2_com::CallDevToolsProtocolMethodCompletedHandler;
+        use wry::WebViewExtWindows;
+
+        let Ok(core) = webview.controller().CoreWebView2() else { return };
+        let params = if enabled {
+            r#"{"features":[{"name":"prefers-color-scheme","value":"light"}]}"#
+        } else {
+            r#"{"features":[]}"#
+        };
+        let handler = CallDevToolsProtocolMethodCompletedHandler::create(Box::new(|_, _| Ok(())));
+        let method = windows::core::HSTRING::from("Emulation.setEmulatedMedia");
+        let params = windows::core::HSTRING::from(params);
+        if let Err(e) = core.CallDevToolsProtocolMethod(&method, &params, &handler) {
+            log::warn!(target: "native", "Could not emulate the light color scheme: {}", e);
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+const WEBVIEW_BACKEND: &str = "webview2";
+#[cfg(target_os = "macos")]
+const WEBVIEW_BACKEND: &str = "wkwebview";
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const WEBVIEW_BACKEND: &str = "webkitgtk";
+
+/// Engine version string; empty if it can't be determined.
+#[allow(unused_variables)]
+fn webview_version(webview: &wry::WebView) -> String {
+    // The environment knows the runtime in use; wry only reports the newest installed one
+    #[cfg(target_os = "windows")]
+    unsafe {
+        use webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2_2;
+        use windows::core::{Interface, PWSTR};
+        use wry::WebViewExtWindows;
+
+        let environment = webview.controller().CoreWebView2().and_then(|c| c.cast::<ICoreWebView2_2>()).and_then(|c| c.Environment());
+        if let Ok(environment) = environment {
+            let mut version = PWSTR::null();
+            if environment.BrowserVersionString(&mut version).is_ok() {
+                return webview2_com::take_pwstr(version);
+            }
+        }
+    }
+    wry::webview_version().unwrap_or_default()
+}
+
+/// Status of a queued streaming chunk; the call stays pending until a regular result arrives.
+const STREAM_CHUNK_STATUS: i32 = 2;
+
+/// Settles every result queued since the last flush with a single script.
+fn flush_returns(state: &mut RuntimeState) {
+    if state.pending_returns.is_empty() {
+        return;
+    }
+    let results: Vec<String> = state.pending_returns
+        .drain(..)
+        .map(|(seq, status, res)| format!("[{},{},{}]", serde_json::Value::String(seq), status, res))
+        .collect();
+    let js = format!("window.__pytron_settle && window.__pytron_settle([{}]);", results.join(","));
+    let _ = state.webview.evaluate_script(&js);
+}
+
+fn on_visibility_changed(state: &mut RuntimeState, visible: bool, proxy: &EventLoopProxy<UserEvent>) {
+    state.visibility_epoch += 1;
+    if visible {
+        set_suspended(state, false);
+    } else if let Some(secs) = state.auto_suspend {
+        let (epoch, proxy) = (state.visibility_epoch, proxy.clone());
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_secs(secs));
+            let _ = proxy.send_event(UserEvent::AutoSuspend(epoch));
+        });
+    }
+}
+
+/// Maps a corner style name to its DWM_WINDOW_CORNER_PREFERENCE value.
+fn corner_preference(name: &str) -> PyResult<i32> {
+    match name {
+        "default" => Ok(0),
+        "square" => Ok(1),
+        "round" => Ok(2),
+        "small" => Ok(3),
+        _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Unknown corner preference '{}', expected round, small, square or default", name
+        ))),
+    }
+}
+
+#[allow(unused_variables)]
+fn apply_corner_preference(window: &tao::window::Window, pref: i32) {
+    #[cfg(target_os = "windows")]
+    unsafe {
+        use tao::platform::windows::WindowExtWindows;
+        use windows::Win32::Foundation::HWND;
+        use windows::Win32::Graphics::Dwm::{DwmSetWindowAttribute, DWMWA_WINDOW_CORNER_PREFERENCE};
+
+        // Fails harmlessly before Windows 11, which has no corner preference
+        let _ = DwmSetWindowAttribute(
+            HWND(window.hwnd() as _),
+            DWMWA_WINDOW_CORNER_PREFERENCE,
+            &pref as *const i32 as *const _,
+            std::mem::size_of::<i32>() as u32,
+        );
+    }
+}
+
+/// wry's default WebView2 arguments, dropped by wry as soon as we pass our own.
+#[cfg(target_os = "windows")]
+pub(crate) const WEBVIEW2_DEFAULT_ARGS: &str = "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection";
+/// Switches that keep Chromium off the GPU.
+#[cfg(target_os = "windows")]
+const SOFTWARE_RENDERING_ARGS: &str = "--disable-gpu --disable-gpu-compositing";
+
+// Only WebView2 environment/controller creation is worth retrying; anything else is our bug
+#[cfg(target_os = "windows")]
+fn is_backend_init_error(e: &wry::Error) -> bool {
+    matches!(e, wry::Error::WebView2Error(_))
+}
+#[cfg(not(target_os = "windows"))]
+fn is_backend_init_error(_e: &wry::Error) -> bool {
+    false
+}
+
+// The app usually has no window of its own yet, so the user would otherwise see nothing at all
+fn alert_webview_failure(e: &wry::Error) {
+    let hint = if cfg!(target_os = "windows") {
+        "Make sure the Microsoft Edge WebView2 Runtime is installed and your graphics drivers are up to date."
+    } else if cfg!(target_os = "linux") {
+        "Make sure WebKitGTK is installed. On some drivers, setting WEBKIT_DISABLE_COMPOSITING_MODE=1 helps."
+    } else {
+        "Try updating your operating system."
+    };
+    rfd::MessageDialog::new()
+        .set_title("Unable to start")
+        .set_description(format!("The web view could not be created:\n{}\n\n{}", e, hint))
+        .set_level(rfd::MessageLevel::Error)
+        .set_buttons(rfd::MessageButtons::Ok)
+        .show();
+}
+
+// WebView2 can truly suspend its renderer; elsewhere hiding the view is what throttles timers
+fn set_suspended(state: &mut RuntimeState, suspend: bool) {
+    if state.suspended == suspend {
+        return;
+    }
+    state.suspended = suspend;
+    log::debug!(target: "native", "Webview {}", if suspend { "suspended" } else { "resumed" });
+
+    if suspend {
+        let _ = state.webview.set_visible(false);
+    }
+    #[cfg(target_os = "windows")]
+    unsafe {
+        use webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2_3;
+        use windows::core::Interface;
+        use wry::WebViewExtWindows;
+        if let Ok(core) = state.webview.controller().CoreWebView2().and_then(|c| c.cast::<ICoreWebView2_3>()) {
+            let res = if suspend {
+                core.TrySuspend(&webview2_com::TrySuspendCompletedHandler::create(Box::new(|_, _| Ok(()))))
+            } else {
+                core.Resume()
+            };
+            if let Err(e) = res {
+                log::warn!(target: "native", "WebView2 suspend/resume failed: {}", e);
+            }
+        }
+    }
+    if !suspend {
+        let _ = state.webview.set_visible(true);
+    }
+}
+
+/// The page answered (or can't be expected to); start counting misses from zero.
+fn reset_watchdog(state: &mut RuntimeState) {
+    if let Some(dog) = state.hang_watchdog.as_mut() {
+        dog.missed = 0;
+        dog.awaiting = false;
+        dog.reported = false;
+    }
+}
+
+fn report_page_hang(cbs: &Arc<Mutex<HashMap<String, PyObject>>>, reason: &str) {
+    log::warn!(target: "native", "Page is not responding ({})", reason);
+    let mut found: Option<PyObject> = None;
+    if let Ok(cbs) = cbs.lock() {
+        if let Some(f) = cbs.get("pytron_on_page_hang") {
+            Python::with_gil(|py| { found = Some(f.clone_ref(py)); });
+        }
     }
+    if let Some(f) = found {
+        Python::with_gil(|py| {
+            if let Err(e) = f.call1(py, (reason,)) {
+                log::warn!(target: "native", "pytron_on_page_hang failed: {}", e);
+            }
+        });
+    }
+}
+
+// Render-process loss leaves a blank window; report it to the loop so it can reload
+fn install_crash_handler(webview: &wry::WebView, proxy: EventLoopProxy<UserEvent>) {
+    #[cfg(target_os = "windows")]
+    unsafe {
+        use webview2_com::Microsoft::Web::WebView2::Win32::*;
+        use webview2_com::ProcessFailedEventHandler;
+        use wry::WebViewExtWindows;
+
+        let Ok(core) = webview.controller().CoreWebView2() else { return };
+        let handler = ProcessFailedEventHandler::create(Box::new(move |_, args| {
+            let Some(args) = args else { return Ok(()) };
+            let mut kind = COREWEBVIEW2_PROCESS_FAILED_KIND::default();
+            args.ProcessFailedKind(&mut kind)?;
+            let reason = match kind {
+                COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_EXITED => "render-exited",
+                COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_UNRESPONSIVE => "render-unresponsive",
+                COREWEBVIEW2_PROCESS_FAILED_KIND_BROWSER_PROCESS_EXITED => "browser-exited",
+                // WebView2 restarts the GPU and helper processes on its own
+                _ => return Ok(()),
+            };
+            let _ = proxy.send_event(UserEvent::RendererCrashed(reason.to_string()));
+            Ok(())
+        }));
+        let mut token = Default::default();
+        let _ = core.add_ProcessFailed(&handler, &mut token);
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use webkit2gtk::{WebProcessTerminationReason, WebViewExt};
+        use wry::WebViewExtUnix;
+        webview.webview().connect_web_process_terminated(move |_, reason| {
+            let reason = match reason {
+                WebProcessTerminationReason::Crashed => "render-exited",
+                WebProcessTerminationReason::ExceededMemoryLimit => "render-oom",
+                _ => return,
+            };
+            let _ = proxy.send_event(UserEvent::RendererCrashed(reason.to_string()));
+        });
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        // wry owns the WKNavigationDelegate that receives content-process termination
+        let _ = (webview, proxy);
+    }
+}
+
+/// A fragment-only change (`page#a` -> `page#b`) scrolls the current document instead of loading one.
+fn is_same_document(target: &str, current: &str) -> bool {
+    let Some((target_doc, _)) = target.split_once('#') else { return false };
+    !current.is_empty() && current.split('#').next() == Some(target_doc)
+}
+
+/// Allowlist entries ending in `:` or `://` match a scheme (`file:`), anything else a host
+/// with optional port (`localhost:5173`, `*.example.com`), whatever the scheme.
+fn is_allowlisted(url: &str, allowlist: &[String]) -> bool {
+    let Ok(parsed) = url::Url::parse(url) else { return false };
+    allowlist.iter().any(|entry| {
+        if let Some(scheme) = entry.strip_suffix("://").or_else(|| entry.strip_suffix(':')) {
+            return parsed.scheme().eq_ignore_ascii_case(scheme);
+        }
+        let Some(host) = parsed.host_str() else { return false };
+        let (pattern, port) = match entry.rsplit_once(':') {
+            Some((h, p)) => (h, p.parse::<u16>().ok()),
+            None => (entry.as_str(), None),
+        };
+        let host_ok = match pattern.strip_prefix("*.") {
+            Some(domain) => host.eq_ignore_ascii_case(domain) || host.to_ascii_lowercase().ends_with(&format!(".{}", domain.to_ascii_lowercase())),
+            None => host.eq_ignore_ascii_case(pattern),
+        };
+        host_ok && (port.is_none() || parsed.port_or_known_default() == port)
+    })
+}
+
+/// `sys.pytron_app_info`: {name, version, author} set by the loader, or from settings.json in dev runs.
+fn read_app_info(py: Python<'_>) -> HashMap<String, Option<String>> {
+    py.import("sys")
+        .and_then(|sys| sys.getattr("pytron_app_info"))
+        .and_then(|i| i.extract())
+        .unwrap_or_default()
+}
+
+/// How long `pytron_on_quit` may run before a graceful quit exits anyway.
+const QUIT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Runs the `pytron_on_quit` callback on its own thread and waits at most `QUIT_TIMEOUT` for it.
+fn run_quit_handler(cbs: &Mutex<HashMap<String, PyObject>>) {
+    let mut found: Option<PyObject> = None;
+    if let Ok(cbs) = cbs.lock() {
+        if let Some(f) = cbs.get("pytron_on_quit") {
+            Python::with_gil(|py| { found = Some(f.clone_ref(py)); });
+        }
+    }
+    let Some(f) = found else { return };
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        Python::with_gil(|py| {
+            if let Err(e) = f.call0(py) {
+                log::warn!(target: "native", "Quit handler failed: {}", e);
+            }
+        });
+        let _ = tx.send(());
+    });
+    if rx.recv_timeout(QUIT_TIMEOUT).is_err() {
+        log::warn!(target: "native", "Quit handler still running after {}s, exiting anyway", QUIT_TIMEOUT.as_secs());
+    }
+}
+
+/// Identifies the only window until multi-window support lands.
+pub const MAIN_WINDOW_ID: &str = "main";
+
+/// Third argument to bound callbacks: `{"window_id", "origin", "method"}` as JSON.
+fn call_context(method: &str, origin: &str) -> String {
+    serde_json::json!({ "window_id": MAIN_WINDOW_ID, "origin": origin, "method": method }).to_string()
+}
+
+/// Calls `f(seq, args, context)` for callbacks bound with `pass_context`, `f(seq, args, 0)` otherwise.
+fn call_bound(f: &PyObject, seq: String, args: String, context: Option<String>) {
+    Python::with_gil(|py| {
+        let res = match context {
+            Some(context) => f.call1(py, (seq, args, context)),
+            None => f.call1(py, (seq, args, 0)),
+        };
+        if let Err(e) = res {
+            log::warn!(target: "bridge", "Bound callback failed: {}", e);
+        }
+    });
 }
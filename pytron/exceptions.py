@@ -34,3 +34,26 @@ class DependencyError(PytronError):
     """Raised when a required dependency is missing."""
 
     pass
+
+
+# Raised by the native engine; all subclass RuntimeError. Fallbacks keep imports working without it.
+try:
+    from .dependencies.pytron_native import (
+        PytronNativeError,
+        PytronWindowError,
+        PytronIPCError,
+        PytronProtocolError,
+    )
+except ImportError:
+
+    class PytronNativeError(RuntimeError):
+        """Base class for native engine failures."""
+
+    class PytronWindowError(PytronNativeError):
+        """The window or webview could not be created."""
+
+    class PytronIPCError(PytronNativeError):
+        """The IPC pipe could not be opened or written."""
+
+    class PytronProtocolError(PytronNativeError):
+        """The custom protocol (asset serving) could not be set up."""
@@ -179,6 +179,19 @@ class MetadataEditor:
             ),
         }
 
+        # Document types for double-click-to-open (delivered to the app as kAEOpenDocuments)
+        associations = settings.get("file_associations") or []
+        if associations:
+            info_plist["CFBundleDocumentTypes"] = [
+                {
+                    "CFBundleTypeName": a.get("description")
+                    or f"{a['ext'].lstrip('.').upper()} file",
+                    "CFBundleTypeExtensions": [a["ext"].lstrip(".").lower()],
+                    "CFBundleTypeRole": "Editor",
+                }
+                for a in associations
+            ]
+
         # Merge user custom plist settings
         custom_plist = settings.get("macos_plist")
         if custom_plist and isinstance(custom_plist, dict):
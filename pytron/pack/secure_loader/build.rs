@@ -1,4 +1,33 @@
+use std::path::{Path, PathBuf};
+
 fn main() {
     #[cfg(windows)]
     embed_resource::compile("app.rc", embed_resource::NONE);
+
+    embed_settings();
+}
+
+// PYTRON_EMBED_SETTINGS=<path to settings.json|toml|yaml> bakes the app's settings into the
+// binary. Without it an empty file is embedded and the loader reads settings from disk.
+fn embed_settings() {
+    println!("cargo:rerun-if-env-changed=PYTRON_EMBED_SETTINGS");
+    let out = PathBuf::from(std::env::var_os("OUT_DIR").expect("OUT_DIR is set by cargo")).join("embedded_settings");
+
+    let content = match std::env::var_os("PYTRON_EMBED_SETTINGS").map(PathBuf::from) {
+        Some(path) => {
+            println!("cargo:rerun-if-changed={}", path.display());
+            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("json");
+            println!("cargo:rustc-env=PYTRON_EMBEDDED_SETTINGS_FORMAT={}", ext);
+            std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("Cannot embed settings from {}: {}", path.display(), e))
+        }
+        None => String::new(),
+    };
+    write_if_changed(&out, &content);
+}
+
+fn write_if_changed(path: &Path, content: &str) {
+    if std::fs::read_to_string(path).map(|c| c == content).unwrap_or(false) {
+        return;
+    }
+    std::fs::write(path, content).expect("Cannot write embedded settings");
 }
@@ -5,10 +5,12 @@ from pathlib import Path
 import os
 
 
-def build_and_deploy():
+def build_and_deploy(settings_path=None):
     """
     Compiles the Rust bootloader and deploys the binary to the bin/ folder.
     This ensures the 'secure' packager always has the latest hardened version.
+    With settings_path, that settings file is compiled into the binary and the
+    loader ignores settings files on disk.
     """
     # 1. Setup paths
     base_dir = Path(__file__).parent.resolve()
@@ -22,6 +24,11 @@ def build_and_deploy():
     # 3. Compile Rust (Release mode)
     print(f"[*] Starting build of {loader_name}...")
     env = os.environ.copy()
+    if settings_path:
+        env["PYTRON_EMBED_SETTINGS"] = str(Path(settings_path).resolve())
+        print(f"[INFO] Embedding settings from {settings_path}")
+    else:
+        env.pop("PYTRON_EMBED_SETTINGS", None)
 
     # macOS requires special linker flags for PyO3
     if sys.platform == "darwin":
@@ -70,4 +77,5 @@ def build_and_deploy():
 
 
 if __name__ == "__main__":
-    build_and_deploy()
+    # Optional: path to the settings file to embed
+    build_and_deploy(sys.argv[1] if len(sys.argv) > 1 else None)
@@ -0,0 +1,136 @@
+use std::path::Path;
+use crate::config::FileAssociation;
+
+/// Registers `assocs` for the current user so double-clicking a document launches this exe
+/// with the path in argv. macOS takes them from Info.plist instead (see pack/metadata.py).
+pub fn register_file_associations(assocs: &[FileAssociation], app_id: &str, exe: &Path) {
+    if assocs.is_empty() {
+        return;
+    }
+    #[cfg(windows)]
+    register_windows(assocs, app_id, exe);
+    #[cfg(target_os = "linux")]
+    register_linux(assocs, app_id, exe);
+    #[cfg(not(any(windows, target_os = "linux")))]
+    let _ = (app_id, exe);
+}
+
+/// Arguments that name an existing file with one of the associated extensions.
+pub fn opened_files(args: &[String], assocs: &[FileAssociation]) -> Vec<String> {
+    args.iter()
+        .skip(1)
+        .filter(|arg| {
+            let path = Path::new(arg);
+            let ext = path.extension().map(|e| e.to_string_lossy().to_ascii_lowercase());
+            path.is_file() && assocs.iter().any(|a| Some(a.extension()) == ext)
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(windows)]
+fn register_windows(assocs: &[FileAssociation], app_id: &str, exe: &Path) {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::shared::minwindef::HKEY;
+    use winapi::um::winnt::{KEY_WRITE, REG_SZ};
+    use winapi::um::winreg::{RegCloseKey, RegCreateKeyExW, RegSetValueExW, HKEY_CURRENT_USER};
+
+    fn wide(s: &str) -> Vec<u16> {
+        std::ffi::OsStr::new(s).encode_wide().chain(Some(0)).collect()
+    }
+
+    // Writes the default value of HKCU\Software\Classes\<key>
+    fn set_default(key: &str, value: &str) -> bool {
+        let path = wide(&format!("Software\\Classes\\{}", key));
+        let data = wide(value);
+        unsafe {
+            let mut handle: HKEY = std::ptr::null_mut();
+            if RegCreateKeyExW(HKEY_CURRENT_USER, path.as_ptr(), 0, std::ptr::null_mut(), 0, KEY_WRITE, std::ptr::null_mut(), &mut handle, std::ptr::null_mut()) != 0 {
+                return false;
+            }
+            let ok = RegSetValueExW(handle, std::ptr::null(), 0, REG_SZ, data.as_ptr() as *const u8, (data.len() * 2) as u32) == 0;
+            RegCloseKey(handle);
+            ok
+        }
+    }
+
+    let exe = exe.display().to_string();
+    for assoc in assocs {
+        let ext = assoc.extension();
+        let prog_id = format!("{}.{}", app_id, ext);
+        let icon = assoc.icon.clone().unwrap_or_else(|| format!("\"{}\",0", exe));
+        let ok = set_default(&format!(".{}", ext), &prog_id)
+            && set_default(&prog_id, &assoc.description())
+            && set_default(&format!("{}\\DefaultIcon", prog_id), &icon)
+            && set_default(&format!("{}\\shell\\open\\command", prog_id), &format!("\"{}\" \"%1\"", exe));
+        if !ok {
+            eprintln!("[Pytron] Could not register .{} files", ext);
+        }
+    }
+
+    // Tell Explorer to refresh its icons and handlers (SHCNE_ASSOCCHANGED)
+    unsafe {
+        let shell32 = winapi::um::libloaderapi::LoadLibraryA(b"shell32.dll\0".as_ptr() as *const i8);
+        if !shell32.is_null() {
+            let addr = winapi::um::libloaderapi::GetProcAddress(shell32, b"SHChangeNotify\0".as_ptr() as *const i8);
+            if !addr.is_null() {
+                let notify: unsafe extern "system" fn(i32, u32, *const u8, *const u8) = std::mem::transmute(addr);
+                notify(0x0800_0000, 0, std::ptr::null(), std::ptr::null());
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn register_linux(assocs: &[FileAssociation], app_id: &str, exe: &Path) {
+    use std::process::Command;
+
+    let Some(data_home) = std::env::var_os("XDG_DATA_HOME")
+        .map(std::path::PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| Path::new(&h).join(".local/share")))
+    else {
+        return;
+    };
+    let slug = app_id.to_ascii_lowercase().replace('.', "-");
+    let mime_type = |ext: &str| format!("application/x-{}-{}", slug, ext);
+
+    let mut mime_xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<mime-info xmlns=\"http://www.freedesktop.org/standards/shared-mime-info\">\n");
+    for assoc in assocs {
+        let ext = assoc.extension();
+        mime_xml.push_str(&format!(
+            "  <mime-type type=\"{}\">\n    <comment>{}</comment>\n    <glob pattern=\"*.{}\"/>\n  </mime-type>\n",
+            mime_type(&ext), assoc.description(), ext
+        ));
+    }
+    mime_xml.push_str("</mime-info>\n");
+
+    let mime_types: String = assocs.iter().map(|a| format!("{};", mime_type(&a.extension()))).collect();
+    let desktop = format!(
+        "[Desktop Entry]\nType=Application\nName={}\nExec=\"{}\" %f\nMimeType={}\nNoDisplay=true\nTerminal=false\n",
+        app_id, exe.display(), mime_types
+    );
+
+    let mime_dir = data_home.join("mime");
+    let apps_dir = data_home.join("applications");
+    let mime_changed = write_if_changed(&mime_dir.join("packages").join(format!("{}.xml", slug)), &mime_xml);
+    let desktop_changed = write_if_changed(&apps_dir.join(format!("{}.desktop", slug)), &desktop);
+
+    // The databases only need a rebuild when something was (re)written
+    if mime_changed {
+        let _ = Command::new("update-mime-database").arg(&mime_dir).status();
+    }
+    if desktop_changed {
+        let _ = Command::new("update-desktop-database").arg(&apps_dir).status();
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn write_if_changed(path: &Path, content: &str) -> bool {
+    if std::fs::read_to_string(path).map(|c| c == content).unwrap_or(false) {
+        return false;
+    }
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    std::fs::write(path, content).is_ok()
+}
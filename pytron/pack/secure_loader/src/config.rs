@@ -1,26 +1,174 @@
 use serde::{Deserialize};
-use std::path::Path;
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::fs;
+use crate::security::AntiDebugLevel;
+use crate::patcher::Migration;
 
 #[derive(Deserialize, Debug)]
 pub struct Settings {
     pub title: Option<String>,
-    #[allow(dead_code)]
     pub version: Option<String>,
     pub author: Option<String>,
+    /// "off", "basic", "standard" (default) or "paranoid". Only embedded settings can go below
+    /// "standard"; a file on disk can only raise it.
+    #[serde(default)]
+    pub anti_debug_level: AntiDebugLevel,
+    /// Fixed limit for the timing check instead of the startup calibration. Embedded settings only.
+    pub anti_debug_timing_ms: Option<u64>,
+    /// Document types the app opens on double-click.
+    #[serde(default)]
+    pub file_associations: Vec<FileAssociation>,
+    /// Shows the loader's splash while Python warms up (.bmp, or a plain title card).
+    pub splash_image: Option<String>,
+    /// Imported in the background behind the splash. Defaults to `["pytron"]`.
+    pub warmup_modules: Option<Vec<String>>,
+    /// On a fatal error, open a console with the full traceback instead of the alert.
+    #[serde(default)]
+    pub show_console_on_error: bool,
+    /// File that receives the app's print() and traceback output. Relative paths are
+    /// resolved against the app's storage directory.
+    pub output_log: Option<String>,
+    /// Upgrade scripts keyed by the version that introduced them.
+    #[serde(default)]
+    pub migrations: Vec<Migration>,
+    /// `PYTHONOPTIMIZE` level: 0 keeps `assert` and `__debug__`, 1 (default) strips them,
+    /// 2 also drops docstrings.
+    pub python_optimize: Option<u8>,
+    /// Let Python cache compiled bytecode, speeding up later imports. Off by default; writes are
+    /// silently skipped where the install directory is read-only.
+    #[serde(default)]
+    pub write_bytecode: bool,
 }
 
-pub fn load_settings(root: &Path, embedded: Option<String>) -> Option<Settings> {
-    if let Some(json) = embedded {
-        if let Ok(s) = serde_json::from_str(&json) {
-            return Some(s);
+#[derive(Deserialize, Debug, Clone)]
+pub struct FileAssociation {
+    /// Extension with or without the leading dot, e.g. "myext".
+    pub ext: String,
+    /// Shown by the file manager, e.g. "My Document".
+    pub description: Option<String>,
+    /// Windows icon resource ("path.ico" or "app.exe,1"). Defaults to the exe's icon.
+    pub icon: Option<String>,
+}
+
+impl FileAssociation {
+    pub fn extension(&self) -> String {
+        self.ext.trim_start_matches('.').to_ascii_lowercase()
+    }
+
+    pub fn description(&self) -> String {
+        self.description.clone().unwrap_or_else(|| format!("{} file", self.extension().to_uppercase()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl SettingsFormat {
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "json" => Some(SettingsFormat::Json),
+            "toml" => Some(SettingsFormat::Toml),
+            "yaml" | "yml" => Some(SettingsFormat::Yaml),
+            _ => None,
         }
     }
-    // Fallback to disk (legacy/dev support)
-    let settings_path = root.join("settings.json");
-    if !settings_path.exists() {
+}
+
+// Search order on disk. JSON wins when several files are present.
+const SETTINGS_FILES: &[(&str, SettingsFormat)] = &[
+    ("settings.json", SettingsFormat::Json),
+    ("settings.toml", SettingsFormat::Toml),
+    ("settings.yaml", SettingsFormat::Yaml),
+    ("settings.yml", SettingsFormat::Yaml),
+];
+
+#[derive(Debug)]
+pub enum SettingsError {
+    /// No settings file next to the payload (dev layouts, bare builds).
+    Missing,
+    /// The file exists but could not be read.
+    Io(PathBuf, std::io::Error),
+    /// The file exists but does not match the `Settings` schema.
+    Parse { path: PathBuf, line: usize, column: usize, message: String },
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Missing => write!(f, "settings file not found"),
+            SettingsError::Io(path, e) => write!(f, "Could not read {}: {}", path.display(), e),
+            SettingsError::Parse { path, line, column, message } => write!(
+                f,
+                "Invalid settings in {} (line {}, column {}):\n{}",
+                path.display(), line, column, message
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+// Baked in at build time by build.rs (PYTRON_EMBED_SETTINGS); empty when nothing was embedded
+const EMBEDDED_SETTINGS: &str = include_str!(concat!(env!("OUT_DIR"), "/embedded_settings"));
+
+/// Settings compiled into the loader, if the build embedded any.
+pub fn embedded_settings() -> Option<(String, SettingsFormat)> {
+    if EMBEDDED_SETTINGS.trim().is_empty() {
         return None;
     }
-    let content = fs::read_to_string(settings_path).ok()?;
-    serde_json::from_str(&content).ok()
+    let format = option_env!("PYTRON_EMBEDDED_SETTINGS_FORMAT")
+        .and_then(SettingsFormat::from_extension)
+        .unwrap_or(SettingsFormat::Json);
+    Some((EMBEDDED_SETTINGS.to_string(), format))
+}
+
+pub fn load_settings(root: &Path, embedded: Option<(String, SettingsFormat)>) -> Result<Settings, SettingsError> {
+    // Embedded settings are authoritative, so an edited file on disk can't override them
+    if let Some((content, format)) = embedded {
+        return parse_settings(&content, format, Path::new("<embedded>"));
+    }
+    // Fallback to disk (legacy/dev support)
+    let (settings_path, format) = SETTINGS_FILES
+        .iter()
+        .map(|(name, format)| (root.join(name), *format))
+        .find(|(path, _)| path.exists())
+        .ok_or(SettingsError::Missing)?;
+
+    let content = fs::read_to_string(&settings_path).map_err(|e| SettingsError::Io(settings_path.clone(), e))?;
+    parse_settings(&content, format, &settings_path)
+}
+
+fn parse_settings(content: &str, format: SettingsFormat, path: &Path) -> Result<Settings, SettingsError> {
+    let parse_error = |line: usize, column: usize, message: String| SettingsError::Parse {
+        path: path.to_path_buf(),
+        line,
+        column,
+        message,
+    };
+
+    match format {
+        SettingsFormat::Json => serde_json::from_str(content)
+            .map_err(|e| parse_error(e.line(), e.column(), e.to_string())),
+        SettingsFormat::Toml => toml::from_str(content).map_err(|e| {
+            let (line, column) = e.span().map(|s| line_col(content, s.start)).unwrap_or((0, 0));
+            parse_error(line, column, e.message().to_string())
+        }),
+        SettingsFormat::Yaml => serde_yaml::from_str(content).map_err(|e| {
+            let (line, column) = e.location().map(|l| (l.line(), l.column())).unwrap_or((0, 0));
+            parse_error(line, column, e.to_string())
+        }),
+    }
+}
+
+// 1-based line/column for a byte offset, matching serde_json's reporting
+fn line_col(content: &str, offset: usize) -> (usize, usize) {
+    let before = content.get(..offset).unwrap_or(content);
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map(|l| l.chars().count()).unwrap_or(0) + 1;
+    (line, column)
 }
@@ -5,36 +5,59 @@ mod config;
 mod patcher;
 mod ui;
 mod python_runtime;
+mod associations;
+mod splash;
+mod restart;
 
 use pyo3::prelude::*;
 use std::env;
-use crate::security::check_debugger;
-use crate::config::load_settings;
-use crate::patcher::check_and_apply_patches;
-use crate::ui::{alert, init_com, set_app_id};
-use crate::python_runtime::{find_internal_dir, run_python_and_payload};
+use crate::security::{check_debugger, AntiDebugLevel};
+use crate::config::{embedded_settings, load_settings, SettingsError};
+use crate::patcher::{app_storage_dir, check_and_apply_patches, MigrationPlan};
+use crate::ui::{alert, alloc_console, init_com, set_app_id};
+use crate::python_runtime::{find_internal_dir, run_python_and_payload, OutputRedirect, StartupTimings, Warmup};
+use crate::splash::Splash;
+use crate::associations::{opened_files, register_file_associations};
 
 fn main() -> PyResult<()> {
+    let mut timings = StartupTimings::start();
+
     // 1. CLI Argument Parsing and Console Allocation
     let args: Vec<String> = env::args().collect();
     let debug_mode = args.iter().any(|arg| arg == "--debug");
 
     if debug_mode {
-        #[cfg(windows)]
-        unsafe {
-            if let Ok(func) = libloading::Library::new("kernel32.dll") {
-                 let alloc: libloading::Symbol<unsafe extern "system" fn() -> i32> = func.get(b"AllocConsole").unwrap();
-                 alloc();
-            }
-        }
+        alloc_console();
     }
 
-    // 2. Anti-Debugging & COM Init
-    check_debugger();
+    // 2. COM Init, Settings & Anti-Debugging
     init_com();
 
     let (root_dir, internal_dir) = find_internal_dir();
-    
+
+    // Settings baked into the binary win; otherwise settings.json (which is now in _internal)
+    // The load_settings helper might need root_dir, but we point to internal_dir for search
+    let embedded = embedded_settings();
+    let trusted = embedded.is_some();
+    let settings = match load_settings(&internal_dir, embedded) {
+        Ok(s) => Some(s),
+        Err(SettingsError::Missing) => None,
+        Err(e) => {
+            // A broken settings file must not silently fall back to defaults
+            alert("Shield: Configuration Error", &e.to_string());
+            None
+        }
+    };
+    // settings.json on disk is unsigned: it may tighten the shield but never loosen it
+    let (level, timing_ms) = match &settings {
+        Some(s) if trusted => (s.anti_debug_level, s.anti_debug_timing_ms),
+        Some(s) => (s.anti_debug_level.max(AntiDebugLevel::default()), None),
+        None => (AntiDebugLevel::default(), None),
+    };
+    check_debugger(level, timing_ms);
+
+    timings.mark("settings_loaded");
+
     check_and_apply_patches(&root_dir);
 
     // Verify critical files (Compiled Payload)
@@ -53,9 +76,6 @@ fn main() -> PyResult<()> {
         }
     }
     
-    // Load config from settings.json (which is now in _internal)
-    // The load_settings helper might need root_dir, but we point to internal_dir for search
-    let settings = load_settings(&internal_dir, None);
     let app_title = settings.as_ref().and_then(|s| s.title.clone()).unwrap_or_else(|| "Pytron App".to_string());
     
     // Set App ID for Task Manager grouping
@@ -64,6 +84,13 @@ fn main() -> PyResult<()> {
         app_title.replace(" ", "")
     );
     set_app_id(&app_id);
+
+    // File associations: make sure the OS knows about them, then hand any opened documents to Python
+    let associations = settings.as_ref().map(|s| s.file_associations.clone()).unwrap_or_default();
+    if let Ok(exe) = env::current_exe() {
+        register_file_associations(&associations, &app_id, &exe);
+    }
+    let open_files = opened_files(&args, &associations);
     
     let app_bundle = internal_dir.join("app.bundle");
 
@@ -97,16 +124,64 @@ fn main() -> PyResult<()> {
     
     env::set_var("PYTHONPATH", &python_path);
     env::set_var("PYTHONNOUSERSITE", "1");
-    // Speed Optimizations
-    env::set_var("PYTHONOPTIMIZE", "1");
-    env::set_var("PYTHONDONTWRITEBYTECODE", "1");
+    // Speed Optimizations (tunable, since some apps rely on asserts or want a bytecode cache)
+    let optimize = settings.as_ref().and_then(|s| s.python_optimize).unwrap_or(1).min(2);
+    if optimize > 0 {
+        env::set_var("PYTHONOPTIMIZE", optimize.to_string());
+    } else {
+        env::remove_var("PYTHONOPTIMIZE");
+    }
+    if settings.as_ref().is_some_and(|s| s.write_bytecode) {
+        env::remove_var("PYTHONDONTWRITEBYTECODE");
+    } else {
+        env::set_var("PYTHONDONTWRITEBYTECODE", "1");
+    }
     // Unicode Stability
     env::set_var("PYTHONUTF8", "1");
+    timings.mark("environment_ready");
+
+    // Upgrade scripts run inside the interpreter, right before the app is imported
+    let migrations = settings.as_ref().and_then(|s| {
+        let version = s.version.as_deref()?;
+        Some(MigrationPlan::load(&s.migrations, version, &app_storage_dir(&app_title)))
+    });
+
+    // Splash + background warmup only when the app asked for a splash
+    let warmup = match settings.as_ref().and_then(|s| s.splash_image.as_ref()) {
+        Some(image) => {
+            let image = [internal_dir.join(image), root_dir.join(image)].into_iter().find(|p| p.exists());
+            Warmup {
+                modules: settings.as_ref().and_then(|s| s.warmup_modules.clone()).unwrap_or_else(|| vec!["pytron".to_string()]),
+                splash: Splash::show(&app_title, image.as_deref()),
+            }
+        }
+        None => Warmup { modules: Vec::new(), splash: None },
+    };
+
+    // print() output: to the configured log file, and to the devtools console with --debug
+    let output = OutputRedirect {
+        log: settings.as_ref().and_then(|s| s.output_log.as_ref()).map(|p| app_storage_dir(&app_title).join(p)),
+        mirror: debug_mode,
+    };
 
     // Run execution
-    let res = run_python_and_payload(&root_dir, &internal_dir, if app_bundle.exists() { Some(&app_bundle) } else { None });
+    // `sys.pytron_app_info`: the page's window.pytron.info, and the native window's initial title
+    let app_info = vec![
+        ("name", Some(app_title.clone())),
+        ("version", settings.as_ref().and_then(|s| s.version.clone())),
+        ("author", settings.as_ref().and_then(|s| s.author.clone())),
+    ];
+    let res = run_python_and_payload(&root_dir, &internal_dir, if app_bundle.exists() { Some(&app_bundle) } else { None }, &open_files, &app_info, output, migrations, warmup, timings);
     if let Err(e) = res {
-        alert(&app_title, &format!("Fatal Engine Error:\n{}", e));
+        // The alert truncates long tracebacks; a console lets users copy the whole thing
+        if cfg!(windows) && !debug_mode && settings.as_ref().is_some_and(|s| s.show_console_on_error) {
+            alloc_console();
+            eprintln!("{} crashed:\n\n{}", app_title, e);
+            eprintln!("\nPress Enter to close this window.");
+            let _ = std::io::stdin().read_line(&mut String::new());
+        } else {
+            alert(&app_title, &format!("Fatal Engine Error:\n{}", e));
+        }
     }
     Ok(())
 }
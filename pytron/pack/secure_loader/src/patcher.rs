@@ -1,6 +1,7 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::fs;
 use std::io::Cursor;
+use serde::Deserialize;
 
 pub fn check_and_apply_patches(root: &Path) {
     let payload_path = root.join("app.pytron");
@@ -35,3 +36,157 @@ pub fn check_and_apply_patches(root: &Path) {
         }
     }
 }
+
+/// One app-upgrade step from `Settings.migrations`: `script` (a Python file, relative to
+/// `_internal`) runs once when the app is upgraded to `version` or past it.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Migration {
+    pub version: String,
+    pub script: String,
+}
+
+/// Migrations still to run, oldest first, and where to record progress.
+pub struct MigrationPlan {
+    pub steps: Vec<Migration>,
+    current: String,
+    state_path: PathBuf,
+}
+
+const MIGRATION_STATE_FILE: &str = ".pytron_migrations.json";
+
+impl MigrationPlan {
+    /// Compares `current` against the last version recorded in `storage_dir`.
+    /// A fresh install (no storage dir yet) has nothing to migrate, so it only records `current`;
+    /// an existing install without a record predates migrations and gets every step up to `current`.
+    pub fn load(migrations: &[Migration], current: &str, storage_dir: &Path) -> Self {
+        let state_path = storage_dir.join(MIGRATION_STATE_FILE);
+        let last_run = fs::read_to_string(&state_path)
+            .ok()
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+            .and_then(|v| v["version"].as_str().map(parse_version));
+
+        let plan = MigrationPlan { steps: Vec::new(), current: current.to_string(), state_path };
+        let last_run = match last_run {
+            Some(v) => v,
+            None if !storage_dir.exists() => {
+                plan.finish();
+                return plan;
+            }
+            // Sorts below every version, so all steps up to `current` run
+            None => Vec::new(),
+        };
+        let current = parse_version(current);
+        let mut steps: Vec<Migration> = migrations
+            .iter()
+            .filter(|m| {
+                let v = parse_version(&m.version);
+                v > last_run && v <= current
+            })
+            .cloned()
+            .collect();
+        steps.sort_by_key(|m| parse_version(&m.version));
+        MigrationPlan { steps, ..plan }
+    }
+
+    /// Marks everything up to `version` as done, so a failed step is retried next launch.
+    pub fn record(&self, version: &str) {
+        if let Some(parent) = self.state_path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = fs::write(&self.state_path, serde_json::json!({ "version": version }).to_string());
+    }
+
+    /// Call once every step succeeded.
+    pub fn finish(&self) {
+        self.record(&self.current);
+    }
+}
+
+/// "1.2.10" -> [1, 2, 10]; anything non-numeric in a part counts as 0.
+fn parse_version(v: &str) -> Vec<u64> {
+    let mut parts: Vec<u64> = v
+        .trim_start_matches('v')
+        .split('.')
+        .map(|p| p.chars().take_while(|c| c.is_ascii_digit()).collect::<String>().parse().unwrap_or(0))
+        .collect();
+    // "1.2" and "1.2.0" are the same version
+    while parts.len() > 1 && parts.last() == Some(&0) {
+        parts.pop();
+    }
+    parts
+}
+
+/// Per-user data directory the Python side also uses (`App.storage_path`).
+pub fn app_storage_dir(title: &str) -> PathBuf {
+    let safe_title: String = title.chars().map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect();
+    let safe_title = safe_title.trim_matches('_');
+    let home = std::env::var_os(if cfg!(windows) { "USERPROFILE" } else { "HOME" }).map(PathBuf::from).unwrap_or_default();
+    let base = if cfg!(windows) {
+        std::env::var_os("LOCALAPPDATA").map(PathBuf::from).unwrap_or(home)
+    } else {
+        home.join(".config")
+    };
+    base.join(safe_title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migration(version: &str) -> Migration {
+        Migration { version: version.to_string(), script: format!("migrate_{}.py", version) }
+    }
+
+    fn versions(plan: &MigrationPlan) -> Vec<&str> {
+        plan.steps.iter().map(|m| m.version.as_str()).collect()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pytron-migrations-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn parses_versions() {
+        assert_eq!(parse_version("1.2.10"), vec![1, 2, 10]);
+        assert_eq!(parse_version("v1.2"), parse_version("1.2.0"));
+        assert_eq!(parse_version("2.0.0-beta1"), vec![2]);
+        assert!(parse_version("1.10") > parse_version("1.9"));
+        assert_eq!(parse_version("x"), vec![0]);
+    }
+
+    #[test]
+    fn fresh_install_only_records_the_baseline() {
+        let dir = temp_dir("fresh");
+        let plan = MigrationPlan::load(&[migration("1.0")], "1.0", &dir);
+        assert!(plan.steps.is_empty());
+        assert!(dir.join(MIGRATION_STATE_FILE).exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn existing_install_without_a_record_runs_everything_up_to_current() {
+        let dir = temp_dir("legacy");
+        fs::create_dir_all(&dir).unwrap();
+        let migrations = [migration("1.1"), migration("0.9"), migration("2.0")];
+        let plan = MigrationPlan::load(&migrations, "1.1", &dir);
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(versions(&plan), ["0.9", "1.1"]);
+    }
+
+    #[test]
+    fn runs_only_steps_after_the_recorded_version() {
+        let dir = temp_dir("upgrade");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(MIGRATION_STATE_FILE), r#"{"version": "1.0"}"#).unwrap();
+        let migrations = [migration("1.0"), migration("1.2"), migration("1.1"), migration("1.3")];
+        let plan = MigrationPlan::load(&migrations, "1.2", &dir);
+        assert_eq!(versions(&plan), ["1.1", "1.2"]);
+
+        plan.finish();
+        let again = MigrationPlan::load(&migrations, "1.2", &dir);
+        let _ = fs::remove_dir_all(&dir);
+        assert!(again.steps.is_empty());
+    }
+}
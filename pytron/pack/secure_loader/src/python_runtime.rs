@@ -1,7 +1,31 @@
 use pyo3::prelude::*;
-use pyo3::types::PyList;
+use pyo3::types::{PyDict, PyList};
 use std::env;
 use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use crate::splash::Splash;
+use crate::patcher::MigrationPlan;
+use crate::restart::pytron_loader;
+
+/// Loader-side startup phases, handed to Python as `sys.pytron_timings` (ms since launch).
+/// The native engine adds window_created, webview_created and first_paint to the same dict.
+pub struct StartupTimings {
+    start_epoch: f64,
+    started: Instant,
+    phases: Vec<(&'static str, f64)>,
+}
+
+impl StartupTimings {
+    pub fn start() -> Self {
+        let start_epoch = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or_default();
+        StartupTimings { start_epoch, started: Instant::now(), phases: Vec::new() }
+    }
+
+    pub fn mark(&mut self, phase: &'static str) {
+        self.phases.push((phase, self.started.elapsed().as_secs_f64() * 1000.0));
+    }
+}
 
 pub fn find_internal_dir() -> (PathBuf, PathBuf) {
     let exe_path = env::current_exe().unwrap_or_else(|_| PathBuf::from("app.exe"));
@@ -15,8 +39,86 @@ pub fn find_internal_dir() -> (PathBuf, PathBuf) {
     }
 }
 
-pub fn run_python_and_payload(root_dir: &Path, internal_dir: &Path, _base_zip: Option<&Path>) -> PyResult<()> {
+/// Modules imported on a background thread while the splash is up, so the `app` import
+/// that follows mostly hits `sys.modules`.
+pub struct Warmup {
+    pub modules: Vec<String>,
+    pub splash: Option<Splash>,
+}
+
+/// Where the payload's `sys.stdout`/`sys.stderr` go. Without a console both are None and
+/// everything printed is lost.
+pub struct OutputRedirect {
+    /// Appended to on every write; created if missing.
+    pub log: Option<PathBuf>,
+    /// Lines are also handed to `sys.pytron_output_listeners`, which the webview forwards
+    /// to its devtools console.
+    pub mirror: bool,
+}
+
+// Tees writes to the original stream (if any), the log file and the listeners (per line)
+const OUTPUT_REDIRECT: &str = r#"
+import io, sys
+
+class PytronOutput(io.TextIOBase):
+    def __init__(self, name, original, log):
+        self.name = name
+        self.original = original
+        self.log = log
+        self.pending = ""
+
+    def writable(self):
+        return True
+
+    def write(self, s):
+        for target in (self.original, self.log):
+            if target is not None:
+                try:
+                    target.write(s)
+                    target.flush()
+                except Exception:
+                    pass
+        if mirror:
+            *lines, self.pending = (self.pending + s).split("\n")
+            for line in lines:
+                for listener in list(sys.pytron_output_listeners):
+                    try:
+                        listener(self.name, line)
+                    except Exception:
+                        pass
+        return len(s)
+
+log = open(log_path, "a", encoding="utf-8", buffering=1) if log_path else None
+sys.pytron_output_listeners = []
+sys.stdout = PytronOutput("stdout", sys.stdout, log)
+sys.stderr = PytronOutput("stderr", sys.stderr, log)
+"#;
+
+fn redirect_output(py: Python<'_>, output: &OutputRedirect) -> PyResult<()> {
+    if output.log.is_none() && !output.mirror {
+        return Ok(());
+    }
+    if let Some(dir) = output.log.as_deref().and_then(Path::parent) {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    let globals = PyDict::new_bound(py);
+    globals.set_item("log_path", output.log.as_ref().map(|p| p.to_string_lossy().into_owned()))?;
+    globals.set_item("mirror", output.mirror)?;
+    py.run_bound(OUTPUT_REDIRECT, Some(&globals), None)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn run_python_and_payload(root_dir: &Path, internal_dir: &Path, _base_zip: Option<&Path>, open_files: &[String], app_info: &[(&str, Option<String>)], output: OutputRedirect, migrations: Option<MigrationPlan>, warmup: Warmup, mut timings: StartupTimings) -> PyResult<()> {
+    // Must be registered before the interpreter starts
+    pyo3::append_to_inittab!(pytron_loader);
+
+    if let Some(splash) = &warmup.splash {
+        splash.set_status("Starting Python...");
+    }
+    // The interpreter is initialized here, on the main thread, so Python's main thread
+    // (signals, threading.main_thread()) is the OS one the app's event loop will run on.
     pyo3::prepare_freethreaded_python();
+    timings.mark("interpreter_init");
 
     let exe_path = env::current_exe().map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("EXE check failed: {}", e)))?;
     
@@ -62,23 +164,116 @@ pub fn run_python_and_payload(root_dir: &Path, internal_dir: &Path, _base_zip: O
         let args: Vec<String> = env::args().collect();
         let py_args = PyList::new_bound(py, &args);
         sys.setattr("argv", py_args)?;
+        // Documents the OS launched us with (file associations), already filtered from argv
+        sys.setattr("pytron_open_files", PyList::new_bound(py, open_files))?;
+        // Name/version/author from settings, for the page's window.pytron.info
+        let py_info = PyDict::new_bound(py);
+        for (key, value) in app_info {
+            py_info.set_item(*key, value)?;
+        }
+        sys.setattr("pytron_app_info", py_info)?;
+
+        // Before the warmup, so import-time prints and warnings are captured too
+        if let Err(e) = redirect_output(py, &output) {
+            eprintln!("[Pytron] Could not redirect output: {}", e);
+        }
+        Ok::<_, PyErr>(())
+    })?;
+
+    // prepare_freethreaded_python released the GIL, so the warmup worker can take it
+    // Kept up through the app's own import; `pytron_loader.close_splash()` takes it down once
+    // the first window shows, and it's dropped here if the app never gets that far
+    let splash = warm_up(warmup);
+    timings.mark("warmup_done");
+
+    Python::with_gil(|py| {
+        let sys = py.import_bound("sys")?;
+
+        if let Some(plan) = &migrations {
+            if let Some(splash) = splash.as_ref().filter(|_| !plan.steps.is_empty()) {
+                splash.set_status("Applying updates...");
+            }
+            run_migrations(py, plan, internal_dir)?;
+        }
+
+        // Everything until the first window shows up is the app's own import cost
+        timings.mark("app_import_start");
+        let py_timings = PyDict::new_bound(py);
+        for (phase, ms) in &timings.phases {
+            py_timings.set_item(*phase, *ms)?;
+        }
+        sys.setattr("pytron_start_time", timings.start_epoch)?;
+        sys.setattr("pytron_timings", py_timings)?;
+
+        if let Some(splash) = &splash {
+            splash.set_status("Starting app...");
+        }
 
         // Load the compiled binary module 'app'
         // Cythonized modules execute their patched 'if True:' block upon import
         match py.import_bound("app") {
             Ok(_) => Ok(()),
-            Err(e) => {
-                let tb = py.import_bound("traceback")?;
-                let tb_list = tb.call_method1("format_exception", (e.clone_ref(py),))?;
-                
-                // Use "".join() to convert the list of lines into one string
-                let empty_str = pyo3::types::PyString::new_bound(py, "");
-                let formatted_tb: String = empty_str.call_method1("join", (tb_list,))?.extract()?;
-                
-                Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                    format!("Shield Error: Failed to start native logic\n\n{}", formatted_tb)
-                ))
-            }
+            Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                format!("Shield Error: Failed to start native logic\n\n{}", format_traceback(py, &e)?)
+            )),
         }
     })
 }
+
+fn format_traceback(py: Python<'_>, e: &PyErr) -> PyResult<String> {
+    let tb = py.import_bound("traceback")?;
+    let tb_list = tb.call_method1("format_exception", (e.clone_ref(py),))?;
+
+    // Use "".join() to convert the list of lines into one string
+    let empty_str = pyo3::types::PyString::new_bound(py, "");
+    empty_str.call_method1("join", (tb_list,))?.extract()
+}
+
+/// Runs pending upgrade scripts in order. Progress is saved after each one, so a failing
+/// script stops the launch and is retried next time instead of being skipped.
+fn run_migrations(py: Python<'_>, plan: &MigrationPlan, internal_dir: &Path) -> PyResult<()> {
+    let runpy = py.import_bound("runpy")?;
+    for step in &plan.steps {
+        let script = internal_dir.join(&step.script);
+        if let Err(e) = runpy.call_method1("run_path", (script.to_string_lossy(), py.None(), "__migration__")) {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Upgrade to {} failed ({}):\n\n{}", step.version, step.script, format_traceback(py, &e)?
+            )));
+        }
+        plan.record(&step.version);
+    }
+    plan.finish();
+    Ok(())
+}
+
+fn warm_up(warmup: Warmup) -> Option<Splash> {
+    let Warmup { modules, splash } = warmup;
+    if modules.is_empty() {
+        return splash;
+    }
+    if let Some(splash) = &splash {
+        splash.set_status("Loading modules...");
+    }
+    let total = modules.len();
+    let (tx, rx) = mpsc::channel();
+    let worker = std::thread::spawn(move || {
+        for (i, name) in modules.iter().enumerate() {
+            Python::with_gil(|py| {
+                // Not fatal: the real import below raises it again with a proper traceback
+                if let Err(e) = py.import_bound(name.as_str()) {
+                    eprintln!("[Pytron] Warmup import of '{}' failed: {}", name, e);
+                }
+            });
+            let _ = tx.send(i + 1);
+        }
+    });
+
+    // The splash paints on its own thread; this only forwards progress
+    for done in rx {
+        if let Some(splash) = &splash {
+            splash.set_progress(done as f32 / total as f32);
+        }
+    }
+    let _ = worker.join();
+    splash
+}
@@ -0,0 +1,31 @@
+use pyo3::prelude::*;
+use std::process::Command;
+
+/// Set on a relaunched instance to the old pid, so the single-instance guard waits for the
+/// old process to exit instead of handing its launch over and quitting.
+pub const RESTART_ENV: &str = "PYTRON_RESTARTED_FROM";
+
+/// Starts a fresh copy of this exe with the same arguments. The caller is expected to exit.
+pub fn spawn_self() -> std::io::Result<u32> {
+    let exe = std::env::current_exe()?;
+    let child = Command::new(exe)
+        .args(std::env::args_os().skip(1))
+        .env(RESTART_ENV, std::process::id().to_string())
+        .spawn()?;
+    Ok(child.id())
+}
+
+/// Relaunches the app and returns the new instance's pid. Quit right after calling it.
+#[pyfunction]
+fn restart() -> PyResult<u32> {
+    spawn_self().map_err(|e| PyErr::new::<pyo3::exceptions::PyOSError, _>(format!("Could not restart: {}", e)))
+}
+
+/// Built-in `pytron_loader` module, importable only inside packaged apps.
+#[pymodule]
+pub fn pytron_loader(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(restart, m)?)?;
+    m.add_function(wrap_pyfunction!(crate::splash::set_splash_status, m)?)?;
+    m.add_function(wrap_pyfunction!(crate::splash::close_splash, m)?)?;
+    Ok(())
+}
@@ -14,7 +14,62 @@ extern crate winapi;
 #[cfg(windows)]
 use crate::ui::alert;
 
-pub fn check_debugger() {
+use serde::Deserialize;
+use std::time::Duration;
+
+/// How much of the shield runs at startup (`anti_debug_level` in settings).
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AntiDebugLevel {
+    /// No checks at all.
+    Off,
+    /// Debugger API checks only.
+    Basic,
+    /// API checks plus the calibrated timing check.
+    #[default]
+    Standard,
+    /// Everything above plus VM, sandbox and API hook detection. Expect false positives.
+    Paranoid,
+}
+
+/// Floor for the calibrated timing limit, so a very fast baseline doesn't make it hair-trigger.
+const MIN_TIMING_LIMIT: Duration = Duration::from_millis(50);
+/// A sample must be this many times slower than the baseline to count as an anomaly.
+const TIMING_FACTOR: u32 = 20;
+
+fn timed_loop() -> Duration {
+    let start = std::time::Instant::now();
+    let mut x = 0u64;
+    for i in 0..10_000 {
+        x = std::hint::black_box(x + i);
+    }
+    start.elapsed()
+}
+
+/// Fastest of a few runs, so one preempted run doesn't skew the baseline.
+fn timing_baseline() -> Duration {
+    (0..5).map(|_| timed_loop()).min().unwrap_or_default()
+}
+
+/// `threshold_ms` overrides the calibrated limit.
+#[allow(dead_code)]
+fn timing_anomaly(baseline: Duration, threshold_ms: Option<u64>) -> bool {
+    let limit = match threshold_ms {
+        Some(ms) => Duration::from_millis(ms),
+        None => (baseline * TIMING_FACTOR).max(MIN_TIMING_LIMIT),
+    };
+    timed_loop() > limit
+}
+
+#[allow(unused_variables)]
+pub fn check_debugger(level: AntiDebugLevel, timing_threshold_ms: Option<u64>) {
+    // Debug builds and `--no-default-features` builds leave the loader debuggable
+    if !cfg!(all(feature = "security", not(debug_assertions))) || level == AntiDebugLevel::Off {
+        return;
+    }
+    // Calibrate before the API calls below so the sample covers the same conditions
+    let baseline = timing_baseline();
+
     #[cfg(windows)]
     unsafe {
         // 1. Standard Check
@@ -33,19 +88,137 @@ pub fn check_debugger() {
             alert(obfstr!("Security Alert"), obfstr!("Unauthorized debugger detected (D2)."));
             std::process::exit(0xDEAB);
         }
-        
+
         // 3. Timing check (debuggers slow down execution)
-        let start = std::time::Instant::now();
-        let mut x = 0;
-        for i in 0..10_000 { 
-            x = std::hint::black_box(x + i); 
+        if level >= AntiDebugLevel::Standard && timing_anomaly(baseline, timing_threshold_ms) {
+            alert(obfstr!("Security Alert"), obfstr!("Timing anomaly detected. Binary compromised."));
+            std::process::exit(0xDEAC);
         }
-        // If it takes more than 5ms for a simple loop, something is wrong
-        if start.elapsed().as_millis() > 5 {
-             alert(obfstr!("Security Alert"), obfstr!("Timing anomaly detected. Binary compromised."));
-             std::process::exit(0xDEAC);
+
+        if level >= AntiDebugLevel::Paranoid {
+            // 4. Virtual machine artifacts
+            if vm_detected() {
+                alert(obfstr!("Security Alert"), obfstr!("Unsupported execution environment (V1)."));
+                std::process::exit(0xDEAE);
+            }
+
+            // 5. Sandbox DLLs injected into the process
+            if sandbox_detected() {
+                alert(obfstr!("Security Alert"), obfstr!("Unsupported execution environment (S1)."));
+                std::process::exit(0xDEAF);
+            }
+
+            // 6. Inline hooks on ntdll
+            if ntdll_hooked() {
+                alert(obfstr!("Security Alert"), obfstr!("Process integrity check failed (H1)."));
+                std::process::exit(0xDEB0);
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+fn c_str(s: &str) -> std::ffi::CString {
+    std::ffi::CString::new(s).unwrap_or_default()
+}
+
+#[cfg(windows)]
+unsafe fn vm_detected() -> bool {
+    use winapi::um::winreg::{RegCloseKey, RegOpenKeyExA, HKEY_LOCAL_MACHINE};
+    use winapi::um::winnt::KEY_READ;
+
+    let keys = [
+        c_str(obfstr!("SOFTWARE\\VMware, Inc.\\VMware Tools")),
+        c_str(obfstr!("SOFTWARE\\Oracle\\VirtualBox Guest Additions")),
+        c_str(obfstr!("SYSTEM\\CurrentControlSet\\Services\\VBoxGuest")),
+        c_str(obfstr!("SYSTEM\\CurrentControlSet\\Services\\vmci")),
+    ];
+    for key in &keys {
+        let mut handle = std::ptr::null_mut();
+        if RegOpenKeyExA(HKEY_LOCAL_MACHINE, key.as_ptr(), 0, KEY_READ, &mut handle) == 0 {
+            RegCloseKey(handle);
+            return true;
         }
     }
+
+    // VMware, VirtualBox and QEMU default MAC prefixes
+    const VM_OUIS: &[[u8; 3]] = &[
+        [0x00, 0x05, 0x69],
+        [0x00, 0x0C, 0x29],
+        [0x00, 0x1C, 0x14],
+        [0x00, 0x50, 0x56],
+        [0x08, 0x00, 0x27],
+        [0x52, 0x54, 0x00],
+    ];
+    mac_addresses().iter().any(|mac| VM_OUIS.iter().any(|oui| mac.starts_with(oui)))
+}
+
+#[cfg(windows)]
+unsafe fn mac_addresses() -> Vec<Vec<u8>> {
+    use winapi::shared::winerror::{ERROR_BUFFER_OVERFLOW, ERROR_SUCCESS};
+    use winapi::um::iphlpapi::GetAdaptersInfo;
+    use winapi::um::iptypes::IP_ADAPTER_INFO;
+
+    let mut size = 0u32;
+    if GetAdaptersInfo(std::ptr::null_mut(), &mut size) != ERROR_BUFFER_OVERFLOW {
+        return Vec::new();
+    }
+    // u64 storage keeps the buffer aligned for IP_ADAPTER_INFO
+    let mut buf = vec![0u64; (size as usize).div_ceil(8)];
+    let first = buf.as_mut_ptr() as *mut IP_ADAPTER_INFO;
+    if GetAdaptersInfo(first, &mut size) != ERROR_SUCCESS {
+        return Vec::new();
+    }
+    let mut macs = Vec::new();
+    let mut adapter = first;
+    while !adapter.is_null() {
+        let info = &*adapter;
+        let len = (info.AddressLength as usize).min(info.Address.len());
+        macs.push(info.Address[..len].to_vec());
+        adapter = info.Next;
+    }
+    macs
+}
+
+#[cfg(windows)]
+unsafe fn sandbox_detected() -> bool {
+    use winapi::um::libloaderapi::GetModuleHandleA;
+
+    // Sandboxie, Comodo, Avast/AVG, Cuckoo-style monitors
+    let modules = [
+        c_str(obfstr!("SbieDll.dll")),
+        c_str(obfstr!("cmdvrt32.dll")),
+        c_str(obfstr!("cmdvrt64.dll")),
+        c_str(obfstr!("snxhk.dll")),
+        c_str(obfstr!("cuckoomon.dll")),
+    ];
+    modules.iter().any(|m| !GetModuleHandleA(m.as_ptr()).is_null())
+}
+
+#[cfg(windows)]
+unsafe fn ntdll_hooked() -> bool {
+    use winapi::um::libloaderapi::{GetModuleHandleA, GetProcAddress};
+
+    let ntdll = GetModuleHandleA(c_str(obfstr!("ntdll.dll")).as_ptr());
+    if ntdll.is_null() {
+        return false;
+    }
+    let exports = [
+        c_str(obfstr!("NtQueryInformationProcess")),
+        c_str(obfstr!("NtSetInformationThread")),
+        c_str(obfstr!("NtCreateFile")),
+        c_str(obfstr!("NtReadVirtualMemory")),
+        c_str(obfstr!("LdrLoadDll")),
+    ];
+    exports.iter().any(|name| {
+        let addr = GetProcAddress(ntdll, name.as_ptr()) as *const u8;
+        if addr.is_null() {
+            return false;
+        }
+        let head = std::slice::from_raw_parts(addr, 2);
+        // jmp rel32 / jmp [rip+x] / push imm32 (push-ret trampoline)
+        head[0] == 0xE9 || head == [0xFF, 0x25] || head[0] == 0x68
+    })
 }
 
 // Footer format removed - switching to Cython compilation
@@ -0,0 +1,251 @@
+use pyo3::prelude::*;
+use std::path::Path;
+
+#[cfg(windows)]
+extern crate winapi;
+
+/// Borderless startup window with a progress bar and a status line, shown while Python warms
+/// up and the app imports. Runs its own thread and message loop, so it keeps painting while
+/// the main thread is blocked in Python. Windows only for now, a no-op elsewhere.
+pub struct Splash {
+    #[cfg(windows)]
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+#[cfg(windows)]
+mod win {
+    use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use winapi::shared::minwindef::{LPARAM, LRESULT, UINT, WPARAM};
+    use winapi::shared::windef::{HBITMAP, HWND, RECT};
+    use winapi::um::wingdi::*;
+    use winapi::um::winuser::*;
+
+    pub static PROGRESS: AtomicU32 = AtomicU32::new(0); // Per mille
+    pub static SPLASH_BITMAP: AtomicUsize = AtomicUsize::new(0);
+    pub static LABEL: Mutex<Vec<u16>> = Mutex::new(Vec::new());
+    pub static STATUS: Mutex<Vec<u16>> = Mutex::new(Vec::new());
+    pub static WINDOW: AtomicUsize = AtomicUsize::new(0); // HWND while the splash is up
+
+    const BAR_HEIGHT: i32 = 4;
+
+    pub unsafe extern "system" fn wndproc(hwnd: HWND, msg: UINT, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        if msg == WM_DESTROY {
+            WINDOW.store(0, Ordering::SeqCst);
+            // Ends the splash thread's message loop
+            PostQuitMessage(0);
+        }
+        if msg != WM_PAINT {
+            return DefWindowProcW(hwnd, msg, wparam, lparam);
+        }
+        let mut ps: PAINTSTRUCT = std::mem::zeroed();
+        let hdc = BeginPaint(hwnd, &mut ps);
+        let mut rc: RECT = std::mem::zeroed();
+        GetClientRect(hwnd, &mut rc);
+
+        let bitmap = SPLASH_BITMAP.load(Ordering::SeqCst) as HBITMAP;
+        if !bitmap.is_null() {
+            let mut info: BITMAP = std::mem::zeroed();
+            GetObjectW(bitmap as _, std::mem::size_of::<BITMAP>() as i32, &mut info as *mut _ as *mut _);
+            let mem = CreateCompatibleDC(hdc);
+            let old = SelectObject(mem, bitmap as _);
+            StretchBlt(hdc, 0, 0, rc.right, rc.bottom, mem, 0, 0, info.bmWidth, info.bmHeight, SRCCOPY);
+            SelectObject(mem, old);
+            DeleteDC(mem);
+        } else {
+            let bg = CreateSolidBrush(RGB(24, 24, 27));
+            FillRect(hdc, &rc, bg);
+            DeleteObject(bg as _);
+            if let Ok(label) = LABEL.lock() {
+                SetBkMode(hdc, TRANSPARENT as i32);
+                SetTextColor(hdc, RGB(228, 228, 231));
+                let mut text_rc = rc;
+                DrawTextW(hdc, label.as_ptr(), -1, &mut text_rc, DT_CENTER | DT_VCENTER | DT_SINGLELINE);
+            }
+        }
+
+        if let Ok(status) = STATUS.lock() {
+            if status.len() > 1 {
+                SetBkMode(hdc, TRANSPARENT as i32);
+                SetTextColor(hdc, RGB(161, 161, 170));
+                let mut text_rc = RECT { bottom: rc.bottom - BAR_HEIGHT - 10, ..rc };
+                DrawTextW(hdc, status.as_ptr(), -1, &mut text_rc, DT_CENTER | DT_BOTTOM | DT_SINGLELINE);
+            }
+        }
+
+        let done = PROGRESS.load(Ordering::SeqCst) as i32;
+        let bar = RECT { left: 0, top: rc.bottom - BAR_HEIGHT, right: rc.right * done / 1000, bottom: rc.bottom };
+        let fg = CreateSolidBrush(RGB(99, 102, 241));
+        FillRect(hdc, &bar, fg);
+        DeleteObject(fg as _);
+
+        EndPaint(hwnd, &ps);
+        0
+    }
+}
+
+impl Splash {
+    /// `image` may point at a .bmp; anything else falls back to the plain title card.
+    #[cfg(windows)]
+    pub fn show(title: &str, image: Option<&Path>) -> Option<Self> {
+        set_status("Initializing...");
+        let (title, image) = (title.to_string(), image.map(Path::to_path_buf));
+        let (tx, rx) = std::sync::mpsc::channel();
+        let thread = std::thread::spawn(move || unsafe {
+            use winapi::um::winuser::*;
+
+            // The window belongs to the thread that creates it, so it's created here
+            let created = create_window(&title, image.as_deref());
+            let _ = tx.send(created);
+            if !created {
+                return;
+            }
+            let mut msg: MSG = std::mem::zeroed();
+            while GetMessageW(&mut msg, std::ptr::null_mut(), 0, 0) > 0 {
+                TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        });
+        if rx.recv().unwrap_or(false) {
+            Some(Splash { thread: Some(thread) })
+        } else {
+            let _ = thread.join();
+            None
+        }
+    }
+
+    #[cfg(not(windows))]
+    pub fn show(_title: &str, _image: Option<&Path>) -> Option<Self> {
+        None
+    }
+
+    #[allow(unused_variables)]
+    pub fn set_progress(&self, fraction: f32) {
+        #[cfg(windows)]
+        {
+            let per_mille = (fraction.clamp(0.0, 1.0) * 1000.0) as u32;
+            win::PROGRESS.store(per_mille, std::sync::atomic::Ordering::SeqCst);
+            repaint();
+        }
+    }
+
+    /// Text under the title, e.g. the startup phase.
+    pub fn set_status(&self, text: &str) {
+        set_status(text);
+    }
+}
+
+impl Drop for Splash {
+    fn drop(&mut self) {
+        #[cfg(windows)]
+        unsafe {
+            // Already closing if the app took it down through `close_splash`
+            close_splash();
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+            let bitmap = win::SPLASH_BITMAP.swap(0, std::sync::atomic::Ordering::SeqCst);
+            if bitmap != 0 {
+                winapi::um::wingdi::DeleteObject(bitmap as _);
+            }
+        }
+    }
+}
+
+/// Creates the splash window on the calling thread, which must then run its message loop.
+#[cfg(windows)]
+unsafe fn create_window(title: &str, image: Option<&Path>) -> bool {
+    use std::os::windows::ffi::OsStrExt;
+    use std::sync::atomic::Ordering;
+    use winapi::um::libloaderapi::GetModuleHandleW;
+    use winapi::um::wingdi::BITMAP;
+    use winapi::um::winuser::*;
+
+    let wide = |s: &std::ffi::OsStr| s.encode_wide().chain(Some(0)).collect::<Vec<u16>>();
+    if let Ok(mut label) = win::LABEL.lock() {
+        *label = wide(std::ffi::OsStr::new(title));
+    }
+
+    let (mut width, mut height) = (480, 270);
+    if let Some(path) = image.filter(|p| p.extension().is_some_and(|e| e.eq_ignore_ascii_case("bmp"))) {
+        let bitmap = LoadImageW(std::ptr::null_mut(), wide(path.as_os_str()).as_ptr(), IMAGE_BITMAP, 0, 0, LR_LOADFROMFILE);
+        if !bitmap.is_null() {
+            let mut info: BITMAP = std::mem::zeroed();
+            winapi::um::wingdi::GetObjectW(bitmap, std::mem::size_of::<BITMAP>() as i32, &mut info as *mut _ as *mut _);
+            width = info.bmWidth;
+            height = info.bmHeight;
+            win::SPLASH_BITMAP.store(bitmap as usize, Ordering::SeqCst);
+        }
+    }
+
+    let instance = GetModuleHandleW(std::ptr::null());
+    let class_name = wide(std::ffi::OsStr::new("PytronSplash"));
+    let class = WNDCLASSEXW {
+        cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+        lpfnWndProc: Some(win::wndproc),
+        hInstance: instance,
+        hCursor: LoadCursorW(std::ptr::null_mut(), IDC_APPSTARTING),
+        lpszClassName: class_name.as_ptr(),
+        ..std::mem::zeroed()
+    };
+    RegisterClassExW(&class);
+
+    let x = (GetSystemMetrics(SM_CXSCREEN) - width) / 2;
+    let y = (GetSystemMetrics(SM_CYSCREEN) - height) / 2;
+    let hwnd = CreateWindowExW(
+        WS_EX_TOOLWINDOW | WS_EX_TOPMOST,
+        class_name.as_ptr(),
+        wide(std::ffi::OsStr::new(title)).as_ptr(),
+        WS_POPUP | WS_VISIBLE,
+        x, y, width, height,
+        std::ptr::null_mut(), std::ptr::null_mut(), instance, std::ptr::null_mut(),
+    );
+    if hwnd.is_null() {
+        return false;
+    }
+    win::WINDOW.store(hwnd as usize, Ordering::SeqCst);
+    true
+}
+
+// Invalidating is safe from any thread; the splash thread's loop does the painting
+#[cfg(windows)]
+fn repaint() {
+    let hwnd = win::WINDOW.load(std::sync::atomic::Ordering::SeqCst) as winapi::shared::windef::HWND;
+    if !hwnd.is_null() {
+        unsafe { winapi::um::winuser::InvalidateRect(hwnd, std::ptr::null(), 0) };
+    }
+}
+
+#[allow(unused_variables)]
+fn set_status(text: &str) {
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::OsStrExt;
+
+        if let Ok(mut status) = win::STATUS.lock() {
+            *status = std::ffi::OsStr::new(text).encode_wide().chain(Some(0)).collect();
+        }
+        repaint();
+    }
+}
+
+/// Shows `text` on the loader's splash, e.g. between slow imports. No-op without a splash.
+#[pyfunction]
+pub fn set_splash_status(text: &str) {
+    set_status(text);
+}
+
+/// Closes the loader's splash; the app calls this once its first window is up.
+#[pyfunction]
+pub fn close_splash() {
+    #[cfg(windows)]
+    unsafe {
+        use winapi::um::winuser::{PostMessageW, WM_CLOSE};
+
+        let hwnd = win::WINDOW.load(std::sync::atomic::Ordering::SeqCst);
+        if hwnd != 0 {
+            // Destroyed by the splash thread, whichever thread asks
+            PostMessageW(hwnd as _, WM_CLOSE, 0, 0);
+        }
+    }
+}
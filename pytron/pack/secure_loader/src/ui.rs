@@ -31,6 +31,17 @@ pub fn set_app_id(_id: &str) {
     }
 }
 
+/// Attaches a console to this GUI-subsystem process (for `--debug` and crash reports).
+pub fn alloc_console() {
+    #[cfg(windows)]
+    unsafe {
+        if let Ok(func) = libloading::Library::new("kernel32.dll") {
+             let alloc: libloading::Symbol<unsafe extern "system" fn() -> i32> = func.get(b"AllocConsole").unwrap();
+             alloc();
+        }
+    }
+}
+
 pub fn init_com() {
     #[cfg(windows)]
     unsafe {
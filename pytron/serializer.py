@@ -22,6 +22,7 @@ class PytronJSONEncoder(json.JSONEncoder):
     def __init__(self, *args, **kwargs):
         # PERFORMANCE: Capture the VAP provider if passed
         self.vap_provider = kwargs.pop("vap_provider", None)
+        self.scheme = kwargs.pop("scheme", "pytron")
         super().__init__(*args, **kwargs)
 
     def default(self, obj):
@@ -42,8 +43,8 @@ class PytronJSONEncoder(json.JSONEncoder):
             # Since this is a static encoder, we check if it's attached elsewhere
             # or fallback to base64 if no asset provider is found.
             if hasattr(self, "vap_provider"):
-                self.vap_provider(asset_id, buffered.getvalue(), "image/png")
-                return f"pytron://{asset_id}"
+                url = self.vap_provider(asset_id, buffered.getvalue(), "image/png")
+                return url or f"{self.scheme}://{asset_id}"
 
             img_str = base64.b64encode(buffered.getvalue()).decode("utf-8")
             return f"data:image/png;base64,{img_str}"
@@ -52,8 +53,8 @@ class PytronJSONEncoder(json.JSONEncoder):
             # PERFORMANCE: Avoid 33% Base64 bloat for binary blobs
             if hasattr(self, "vap_provider"):
                 asset_id = f"gen_bin_{uuid.uuid4().hex[:8]}"
-                self.vap_provider(asset_id, obj, "application/octet-stream")
-                return f"pytron://{asset_id}"
+                url = self.vap_provider(asset_id, obj, "application/octet-stream")
+                return url or f"{self.scheme}://{asset_id}"
             return base64.b64encode(obj).decode("utf-8")
 
         if isinstance(obj, (datetime.datetime, datetime.date, datetime.time)):
@@ -136,7 +137,7 @@ class PytronJSONEncoder(json.JSONEncoder):
             return super().default(obj)
 
 
-def pytron_serialize(obj, vap_provider=None):
+def pytron_serialize(obj, vap_provider=None, scheme="pytron"):
     """
     Helper to serialize objects to JSON-compatible primitives.
     OPTIMIZED: Avoids double serialization (dumps/loads) by recursively
@@ -148,9 +149,9 @@ def pytron_serialize(obj, vap_provider=None):
     # Handle Pydantic Models
     if pydantic and isinstance(obj, pydantic.BaseModel):
         try:
-            return pytron_serialize(obj.model_dump(), vap_provider)
+            return pytron_serialize(obj.model_dump(), vap_provider, scheme)
         except AttributeError:
-            return pytron_serialize(obj.dict(), vap_provider)
+            return pytron_serialize(obj.dict(), vap_provider, scheme)
 
     # Handle PIL Images
     if Image and isinstance(obj, Image.Image):
@@ -158,16 +159,17 @@ def pytron_serialize(obj, vap_provider=None):
         obj.save(buffered, format="PNG")
         if vap_provider:
             asset_id = f"gen_img_{uuid.uuid4().hex[:8]}"
-            vap_provider(asset_id, buffered.getvalue(), "image/png")
-            return f"pytron://{asset_id}"
+            # The provider knows the scheme and protocol token; older ones return nothing
+            url = vap_provider(asset_id, buffered.getvalue(), "image/png")
+            return url or f"{scheme}://{asset_id}"
         img_str = base64.b64encode(buffered.getvalue()).decode("utf-8")
         return f"data:image/png;base64,{img_str}"
 
     if isinstance(obj, bytes):
         if vap_provider:
             asset_id = f"gen_bin_{uuid.uuid4().hex[:8]}"
-            vap_provider(asset_id, obj, "application/octet-stream")
-            return f"pytron://{asset_id}"
+            url = vap_provider(asset_id, obj, "application/octet-stream")
+            return url or f"{scheme}://{asset_id}"
         return base64.b64encode(obj).decode("utf-8")
 
     if isinstance(obj, (datetime.datetime, datetime.date, datetime.time)):
@@ -181,7 +183,7 @@ def pytron_serialize(obj, vap_provider=None):
     if isinstance(obj, pathlib.Path):
         return str(obj)
     if isinstance(obj, set):
-        return [pytron_serialize(i, vap_provider) for i in obj]
+        return [pytron_serialize(i, vap_provider, scheme) for i in obj]
     if isinstance(obj, complex):
         return {"real": obj.real, "imag": obj.imag}
 
@@ -190,7 +192,7 @@ def pytron_serialize(obj, vap_provider=None):
         import dataclasses
 
         if dataclasses.is_dataclass(obj):
-            return pytron_serialize(dataclasses.asdict(obj), vap_provider)
+            return pytron_serialize(dataclasses.asdict(obj), vap_provider, scheme)
     except ImportError:
         pass
 
@@ -205,16 +207,16 @@ def pytron_serialize(obj, vap_provider=None):
 
     # Dictionaries
     if isinstance(obj, dict):
-        return {str(k): pytron_serialize(v, vap_provider) for k, v in obj.items()}
+        return {str(k): pytron_serialize(v, vap_provider, scheme) for k, v in obj.items()}
 
     # Iterables
     if isinstance(obj, (list, tuple)):
-        return [pytron_serialize(i, vap_provider) for i in obj]
+        return [pytron_serialize(i, vap_provider, scheme) for i in obj]
 
     # Universal Fallback: Try __dict__
     if hasattr(obj, "__dict__"):
         return {
-            k: pytron_serialize(v, vap_provider)
+            k: pytron_serialize(v, vap_provider, scheme)
             for k, v in vars(obj).items()
             if not k.startswith("_")
         }
@@ -228,7 +230,7 @@ def pytron_serialize(obj, vap_provider=None):
         for key in slots:
             if not key.startswith("_"):
                 try:
-                    data[key] = pytron_serialize(getattr(obj, key), vap_provider)
+                    data[key] = pytron_serialize(getattr(obj, key), vap_provider, scheme)
                 except Exception:
                     pass
         return data
@@ -31,10 +31,37 @@ from .exceptions import ConfigError
 IS_ANDROID = False
 
 
+class CallStream:
+    """
+    Streams a bound function's output to the calling page. emit_chunk() may be called
+    from any thread until end(); the JS promise resolves once the stream ends.
+    """
+
+    def __init__(self, webview, seq, serialize):
+        self._webview = webview
+        self._seq = seq
+        self._serialize = serialize
+        self.ended = False
+
+    def emit_chunk(self, data):
+        if self.ended:
+            raise RuntimeError("Stream already ended")
+        self._webview.native.emit_chunk(self._seq, json.dumps(self._serialize(data)))
+
+    def end(self):
+        if not self.ended:
+            self.ended = True
+            self._webview.native.end_stream(self._seq)
+
+
 # -------------------------------------------------------------------
 # Browser wrapper (Native PyO3 Version)
 # -------------------------------------------------------------------
 class Webview:
+    # Ids of the native tray's default "Show App" / "Quit" items; custom tray menus may reuse them
+    TRAY_SHOW_ID = "1000"
+    TRAY_QUIT_ID = "1001"
+
     def __init__(self, config):
         if not pytron_native:
             raise ImportError(
@@ -64,18 +91,24 @@ class Webview:
 
         self._bound_functions = {}
         self._served_data = {}
+        self._accelerators = {}
+        self._tray_icon_handlers = {}
+        self._page_hang_handler = None
+        self._power_handler = None
+        self._connectivity_handler = None
 
         # 3. Native Engine Initialization
         # 3. Native Engine Initialization
         # Logic to determine Root Path for Virtual Host (pytron://app/)
         raw_url = config.get("url", "")
         debug = config.get("debug", False)
+        self.scheme = config.get("scheme", "pytron")
 
         root_path = str(self._app_root)
         final_url = raw_url
 
         # Check if URL looks like a local file path
-        if not raw_url.startswith(("http:", "https:", "pytron:")):
+        if not raw_url.startswith(("http:", "https:", f"{self.scheme}:")):
             path_obj = pathlib.Path(raw_url)
             if not path_obj.is_absolute():
                 path_obj = (self._app_root / path_obj).resolve()
@@ -84,15 +117,15 @@ class Webview:
                 # Valid local file found.
                 # Map its parent dir as the App Root.
                 root_path = str(path_obj.parent)
-                # URL becomes https://pytron.localhost/app/<filename>
+                # URL becomes https://<scheme>.localhost/app/<filename>
                 final_url = (
-                    f"https://pytron.localhost/app/{urllib.parse.quote(path_obj.name)}"
+                    f"https://{self.scheme}.localhost/app/{urllib.parse.quote(path_obj.name)}"
                 )
             else:
                 # Fallback
                 root_path = str(path_obj.parent)
                 final_url = (
-                    f"https://pytron.localhost/app/{urllib.parse.quote(path_obj.name)}"
+                    f"https://{self.scheme}.localhost/app/{urllib.parse.quote(path_obj.name)}"
                 )
 
         self.root_path = root_path  # Store for later navigations
@@ -115,6 +148,36 @@ class Webview:
                 root_path,
                 bool(resizable),
                 bool(frameless),
+                scheme=self.scheme,
+                cors_origins=config.get("cors_origins"),
+                native_alert=config.get("native_alert", True),
+                spa_fallback=config.get("spa_fallback", False),
+                error_pages={int(k): v for k, v in config.get("error_pages", {}).items()},
+                protocol_stats=config.get("protocol_stats", False),
+                asset_archive=config.get("asset_archive"),
+                headers=config.get("headers"),
+                service_worker=config.get("service_worker"),
+                start_maximized=config.get("start_maximized", False),
+                start_fullscreen=config.get("start_fullscreen", False),
+                auto_show_on_ready=config.get("auto_show_on_ready", False),
+                reload_on_crash=config.get("reload_on_crash", False),
+                watch=config.get("watch", False),
+                shadow=config.get("shadow", True),
+                corners=config.get("corners"),
+                background_color=config.get("background_color"),
+                additional_browser_args=config.get("additional_browser_args"),
+                navigation_allowlist=config.get("navigation_allowlist"),
+                title=config.get("title"),
+                protocol_token=config.get("protocol_token", False),
+                initial_css=config.get("initial_css"),
+                app_id=getattr(self.app, "app_id", None),
+                data_directory=self._data_directory(config),
+                profile=config.get("profile"),
+                ipc_batching=config.get("ipc_batching", False),
+                initial_state=self._initial_state(config),
+                title_bar_style=config.get("title_bar_style"),
+                traffic_light_position=config.get("traffic_light_position"),
+                custom_protocols=self._custom_protocols(),
             )
         except TypeError:
             # Fallback if pyd wasn't updated yet? No, we will rebuild.
@@ -123,6 +186,11 @@ class Webview:
         # 4. Bindings
         self._init_bindings()
 
+        # Packaged apps started with --debug mirror print() output into the devtools console
+        output_listeners = getattr(sys, "pytron_output_listeners", None)
+        if output_listeners is not None:
+            output_listeners.append(self._mirror_output)
+
         # 5. Window Settings
         self.set_title(config.get("title", "Pytron App"))
         w, h = config.get("dimensions", [800, 600])
@@ -184,12 +252,27 @@ class Webview:
 
         # Register Native Event Handlers (Direct Binding)
         self.native.bind("pytron_on_close", self._on_close_requested)
+        self.native.bind("pytron_on_quit", self._on_quit)
         self.native.bind("pytron_tray_click", self._on_tray_click)
+        self.native.bind("pytron_on_accelerator", self._on_accelerator)
+        self.native.bind("pytron_on_open_file", self._on_open_file)
+        self.native.bind("pytron_on_page_hang", self._on_page_hang)
+        self.native.bind("pytron_on_power_changed", self._on_power_changed)
+        self.native.bind("pytron_on_connectivity_changed", self._on_connectivity_changed)
+        self.native.bind("pytron_tray_icon_click", lambda x, y: self._on_tray_icon("click", x, y))
+        self.native.bind("pytron_tray_icon_double_click", lambda x, y: self._on_tray_icon("double_click", x, y))
 
         # Configure Close Behavior
         if self.config.get("close_to_tray", False):
             self.logger.info("Enabling Close-to-Tray behavior.")
-            self.set_prevent_close(True)
+            if hasattr(self.native, "set_close_to_tray"):
+                self.set_close_to_tray(True)
+            else:
+                self.set_prevent_close(True)
+        if self.config.get("minimize_to_tray", False) and hasattr(self.native, "set_minimize_to_tray"):
+            self.native.set_minimize_to_tray(True)
+        if self.config.get("auto_suspend") and hasattr(self.native, "set_auto_suspend"):
+            self.native.set_auto_suspend(int(self.config["auto_suspend"]))
 
         # Trigger initial navigation now that bindings are (presumably) queued
         if hasattr(self, "_start_url"):
@@ -268,6 +351,36 @@ class Webview:
             # enable slim -> disable decorations
             self.native.set_decorations(not enable)
 
+    def set_shadow(self, enable=True):
+        if hasattr(self.native, "set_shadow"):
+            self.native.set_shadow(enable)
+
+    def set_ignore_cursor_events(self, ignore=True):
+        """Click-through mode for overlays: visible, but mouse input passes to the window below."""
+        if hasattr(self.native, "set_ignore_cursor_events"):
+            self.native.set_ignore_cursor_events(ignore)
+
+    def register_accelerator(self, accel, id, callback=None):
+        """
+        Window-local shortcut, e.g. "CmdOrCtrl+Shift+K". Only fires while this window is focused,
+        even for keys the page's shortcut blocklist swallows. The page also gets a 'pytron:accelerator' event.
+        """
+        if not hasattr(self.native, "register_accelerator"):
+            return
+        self.native.register_accelerator(accel, id)
+        if callback:
+            self._accelerators[id] = callback
+
+    def unregister_accelerator(self, id):
+        self._accelerators.pop(id, None)
+        if hasattr(self.native, "unregister_accelerator"):
+            self.native.unregister_accelerator(id)
+
+    def set_corner_preference(self, pref):
+        """'round', 'small', 'square' or 'default' (Windows 11 only)."""
+        if hasattr(self.native, "set_corner_preference"):
+            self.native.set_corner_preference(pref)
+
     @property
     def hwnd(self):
         if hasattr(self.native, "get_hwnd"):
@@ -275,15 +388,37 @@ class Webview:
         return getattr(self, "_hwnd_cache", 0)
 
     # ... Bindings Logic ... (omitted for brevity, assume existing)
-    def bind(self, name, python_func, run_in_thread=True, secure=False):
+    def bind(
+        self,
+        name,
+        python_func,
+        run_in_thread=True,
+        secure=False,
+        pass_context=False,
+        stream=False,
+    ):
+        """
+        pass_context: also call python_func with context={"window_id", "origin", "method"}
+        describing which window/page made the call.
+        stream: also call python_func with stream=<CallStream>; it may emit_chunk() any number
+        of times and must end() the stream. Generator functions stream their yields automatically.
+        JS reads the chunks with `for await (const chunk of call)` or `call.stream()`.
+        """
         is_async = inspect.iscoroutinefunction(python_func)
+        is_generator = inspect.isgeneratorfunction(python_func)
+        is_async_generator = inspect.isasyncgenfunction(python_func)
 
-        # The Wrapper that Rust calls: (seq, args_json, ptr)
-        def _native_callback(seq, req, arg_ptr):
+        # The Wrapper that Rust calls: (seq, args_json, context_json)
+        def _native_callback(seq, req, context=None):
             try:
                 args = json.loads(req) if req else []
             except Exception:
                 args = []
+            try:
+                ctx = json.loads(context) if isinstance(context, str) else {}
+            except Exception:
+                ctx = {}
+            kwargs = {"context": ctx} if pass_context else {}
 
             # Internal logging
             if not name.startswith("inspector_") and name not in self._spammy_methods:
@@ -291,31 +426,49 @@ class Webview:
 
             # Result serializer
             def _serialize_result(res):
-                return pytron_serialize(res, vap_provider=self.serve_data)
+                return pytron_serialize(res, vap_provider=self.serve_data, scheme=self.scheme)
+
+            call_stream = CallStream(self, seq, _serialize_result)
+            if stream:
+                kwargs["stream"] = call_stream
 
             # Response Helper
             def _respond(status, result):
+                if call_stream.ended:
+                    return
+                call_stream.ended = True
                 res_str = json.dumps(result)
                 self.native.return_result(seq, status, res_str)
 
             # Runner Logic
             def _runner():
                 try:
-                    res = python_func(*args)
-                    _respond(0, _serialize_result(res))
+                    res = python_func(*args, **kwargs)
+                    if is_generator:
+                        for chunk in res:
+                            call_stream.emit_chunk(chunk)
+                        call_stream.end()
+                    elif not stream:
+                        _respond(0, _serialize_result(res))
                 except Exception as e:
                     self.logger.error(f"Error in {name}: {e}")
                     _respond(1, str(e))
 
             async def _async_runner():
                 try:
-                    res = await python_func(*args)
-                    _respond(0, _serialize_result(res))
+                    if is_async_generator:
+                        async for chunk in python_func(*args, **kwargs):
+                            call_stream.emit_chunk(chunk)
+                        call_stream.end()
+                        return
+                    res = await python_func(*args, **kwargs)
+                    if not stream:
+                        _respond(0, _serialize_result(res))
                 except Exception as e:
                     self.logger.error(f"Error in {name}: {e}")
                     _respond(1, str(e))
 
-            if is_async:
+            if is_async or is_async_generator:
                 asyncio.run_coroutine_threadsafe(_async_runner(), self.loop)
             else:
                 if run_in_thread:
@@ -323,22 +476,78 @@ class Webview:
                 else:
                     _runner()
 
-        # Register with Rust
-        self.native.bind(name, _native_callback)
+        # Register with Rust; only context-aware bindings get the context JSON
+        self.native.bind(name, _native_callback, pass_context=pass_context)
 
     # --- Core API ---
 
-    def navigate(self, url):
+    def navigate(self, url, headers=None):
         target = self._normalize_to_pytron(url)
         self.config["url"] = target
-        self.native.navigate(target)
+        if headers:
+            self.native.navigate_with_headers(target, dict(headers))
+        else:
+            self.native.navigate(target)
         # Attempt to apply UI settings (Context Menu, BG) via JS for Native Engine
         # Note: This might race with page load clearing scripts, but it's best effort.
         self._apply_ui_settings()
 
+    def get_url(self):
+        """The URL the page is showing now, including routes changed from JS."""
+        call_native = getattr(self.native, "get_url", None)
+        url = call_native() if call_native else ""
+        return url or self.config.get("url", "")
+
+    def get_webview_version(self):
+        """
+        {"backend": "webview2" | "wkwebview" | "webkitgtk", "version": str}, for bug reports.
+        The version is empty when the engine doesn't report one.
+        """
+        call_native = getattr(self.native, "get_webview_version", None)
+        if call_native:
+            return call_native()
+        return {"backend": "unknown", "version": ""}
+
+    def _initial_state(self, config):
+        """JSON-encodes the `initial_state` dict for window.pytron.initial_state."""
+        state = config.get("initial_state")
+        if not state:
+            return None
+        return {str(k): json.dumps(pytron_serialize(v)) for k, v in state.items()}
+
+    def _data_directory(self, config):
+        """
+        Where the webview keeps cookies and storage. A `profile` is isolated under
+        <data_directory>/profiles/<profile> and must be chosen when the window is created;
+        without an explicit data_directory it lives in the app's storage path.
+        """
+        data_directory = config.get("data_directory")
+        if not data_directory and config.get("profile"):
+            data_directory = getattr(self.app, "storage_path", None)
+        return data_directory
+
+    def _custom_protocols(self):
+        protocols = getattr(self.app, "protocols", None)
+        if not protocols:
+            return None
+        return {scheme: self._protocol_handler(func) for scheme, func in protocols.items()}
+
+    def _protocol_handler(self, func):
+        # Lets handlers return str/None bodies and any header values; the engine wants bytes
+        def handle(method, url, headers, body):
+            status, response_headers, data = func(method, url, headers, body)
+            if data is None:
+                data = b""
+            elif isinstance(data, str):
+                data = data.encode("utf-8")
+            headers_out = {str(k): str(v) for k, v in (response_headers or {}).items()}
+            return int(status), headers_out, bytes(data)
+
+        return handle
+
     def _normalize_to_pytron(self, url):
-        """Ensures local file paths are converted to pytron://app/ URLs relative to root_path."""
-        if url.startswith(("http:", "https:", "pytron:")):
+        """Ensures local file paths are converted to <scheme>://app/ URLs relative to root_path."""
+        if url.startswith(("http:", "https:", f"{self.scheme}:")):
             return url
 
         path_obj = pathlib.Path(url)
@@ -353,7 +562,7 @@ class Webview:
             # relative_to throws ValueError if not relative
             rel = path_obj.resolve().relative_to(root.resolve())
             # Use forward slashes for URL
-            return f"https://pytron.localhost/app/{urllib.parse.quote(rel.as_posix())}"
+            return f"https://{self.scheme}.localhost/app/{urllib.parse.quote(rel.as_posix())}"
         except (ValueError, Exception):
             # If outside root, we can't serve it via current pytron instance easily.
             # But maybe the current logic allows it if we didn't lock protocol_root?
@@ -370,7 +579,12 @@ class Webview:
         """
         self._served_data[key] = (data, mime_type)
         # Use HTTPS scheme for Windows/Native compatibility
-        return f"https://pytron.localhost/{key}"
+        url = f"https://{self.scheme}.localhost/{key}"
+        # src attributes can't send the token header, so it rides in the query
+        token = self.native.protocol_token()
+        if token:
+            url += "?" + urllib.parse.urlencode({"pytron_token": token})
+        return url
 
     def _apply_ui_settings(self):
         """Applies UI configuration via JavaScript injection."""
@@ -398,14 +612,43 @@ class Webview:
         self.native.set_title(title)
 
     def set_size(self, w, h):
+        """Content size in logical pixels (DPI-independent), like get_size()."""
         self.native.set_size(w, h, 0)
 
+    def get_size(self):
+        """(width, height) of the content area in logical pixels, or None if unavailable."""
+        call_native = getattr(self.native, "get_size", None)
+        return call_native() if call_native else None
+
     def eval(self, js):
         self.native.eval(js)
 
+    def wait_until_ready(self, timeout=5.0):
+        """Blocks until the page's bridge is up (eval/emit won't be dropped). False on timeout."""
+        if not hasattr(self.native, "wait_until_ready"):
+            return True
+        return self.native.wait_until_ready(int(timeout * 1000))
+
     def reload(self):
         self.native.eval("location.reload()")
 
+    def insert_css(self, css, id=None):
+        """Adds a stylesheet that survives navigation; reusing `id` swaps it in place. Returns the id."""
+        if hasattr(self.native, "insert_css"):
+            return self.native.insert_css(css, id)
+        style_id = id or f"pytron-css-{int(time.time() * 1000)}"
+        self.eval(
+            f"(document.getElementById({json.dumps(style_id)}) || document.head.appendChild("
+            f"Object.assign(document.createElement('style'), {{id: {json.dumps(style_id)}}}))).textContent = {json.dumps(css)};"
+        )
+        return style_id
+
+    def remove_css(self, id):
+        if hasattr(self.native, "remove_css"):
+            self.native.remove_css(id)
+        else:
+            self.eval(f"document.getElementById({json.dumps(id)})?.remove();")
+
     def close(self, force=False):
         """
         Closes the window.
@@ -417,7 +660,14 @@ class Webview:
             # Assuming App deals with that.
             return
 
-        self.native.terminate()
+        self.quit()
+
+    def quit(self):
+        """Graceful exit: on_exit callbacks get a bounded chance to save state before the loop stops."""
+        if hasattr(self.native, "quit"):
+            self.native.quit()
+        else:
+            self.native.terminate()
 
     def emit(self, event, data=None):
         """
@@ -430,6 +680,12 @@ class Webview:
         js = f"window.dispatchEvent(new CustomEvent('{event}', {{ detail: {payload} }}));"
         self.eval(js)
 
+    def broadcast(self, event, data=None):
+        """
+        Emits a custom event to every native window in the process, not just this one.
+        """
+        self.native.broadcast(event, json.dumps(data))
+
     # --- Asset Serving (VAP) ---
     # serve_data is defined above to return the URL.
 
@@ -444,16 +700,16 @@ class Webview:
         if not raw_url:
             raise ConfigError("No URL Configured")
 
-        if raw_url.startswith(("http:", "https:", "pytron:")):
+        if raw_url.startswith(("http:", "https:", f"{self.scheme}:")):
             return
 
         path_obj = pathlib.Path(raw_url)
         if not path_obj.is_absolute():
             path_obj = (self._app_root / path_obj).resolve()
 
-        # Convert to pytron://
+        # Convert to <scheme>://
         # Use localhost as authority to prevent "Origin null" issues
-        config["url"] = path_obj.as_uri().replace("file:///", "pytron://localhost/")
+        config["url"] = path_obj.as_uri().replace("file:///", f"{self.scheme}://localhost/")
 
     # --- Native Mappings ---
     def set_icon(self, icon_path):
@@ -464,6 +720,9 @@ class Webview:
         self.native.minimize()
 
     def toggle_maximize(self):
+        if hasattr(self.native, "toggle_maximize"):
+            self.native.toggle_maximize()
+            return
         # Native Toggle using HWND check because Native Engine is async state
         if sys.platform == "win32" and self.hwnd:
             import ctypes
@@ -512,6 +771,12 @@ class Webview:
         if call_native:
             call_native(enable)
 
+    def set_aspect_ratio(self, width=None, height=None):
+        """Keeps the window at width:height while resizing; no arguments lifts the constraint."""
+        call_native = getattr(self.native, "set_aspect_ratio", None)
+        if call_native:
+            call_native(width, height)
+
     def maximize(self):
         self.native.maximize()
 
@@ -525,8 +790,26 @@ class Webview:
     def show(self):
         self.native.show()
 
-    def set_fullscreen(self, enable):
-        self.native.set_fullscreen(enable)
+    def set_fullscreen(self, enable, monitor=None, exclusive=False):
+        """
+        monitor: index of the display to cover (default: the window's current one).
+        exclusive: switch the display's video mode instead of a borderless window.
+        """
+        call_native = getattr(self.native, "set_fullscreen_ex", None)
+        if call_native and (monitor is not None or exclusive):
+            call_native(enable, monitor, exclusive)
+        else:
+            self.native.set_fullscreen(enable)
+
+    def print(self, force_light=False):
+        """
+        Opens the print dialog. force_light prints a dark-themed page in a light scheme.
+        """
+        call_native = getattr(self.native, "print", None)
+        if call_native:
+            call_native(force_light)
+        else:
+            self.eval("window.print()")
 
     def center(self):
         call_native = getattr(self.native, "center", None)
@@ -619,6 +902,20 @@ class Webview:
             return self._platform.message_box(self.hwnd, *args, **kwargs)
         return 0
 
+    def show_about(self):
+        """Native About box with the app's name, version and author from settings."""
+        if hasattr(self.native, "show_about"):
+            self.native.show_about()
+            return
+        info = getattr(sys, "pytron_app_info", {})
+        name = info.get("name") or self.config.get("title", "Pytron App")
+        lines = [name]
+        if info.get("version"):
+            lines.append(f"Version {info['version']}")
+        if info.get("author"):
+            lines.append(f"\u00a9 {info['author']}")
+        self.message_box(f"About {name}", "\n".join(lines), 0x40)
+
     def set_taskbar_progress(self, state="normal", value=0, max_value=100):
         # State mapping: normal, error, paused, indeterminate, none
         # Native Lib expects: 2=Normal, 4=Error, 8=Paused
@@ -647,21 +944,138 @@ class Webview:
             self._platform.notification(self.hwnd, title, message, icon)
 
     # --- Native Tray & Close Handling ---
-    def create_tray(self, icon_path, tooltip="Pytron App"):
-        if hasattr(self.native, "create_tray"):
+    def create_tray(self, icon_path, tooltip="Pytron App", menu=None):
+        """
+        `menu` replaces the default Show App / Quit items, e.g.
+        [{"id": Webview.TRAY_SHOW_ID, "text": "Open"}, {"type": "separator"}, {"id": "sync", "text": "Sync now"}].
+        An empty list creates the icon without a menu. Clicks arrive at the tray click handler by id.
+        """
+        if not hasattr(self.native, "create_tray"):
+            return
+        if menu is None:
             self.native.create_tray(icon_path, tooltip)
+        else:
+            self.native.create_tray(icon_path, tooltip, json.dumps(menu))
+
+    def set_tray_animation(self, frames, interval_ms=100):
+        """Cycles the tray icon through the given image paths, e.g. a spinner while syncing."""
+        if hasattr(self.native, "set_tray_animation"):
+            self.native.set_tray_animation([str(f) for f in frames], int(interval_ms))
+
+    def stop_tray_animation(self):
+        """Restores the static tray icon."""
+        if hasattr(self.native, "stop_tray_animation"):
+            self.native.stop_tray_animation()
 
     def set_prevent_close(self, prevent):
         if hasattr(self.native, "set_prevent_close"):
             self.native.set_prevent_close(prevent)
 
+    def set_close_to_tray(self, enable=True):
+        """Close hides the window while a tray icon exists; the tray's Quit item exits."""
+        if hasattr(self.native, "set_close_to_tray"):
+            self.native.set_close_to_tray(enable)
+
+    def on_tray_icon_click(self, callback):
+        """Left-click on the tray icon itself (not a menu item). Called with the screen position (x, y)."""
+        self._tray_icon_handlers["click"] = callback
+        return callback
+
+    def on_tray_icon_double_click(self, callback):
+        """Left double-click on the tray icon. Windows only; other platforms only report single clicks."""
+        self._tray_icon_handlers["double_click"] = callback
+        return callback
+
+    def _on_tray_icon(self, kind, x, y):
+        callback = self._tray_icon_handlers.get(kind)
+        if callback:
+            try:
+                callback(x, y)
+            except Exception as e:
+                self.logger.error(f"Tray icon {kind} handler failed: {e}")
+
+    def _mirror_output(self, stream, line):
+        level = "error" if stream == "stderr" else "log"
+        self.native.eval(f"console.{level}({json.dumps(line)})")
+
+    def set_hang_watchdog(self, interval=2.0, misses=3):
+        """
+        Pings the page every `interval` seconds and calls the on_page_hang handler once it
+        misses `misses` pings in a row. interval=None stops the watchdog.
+        """
+        call_native = getattr(self.native, "set_hang_watchdog", None)
+        if call_native:
+            call_native(None if interval is None else int(interval * 1000), misses)
+
+    def on_page_hang(self, callback):
+        """
+        Called with a reason ("unresponsive" from the watchdog, "render-unresponsive" from
+        WebView2) when the page stops responding, e.g. to reload() or alert the user.
+        """
+        self._page_hang_handler = callback
+        return callback
+
+    def _on_page_hang(self, reason):
+        callback = self._page_hang_handler
+        if callback:
+            # Off the UI thread, so the handler can show dialogs or reload
+            self.thread_pool.submit(callback, reason)
+        else:
+            self.logger.warning(f"Page is not responding ({reason})")
+
+    def get_power_state(self):
+        """
+        {"on_battery": bool, "battery_percent": int | None, "charging": bool}.
+        Reports AC power when the engine can't tell.
+        """
+        call_native = getattr(self.native, "get_power_state", None)
+        if call_native:
+            return call_native()
+        return {"on_battery": False, "battery_percent": None, "charging": False}
+
+    def on_power_changed(self, callback):
+        """
+        Called with the new get_power_state() dict when the machine is plugged in or
+        unplugged, or the battery level changes. Also emitted to the page as "pytron:power".
+        """
+        self._power_handler = callback
+        return callback
+
+    def _on_power_changed(self, state):
+        self.emit("pytron:power", state)
+        callback = self._power_handler
+        if callback:
+            self.thread_pool.submit(callback, state)
+
+    def is_online(self):
+        call_native = getattr(self.native, "is_online", None)
+        return call_native() if call_native else True
+
+    def on_connectivity_changed(self, callback):
+        """
+        Called with True/False when the machine goes online or offline. The page gets the
+        same change as a "pytron:connectivity" event with detail {online}.
+        """
+        self._connectivity_handler = callback
+        return callback
+
+    def _on_connectivity_changed(self, online):
+        callback = self._connectivity_handler
+        if callback:
+            self.thread_pool.submit(callback, online)
+
     def _on_close_requested(self):
         """Called by Native Engine when X is clicked and prevent_close is True."""
         if self.config.get("close_to_tray", False):
             self.hide()
         else:
             # Should not happen if prevent_close logic is consistent, but fallback
-            self.native.terminate()
+            self.quit()
+
+    def _on_quit(self):
+        """Called by Native Engine during a graceful quit, before the event loop exits."""
+        if self.app:
+            self.app._run_exit_callbacks()
 
     def _get_binary_asset(self, key):
         """
@@ -699,15 +1113,24 @@ class Webview:
     def _on_tray_click(self, menu_id):
         """Called by Native Engine when tray menu is clicked."""
         self.logger.info(f"Tray Click: {menu_id}")
-        if str(menu_id) == "1001" or str(menu_id) == "Quit":  # Approx
-            self.native.terminate()
-        elif str(menu_id) == "1000" or str(menu_id) == "Show":  # Approx
+        if str(menu_id) == self.TRAY_QUIT_ID or str(menu_id) == "Quit":  # Approx
+            self.quit()
+        elif str(menu_id) == "Show":
             self.show()
-            self.native.set_visible(True)
-            if sys.platform == "win32":
-                self.minimize()  # Hack to restore? No, show() involves restore.
-                self.native.minimize()
-                self.native.show()  # Force restore from minimize
+        # TRAY_SHOW_ID is restored by the native engine itself
+
+    def _on_open_file(self, path):
+        """Called by Native Engine when macOS opens a document with the running app."""
+        if self.app:
+            self.app._dispatch_open_file(self, path)
+
+    def _on_accelerator(self, accel_id):
+        callback = self._accelerators.get(accel_id)
+        if callback:
+            try:
+                callback()
+            except Exception as e:
+                self.logger.error(f"Accelerator '{accel_id}' handler failed: {e}")
 
     # Redundant _init_bindings removed.
     def expose(self, entity):
@@ -0,0 +1,69 @@
+import io
+import pytest
+
+from pytron.engines.chrome.framing import (
+    HEADER,
+    MAX_FRAME_LEN,
+    TYPE_BINARY,
+    TYPE_CONTROL,
+    TYPE_TEXT,
+    FrameError,
+    encode_frame,
+    read_frame,
+)
+
+
+def _reader(data):
+    stream = io.BytesIO(data)
+
+    def read_exact(n):
+        chunk = stream.read(n)
+        return chunk if len(chunk) == n else None
+
+    return read_exact
+
+
+def test_round_trip_text_and_binary():
+    data = encode_frame('{"a": 1}') + encode_frame(b"\x00\x01", TYPE_BINARY)
+    read = _reader(data)
+    assert read_frame(read) == (TYPE_TEXT, b'{"a": 1}')
+    assert read_frame(read) == (TYPE_BINARY, b"\x00\x01")
+    assert read_frame(read) is None
+
+
+def test_header_layout():
+    frame = encode_frame("hi", TYPE_CONTROL)
+    assert frame[:2] == b"PT"
+    assert frame[2] == 1
+    assert frame[3] == TYPE_CONTROL
+    assert int.from_bytes(frame[4:8], "little") == 2
+    assert len(frame) == HEADER.size + 2
+
+
+def test_resyncs_past_garbage():
+    read = _reader(b"\x00garbage" + encode_frame("ok"))
+    assert read_frame(read) == (TYPE_TEXT, b"ok")
+
+
+def test_stray_magic_is_skipped():
+    read = _reader(b"PT" + encode_frame("ok"))
+    assert read_frame(read) == (TYPE_TEXT, b"ok")
+    read = _reader(b"\x00PT\x63\x00" + encode_frame("ok"))
+    assert read_frame(read) == (TYPE_TEXT, b"ok")
+
+
+def test_oversized_length_raises_without_allocating():
+    header = HEADER.pack(b"PT", 1, TYPE_TEXT, MAX_FRAME_LEN + 1)
+    with pytest.raises(FrameError):
+        read_frame(_reader(header))
+
+
+def test_version_mismatch_raises():
+    frame = bytearray(encode_frame("ok"))
+    frame[2] = 99
+    with pytest.raises(FrameError):
+        read_frame(_reader(bytes(frame)))
+
+
+def test_truncated_body_is_eof():
+    assert read_frame(_reader(encode_frame("hello")[:-2])) is None
@@ -1,6 +1,6 @@
 import sys
 import pytest
-from unittest.mock import MagicMock, patch
+from unittest.mock import ANY, MagicMock, patch
 from pytron.apputils.native import NativeMixin
 
 
@@ -89,3 +89,84 @@ def test_system_notification_defaults(app):
     # Should use author/title from config as title
     args = mock_window.system_notification.call_args[0]
     assert args[0] == "Me"  # Author
+
+
+def test_call_stream_chunks_then_end():
+    from pytron.webview import CallStream
+
+    webview = MagicMock()
+    stream = CallStream(webview, "seq1", lambda data: data)
+
+    stream.emit_chunk("a")
+    stream.emit_chunk({"b": 1})
+    stream.end()
+    stream.end()
+
+    webview.native.emit_chunk.assert_any_call("seq1", '"a"')
+    webview.native.emit_chunk.assert_any_call("seq1", '{"b": 1}')
+    webview.native.end_stream.assert_called_once_with("seq1")
+    with pytest.raises(RuntimeError):
+        stream.emit_chunk("late")
+
+
+def test_protocol_handler_normalizes_response():
+    from pytron.webview import Webview
+
+    handler = Webview._protocol_handler(None, lambda m, u, h, b: (206, {"Content-Length": 5}, "hello"))
+    assert handler("GET", "media://localhost/a", {}, b"") == (206, {"Content-Length": "5"}, b"hello")
+
+    empty = Webview._protocol_handler(None, lambda m, u, h, b: (204, None, None))
+    assert empty("GET", "media://localhost/a", {}, b"") == (204, {}, b"")
+
+
+def test_bind_forwards_context_only_when_opted_in():
+    from pytron.webview import Webview
+
+    webview = Webview.__new__(Webview)
+    webview.native = MagicMock()
+    webview.logger = MagicMock()
+    webview._spammy_methods = set()
+    webview.serve_data = None
+    seen = []
+
+    webview.bind("plain", lambda x: seen.append(x), run_in_thread=False)
+    webview.native.bind.assert_called_with("plain", ANY, pass_context=False)
+    webview.native.bind.call_args[0][1]("1", "[5]", 0)
+
+    webview.bind("ctx", lambda x, context: seen.append(context), run_in_thread=False, pass_context=True)
+    webview.native.bind.assert_called_with("ctx", ANY, pass_context=True)
+    webview.native.bind.call_args[0][1]("2", "[5]", '{"window_id": "main", "method": "ctx"}')
+
+    assert seen == [5, {"window_id": "main", "method": "ctx"}]
+
+
+def test_served_data_urls_carry_the_protocol_token():
+    from pytron.serializer import pytron_serialize
+    from pytron.webview import Webview
+
+    webview = Webview.__new__(Webview)
+    webview.native = MagicMock()
+    webview.scheme = "pytron"
+    webview._served_data = {}
+
+    webview.native.protocol_token.return_value = "t0k"
+    url = pytron_serialize(b"\x00\x01", vap_provider=webview.serve_data)
+    key = url.split("/")[-1].split("?")[0]
+    assert url == f"https://pytron.localhost/{key}?pytron_token=t0k"
+    assert webview._served_data[key] == (b"\x00\x01", "application/octet-stream")
+
+    webview.native.protocol_token.return_value = None
+    assert webview.serve_data("k", b"", "text/plain") == "https://pytron.localhost/k"
+
+
+def test_custom_scheme_urls_are_left_alone():
+    from pytron.serializer import pytron_serialize
+    from pytron.webview import Webview
+
+    webview = Webview.__new__(Webview)
+    webview.scheme = "myapp"
+    assert webview._normalize_to_pytron("myapp://app/x") == "myapp://app/x"
+    config = {"url": "myapp://app/index.html"}
+    webview.normalize_path(config)
+    assert config["url"] == "myapp://app/index.html"
+    assert pytron_serialize(b"x", lambda *a: None, scheme="myapp").startswith("myapp://gen_bin_")
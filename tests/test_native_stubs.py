@@ -0,0 +1,27 @@
+import os
+import sys
+
+sys.path.append(
+    os.path.abspath(
+        os.path.join(os.path.dirname(__file__), "..", "pytron", "engines", "native")
+    )
+)
+
+import gen_stubs
+
+
+def test_native_stub_is_up_to_date():
+    with open(gen_stubs.DEFAULT_OUT, encoding="utf-8") as fh:
+        committed = fh.read()
+    assert committed == gen_stubs.generate(), (
+        "pytron_native.pyi is stale; run pytron/engines/native/gen_stubs.py"
+    )
+
+
+def test_stub_keeps_optional_signature_defaults():
+    stub = gen_stubs.generate()
+    assert (
+        "def dialog_open_file(self, title: str, dir: str | None = None, filters: str | None = None) -> str | None"
+        in stub
+    )
+    assert "class ChromeIPC:" in stub
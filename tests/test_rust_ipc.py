@@ -5,6 +5,8 @@ import threading
 import json
 import pytest
 
+from pytron.engines.chrome.framing import HEADER, encode_frame
+
 # Add dependencies to path
 sys.path.append(
     os.path.abspath(
@@ -50,21 +52,20 @@ def test_chrome_ipc_handshake():
 
             # Send Handshake
             msg = json.dumps({"type": "lifecycle", "payload": "app_ready"})
-            body = msg.encode("utf-8")
-            header = len(body).to_bytes(4, "little")
+            frame = encode_frame(msg)
             written = ctypes.c_ulong(0)
             ctypes.windll.kernel32.WriteFile(
-                h_out, header, 4, ctypes.byref(written), None
-            )
-            ctypes.windll.kernel32.WriteFile(
-                h_out, body, len(body), ctypes.byref(written), None
+                h_out, frame, len(frame), ctypes.byref(written), None
             )
 
             # Read something back
             buf = ctypes.create_string_buffer(1024)
             read = ctypes.c_ulong(0)
-            ctypes.windll.kernel32.ReadFile(h_in, buf, 4, ctypes.byref(read), None)
-            msg_len = int.from_bytes(buf[:4], "little")
+            ctypes.windll.kernel32.ReadFile(
+                h_in, buf, HEADER.size, ctypes.byref(read), None
+            )
+            magic, version, kind, msg_len = HEADER.unpack(buf[: HEADER.size])
+            assert magic == b"PT" and version == 1 and kind == 0
             ctypes.windll.kernel32.ReadFile(
                 h_in, buf, msg_len, ctypes.byref(read), None
             )
@@ -81,13 +82,11 @@ def test_chrome_ipc_handshake():
 
             # Send
             msg = json.dumps({"type": "lifecycle", "payload": "app_ready"})
-            body = msg.encode("utf-8")
-            header = len(body).to_bytes(4, "little")
-            sock.sendall(header + body)
+            sock.sendall(encode_frame(msg))
 
             # Read
-            header = sock.recv(4)
-            msg_len = int.from_bytes(header, "little")
+            magic, version, kind, msg_len = HEADER.unpack(sock.recv(HEADER.size))
+            assert magic == b"PT" and version == 1 and kind == 0
             body = sock.recv(msg_len)
             resp = json.loads(body.decode("utf-8"))
             assert resp["action"] == "test_action"
@@ -118,6 +117,31 @@ def test_chrome_ipc_handshake():
     t.join()
 
 
+@pytest.mark.skipif(not HAS_NATIVE, reason="pytron_native module not found")
+def test_chrome_ipc_uid_validation():
+    ipc = pytron_native.ChromeIPC()
+    for bad in ["../escape", "with space", "x" * 65]:
+        with pytest.raises(ValueError):
+            ipc.listen(bad)
+    with pytest.raises(ValueError):
+        pytron_native.is_pipe_available("a/b")
+
+    # No uid: a random one is picked and shows up in the returned path
+    path = ipc.listen()
+    assert "pytron-" in path
+    assert path != pytron_native.ChromeIPC().listen()
+
+
+@pytest.mark.skipif(not HAS_NATIVE, reason="pytron_native module not found")
+def test_chrome_ipc_probe_does_not_steal_peer():
+    ipc = pytron_native.ChromeIPC()
+    ipc.listen("test-probe")
+    # Probing a live channel must not be accepted as its peer
+    assert not pytron_native.is_pipe_available("test-probe")
+    with pytest.raises(Exception):
+        pytron_native.ChromeIPC().listen("test-probe")
+
+
 if __name__ == "__main__":
     # Manual run support
     try:
@@ -82,6 +82,21 @@ def test_create_window_exposed_functions(app, mock_webview):
     )
 
 
+def test_create_window_exposed_functions_with_context(app, mock_webview):
+    mock_func = MagicMock()
+    app._exposed_functions["whoami"] = {
+        "func": mock_func,
+        "secure": False,
+        "pass_context": True,
+    }
+
+    window = app.create_window()
+
+    window.bind.assert_called_with(
+        "whoami", mock_func, secure=False, run_in_thread=True, pass_context=True
+    )
+
+
 def test_broadcast(app, mock_webview):
     # Create two windows
     win1 = app.create_window()
@@ -124,6 +139,29 @@ def test_run_lifecycle(app, mock_webview):
     app.shortcut_manager.stop.assert_called_once()
 
 
+def test_run_dispatches_opened_files(app, mock_webview):
+    app._on_open_file_callback = MagicMock()
+    app.thread_pool = MagicMock()
+    with patch.object(sys, "pytron_open_files", ["doc.myext"], create=True):
+        app.run()
+
+    window = app.windows[0]
+    app.thread_pool.submit.assert_called_once_with(
+        app._on_open_file_callback, window, "doc.myext"
+    )
+    window.emit.assert_called_with("pytron:open-file", {"path": "doc.myext"})
+
+
+def test_exit_callbacks_run_once(app, mock_webview):
+    # The native graceful quit runs them before run() gets the chance
+    callback = MagicMock()
+    app._on_exit_callbacks.append(callback)
+    app._run_exit_callbacks()
+    app.run()
+
+    callback.assert_called_once()
+
+
 def test_run_cleanup_dev_storage(app, mock_webview):
     app.config["debug"] = True
     # Use actual PID to match the check in windows.py